@@ -0,0 +1,64 @@
+use anyhow::{bail, Context, Result};
+
+/// Same default as Redis' `proto-max-bulk-len`.
+pub const DEFAULT_MAX_REQUEST_SIZE: usize = 512 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+  pub max_request_size: usize,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Config {
+      max_request_size: DEFAULT_MAX_REQUEST_SIZE,
+    }
+  }
+}
+
+impl Config {
+  pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self> {
+    let mut config = Config::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+      match arg.as_str() {
+        "--max-request-size" => {
+          let Some(value) = args.next() else {
+            bail!("--max-request-size should be followed by a number of bytes");
+          };
+          config.max_request_size = value
+            .parse()
+            .with_context(|| format!("invalid --max-request-size value: {}", value))?;
+        }
+        _ => bail!("unknown argument: {}", arg),
+      }
+    }
+
+    Ok(config)
+  }
+}
+
+#[cfg(test)]
+mod tests_config {
+  use super::*;
+
+  fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+  }
+
+  #[test]
+  fn should_use_default_without_arguments() {
+    assert_eq!(Config::from_args(args(&[])).unwrap(), Config::default());
+  }
+
+  #[test]
+  fn should_parse_max_request_size() {
+    let config = Config::from_args(args(&["--max-request-size", "2048"])).unwrap();
+    assert_eq!(config.max_request_size, 2048);
+
+    assert!(Config::from_args(args(&["--max-request-size"])).is_err());
+    assert!(Config::from_args(args(&["--max-request-size", "big"])).is_err());
+    assert!(Config::from_args(args(&["--unknown"])).is_err());
+  }
+}
@@ -0,0 +1,108 @@
+use anyhow::{bail, Context, Result};
+use bytes::BytesMut;
+use tokio::{
+  io::{AsyncReadExt, AsyncWriteExt},
+  net::TcpStream,
+};
+
+use crate::resp_server::{frame_len, RedisError};
+
+const READ_BUFFER_CAPACITY: usize = 4 * 1024;
+
+/// Wraps a client stream and buffers incoming bytes until complete RESP frames are available.
+#[derive(Debug)]
+pub struct Connection {
+  stream: TcpStream,
+  buffer: BytesMut,
+  max_request_size: usize,
+}
+
+impl Connection {
+  pub fn new(stream: TcpStream, max_request_size: usize) -> Self {
+    Connection {
+      stream,
+      buffer: BytesMut::with_capacity(READ_BUFFER_CAPACITY),
+      max_request_size,
+    }
+  }
+
+  /// Reads from the stream until at least one complete frame is buffered and returns every
+  /// complete frame at once, so pipelined commands are handled as a single batch.
+  /// Bytes of a trailing partial frame are kept for the next call.
+  /// `None` means the peer closed the connection between two frames.
+  pub async fn read_frames(&mut self) -> Result<Option<BytesMut>> {
+    loop {
+      let frames_len = self.complete_frames_len()?;
+      if frames_len > 0 {
+        return Ok(Some(self.buffer.split_to(frames_len)));
+      }
+
+      if self.buffer.len() > self.max_request_size {
+        bail!(RedisError::Protocol(format!(
+          "request exceeds the maximum size of {} bytes",
+          self.max_request_size
+        )));
+      }
+
+      let n = self
+        .stream
+        .read_buf(&mut self.buffer)
+        .await
+        .context("failed to read from stream")?;
+      if n == 0 {
+        if self.buffer.is_empty() {
+          return Ok(None);
+        }
+        bail!("connection closed in the middle of a request");
+      }
+      println!("read {} bytes", n);
+    }
+  }
+
+  /// Buffers whatever the peer sends next without parsing it, which lets a blocked client notice
+  /// a disconnect. Returns `false` once the peer closed the connection.
+  pub async fn read_more(&mut self) -> Result<bool> {
+    if self.buffer.len() > self.max_request_size {
+      bail!(RedisError::Protocol(format!(
+        "request exceeds the maximum size of {} bytes",
+        self.max_request_size
+      )));
+    }
+    let n = self
+      .stream
+      .read_buf(&mut self.buffer)
+      .await
+      .context("failed to read from stream")?;
+    Ok(n > 0)
+  }
+
+  pub async fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+    self
+      .stream
+      .write_all(bytes)
+      .await
+      .context("failed to write to stream")?;
+    self.stream.flush().await.context("failed to flush stream")
+  }
+
+  fn complete_frames_len(&self) -> Result<usize> {
+    let mut total = 0;
+    loop {
+      let n = match frame_len(&self.buffer[total..]) {
+        Ok(Some(n)) => n,
+        Ok(None) => break,
+        // serve the frames before a malformed one first; the error surfaces on the next call
+        Err(_) if total > 0 => break,
+        Err(err) => return Err(err),
+      };
+      if n > self.max_request_size {
+        bail!(RedisError::Protocol(format!(
+          "request exceeds the maximum size of {} bytes",
+          self.max_request_size
+        )));
+      }
+      total += n;
+    }
+    Ok(total)
+  }
+}
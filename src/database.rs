@@ -1,72 +1,328 @@
-use anyhow::{Context, Result};
+mod active_expire;
+mod blocking;
+mod clock;
+mod consumer_group;
+mod hash;
+mod lazy_free;
+mod set;
+mod skiplist;
+mod sorted_set;
+mod stream;
+mod string;
+mod value;
+
+use anyhow::{bail, Result};
+use bytes::Bytes;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
-pub type ExpireTime = u128;
+pub use active_expire::{active_expire_cycle, run_active_expire_cycle};
+pub use blocking::ServeFn;
+pub use clock::{Clock, ManualClock, MonotonicClock};
+pub use consumer_group::{Consumer, ConsumerGroup, PendingEntry};
+pub use hash::Hash;
+pub use set::SetValue;
+pub use sorted_set::{LexBound, ScoreBound, SortedSet};
+pub use stream::{Stream, StreamFields, StreamId, TrimStrategy, STREAM_NODE_MAX_ENTRIES};
+pub use string::StringValue;
+pub use value::{Value, ValueKind};
 
-trait Expire {
-  fn expire_time(&self) -> Option<ExpireTime>;
-}
+use crate::resp_server::RedisError;
+use active_expire::{ExpireIndex, Rng};
+use blocking::BlockedClients;
+use lazy_free::{free_effort, free_in_background, LAZYFREE_THRESHOLD};
+
+pub type ExpireTime = u128;
 
 #[derive(Debug, Clone)]
 pub struct Data {
-  pub value: String,
+  pub value: Value,
   pub expire_time: Option<ExpireTime>,
 }
 
-impl Expire for Data {
-  fn expire_time(&self) -> Option<ExpireTime> {
-    self.expire_time
+impl Data {
+  /// The name `TYPE` reports for this value.
+  pub fn type_name(&self) -> &'static str {
+    self.value.type_name()
   }
 }
 
-impl Expire for &Data {
-  fn expire_time(&self) -> Option<ExpireTime> {
-    self.expire_time
-  }
+/// Counters about expired keys, reported by `INFO`.
+#[derive(Debug, Default, Clone)]
+pub struct ExpireStats {
+  /// keys deleted because their expire time passed, whether on access or by the active cycle
+  pub expired_keys: u64,
+  /// hash fields deleted because their expire time passed
+  pub expired_subkeys: u64,
+  /// running estimate of the percentage of keys with an expire time that are already expired
+  pub expired_stale_perc: f64,
+  /// wall-clock time spent in the active expire cycle, which also counts waiting for the lock
+  /// and being descheduled, so it is not reported as CPU time
+  pub expire_cycle_time: Duration,
+  /// running estimate of the remaining time to live of keys with an expire time, in milliseconds
+  pub avg_ttl: u128,
 }
 
 #[derive(Debug)]
 pub struct Database {
-  db: HashMap<String, Data>,
+  db: HashMap<Bytes, Data>,
+  /// keys that have an expire time, sampled by the active expire cycle
+  expires: ExpireIndex,
+  /// hashes that have fields with an expire time, sampled by the active expire cycle as well
+  hash_field_expires: ExpireIndex,
+  stats: ExpireStats,
+  rng: Rng,
+  clock: Arc<dyn Clock>,
+  /// turned off by `DEBUG SET-ACTIVE-EXPIRE 0` so that tests can observe lazy expiry alone
+  active_expire_enabled: bool,
+  blocked: BlockedClients,
+}
+
+impl Default for Database {
+  fn default() -> Self {
+    Database::with_clock(Arc::new(MonotonicClock::new()))
+  }
 }
 
 impl Database {
   pub fn new() -> Self {
-    Database { db: HashMap::new() }
+    Database::default()
   }
 
-  pub fn get(&self, key: &str) -> Option<&Data> {
-    self.db.get(key).and_then(|data| self.handle_expiry(data))
+  pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+    Database {
+      db: HashMap::new(),
+      expires: ExpireIndex::default(),
+      hash_field_expires: ExpireIndex::default(),
+      stats: ExpireStats::default(),
+      rng: Rng::default(),
+      clock,
+      active_expire_enabled: true,
+      blocked: BlockedClients::default(),
+    }
   }
 
-  pub fn set(&mut self, key: &str, val: &Data) -> Option<Data> {
-    self
-      .db
-      .insert(key.to_owned(), val.clone())
-      .and_then(|data| self.handle_expiry(data))
-  }
-
-  fn handle_expiry<T: Expire>(&self, data: T) -> Option<T> {
-    match data.expire_time() {
-      None => Some(data),
-      Some(expire_time) => match is_expired(expire_time) {
-        Ok(expired) if !expired => Some(data),
-        Ok(_) => None,
-        Err(e) => {
-          eprintln!("{}", e);
-          None
-        }
+  /// The current time according to the database clock.
+  pub fn now(&self) -> ExpireTime {
+    self.clock.now()
+  }
+
+  /// Returns the data of `key`, deleting it first if it has expired.
+  pub fn get(&mut self, key: &[u8]) -> Option<&Data> {
+    self.expire_if_needed(key);
+    self.db.get(key)
+  }
+
+  /// Returns the value of `key` as the type a command expects, or WRONGTYPE if the key holds
+  /// another kind of value.
+  pub fn get_value<T: ValueKind>(&mut self, key: &[u8]) -> Result<Option<&T>> {
+    match self.get(key) {
+      None => Ok(None),
+      Some(data) => match T::from_value(&data.value) {
+        Some(value) => Ok(Some(value)),
+        None => bail!(RedisError::WrongType),
+      },
+    }
+  }
+
+  /// Like `get_value` for several keys at once, for commands that combine values.
+  pub fn get_values<T: ValueKind>(&mut self, keys: &[Bytes]) -> Result<Vec<Option<&T>>> {
+    for key in keys {
+      self.expire_if_needed(key);
+    }
+    keys
+      .iter()
+      .map(|key| match self.db.get(key) {
+        None => Ok(None),
+        Some(data) => match T::from_value(&data.value) {
+          Some(value) => Ok(Some(value)),
+          None => bail!(RedisError::WrongType),
+        },
+      })
+      .collect()
+  }
+
+  /// Like `get_value`, but for a command that modifies the value in place.
+  pub fn get_value_mut<T: ValueKind>(&mut self, key: &[u8]) -> Result<Option<&mut T>> {
+    self.expire_if_needed(key);
+    match self.db.get_mut(key) {
+      None => Ok(None),
+      Some(data) => match T::from_value_mut(&mut data.value) {
+        Some(value) => Ok(Some(value)),
+        None => bail!(RedisError::WrongType),
       },
     }
   }
+
+  /// Like `get_value_mut`, but creates an empty value first if `key` doesn't exist.
+  pub fn get_value_or_default<T: ValueKind>(&mut self, key: &[u8]) -> Result<&mut T> {
+    self.expire_if_needed(key);
+    let data = self
+      .db
+      .entry(Bytes::copy_from_slice(key))
+      .or_insert_with(|| Data {
+        value: T::default().into(),
+        expire_time: None,
+      });
+    match T::from_value_mut(&mut data.value) {
+      Some(value) => Ok(value),
+      None => bail!(RedisError::WrongType),
+    }
+  }
+
+  /// Deletes `key` if it holds a collection without elements, which Redis never keeps around.
+  pub fn remove_if_empty(&mut self, key: &[u8]) {
+    if self.db.get(key).is_some_and(|data| data.value.is_empty()) {
+      self.remove(key);
+    }
+  }
+
+  pub fn set(&mut self, key: &[u8], val: &Data) -> Option<Data> {
+    self.expire_if_needed(key);
+    let key = Bytes::copy_from_slice(key);
+    match val.expire_time {
+      Some(_) => self.expires.insert(key.clone()),
+      None => self.expires.remove(&key),
+    }
+    match &val.value {
+      Value::Hash(hash) if hash.has_expiring_fields() => {
+        self.hash_field_expires.insert(key.clone())
+      }
+      _ => self.hash_field_expires.remove(&key),
+    }
+    self.signal_key_as_ready(&key);
+    self.db.insert(key, val.clone())
+  }
+
+  pub fn remove(&mut self, key: &[u8]) -> Option<Data> {
+    self.expire_if_needed(key);
+    self.expires.remove(key);
+    self.hash_field_expires.remove(key);
+    self.db.remove(key)
+  }
+
+  /// Removes `key` like `remove`, but frees a large value in the background instead of in place.
+  pub fn unlink(&mut self, key: &[u8]) -> bool {
+    match self.remove(key) {
+      Some(data) => {
+        free(data);
+        true
+      }
+      None => false,
+    }
+  }
+
+  pub fn contains_key(&mut self, key: &[u8]) -> bool {
+    self.get(key).is_some()
+  }
+
+  /// Sets or clears the expire time of an existing key. Returns whether the key exists.
+  pub fn set_expire_time(&mut self, key: &[u8], expire_time: Option<ExpireTime>) -> bool {
+    self.expire_if_needed(key);
+    let Some(data) = self.db.get_mut(key) else {
+      return false;
+    };
+    data.expire_time = expire_time;
+    match expire_time {
+      Some(_) => self.expires.insert(Bytes::copy_from_slice(key)),
+      None => self.expires.remove(key),
+    }
+    true
+  }
+
+  /// Lets the active expire cycle find the hash at `key` after fields of it got an expire time.
+  pub fn track_hash_field_expires(&mut self, key: &[u8]) {
+    self.hash_field_expires.insert(Bytes::copy_from_slice(key));
+  }
+
+  /// Number of keys, including expired ones that have not been deleted yet.
+  pub fn len(&self) -> usize {
+    self.db.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.db.is_empty()
+  }
+
+  /// Number of keys that have an expire time.
+  pub fn expires_len(&self) -> usize {
+    self.expires.len()
+  }
+
+  /// Number of hashes that have fields with an expire time.
+  pub fn hash_field_expires_len(&self) -> usize {
+    self.hash_field_expires.len()
+  }
+
+  pub fn stats(&self) -> &ExpireStats {
+    &self.stats
+  }
+
+  pub fn set_active_expire(&mut self, enabled: bool) {
+    self.active_expire_enabled = enabled;
+  }
+
+  /// A pseudo-random number in `0..bound` for commands like `HRANDFIELD`; `bound` must not be zero.
+  pub fn random_below(&mut self, bound: usize) -> usize {
+    self.rng.below(bound)
+  }
+
+  fn expire_if_needed(&mut self, key: &[u8]) {
+    match self.db.get(key).and_then(|data| data.expire_time) {
+      Some(expire_time) if self.now() > expire_time => self.delete_expired(key),
+      _ => {
+        self.expire_hash_fields(key);
+      }
+    }
+  }
+
+  /// Deletes the expired fields of the hash at `key`, and the hash itself once no field is left.
+  /// Returns how many fields were deleted.
+  fn expire_hash_fields(&mut self, key: &[u8]) -> usize {
+    let has_expiring_fields = matches!(
+      self.db.get(key),
+      Some(Data { value: Value::Hash(hash), .. }) if hash.has_expiring_fields()
+    );
+    if !has_expiring_fields {
+      self.hash_field_expires.remove(key);
+      return 0;
+    }
+    let now = self.now();
+    let Some(Data {
+      value: Value::Hash(hash),
+      ..
+    }) = self.db.get_mut(key)
+    else {
+      unreachable!("checked above");
+    };
+
+    let expired = hash.remove_expired(now);
+    self.stats.expired_subkeys += expired as u64;
+    // the hash itself did not expire, so it doesn't count as an expired key
+    if hash.is_empty() {
+      self.db.remove(key);
+      self.expires.remove(key);
+      self.hash_field_expires.remove(key);
+    } else if !hash.has_expiring_fields() {
+      self.hash_field_expires.remove(key);
+    }
+    expired
+  }
+
+  fn delete_expired(&mut self, key: &[u8]) {
+    self.expires.remove(key);
+    self.hash_field_expires.remove(key);
+    if let Some(data) = self.db.remove(key) {
+      self.stats.expired_keys += 1;
+      free(data);
+    }
+  }
 }
 
-fn is_expired(expire_time: u128) -> Result<bool> {
-  use std::time::SystemTime;
-  // duration since UNIX EPOCH(1970-01-01 00:00:00 UTC)
-  let now = SystemTime::now()
-    .duration_since(SystemTime::UNIX_EPOCH)
-    .context("SystemTime before unix epoch")?
-    .as_millis();
-  Ok(now > expire_time)
+/// Drops `data`, in the background if freeing it would take long.
+fn free(data: Data) {
+  if free_effort(&data) > LAZYFREE_THRESHOLD {
+    free_in_background(data);
+  }
 }
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use bytes::Bytes;
+
+use super::Database;
+
+/// How often the active expire cycle runs, like Redis' default `hz` of 10.
+const ACTIVE_EXPIRE_CYCLE_PERIOD: Duration = Duration::from_millis(100);
+/// Keys with an expire time sampled by each step of the cycle.
+const KEYS_PER_STEP: usize = 20;
+/// The cycle keeps sampling while more than this percentage of the sampled keys were expired.
+const ACCEPTABLE_STALE_PERCENT: usize = 10;
+/// Upper bound on the time spent in one cycle, a quarter of the period like Redis.
+const CYCLE_TIME_LIMIT: Duration = Duration::from_millis(25);
+
+/// Keys that have an expire time, stored so that they can be sampled at random.
+#[derive(Debug, Default)]
+pub struct ExpireIndex {
+  keys: Vec<Bytes>,
+  positions: HashMap<Bytes, usize>,
+}
+
+impl ExpireIndex {
+  pub fn insert(&mut self, key: Bytes) {
+    if !self.positions.contains_key(&key) {
+      self.positions.insert(key.clone(), self.keys.len());
+      self.keys.push(key);
+    }
+  }
+
+  pub fn remove(&mut self, key: &[u8]) {
+    let Some(position) = self.positions.remove(key) else {
+      return;
+    };
+    self.keys.swap_remove(position);
+    if let Some(moved_key) = self.keys.get(position) {
+      self.positions.insert(moved_key.clone(), position);
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.keys.len()
+  }
+}
+
+/// xorshift64, good enough to pick keys to sample.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Default for Rng {
+  fn default() -> Self {
+    let seed = SystemTime::now()
+      .duration_since(SystemTime::UNIX_EPOCH)
+      .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+    // the state must never be zero
+    Rng(seed | 1)
+  }
+}
+
+impl Rng {
+  /// A number in `0..bound`; `bound` must not be zero.
+  pub(super) fn below(&mut self, bound: usize) -> usize {
+    let Rng(state) = self;
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    (*state % bound as u64) as usize
+  }
+}
+
+/// Runs the active expire cycle forever, once every `ACTIVE_EXPIRE_CYCLE_PERIOD`.
+pub async fn run_active_expire_cycle(db: Arc<Mutex<Database>>) {
+  let mut interval = tokio::time::interval(ACTIVE_EXPIRE_CYCLE_PERIOD);
+  loop {
+    interval.tick().await;
+    active_expire_cycle(&db);
+  }
+}
+
+/// Deletes expired keys that nobody accesses, like Redis' adaptive active expire cycle.
+/// Keys are sampled a few at a time, and sampling continues while many of them turn out expired
+/// and the time limit is not reached. The lock is released between steps so that clients are
+/// not kept waiting for the whole cycle.
+pub fn active_expire_cycle(db: &Mutex<Database>) {
+  let start = Instant::now();
+  let mut total_sampled = 0;
+  let mut total_expired = 0;
+  loop {
+    let mut db = db.lock().unwrap();
+    if !db.active_expire_enabled {
+      return;
+    }
+    let (sampled, expired) = db.expire_step();
+    total_sampled += sampled;
+    total_expired += expired;
+
+    let elapsed = start.elapsed();
+    let stale_enough = expired * 100 <= sampled * ACCEPTABLE_STALE_PERCENT;
+    if sampled == 0 || stale_enough || elapsed >= CYCLE_TIME_LIMIT {
+      let stats = &mut db.stats;
+      stats.expire_cycle_time += elapsed;
+      if total_sampled > 0 {
+        let stale_perc = total_expired as f64 / total_sampled as f64 * 100.0;
+        stats.expired_stale_perc = stale_perc * 0.05 + stats.expired_stale_perc * 0.95;
+      }
+      return;
+    }
+  }
+}
+
+impl Database {
+  /// Samples up to `KEYS_PER_STEP` keys with an expire time and deletes the expired ones, then
+  /// does the same for hashes with expiring fields.
+  /// Returns how many keys were sampled and how many of them were expired or had expired fields.
+  fn expire_step(&mut self) -> (usize, usize) {
+    let now = self.now();
+    let mut sampled = 0;
+    let mut expired = 0;
+    let mut ttl_sum = 0;
+    let mut ttl_samples = 0;
+
+    while sampled < KEYS_PER_STEP && self.expires.len() > 0 {
+      sampled += 1;
+      let position = self.rng.below(self.expires.len());
+      let key = self.expires.keys[position].clone();
+      match self.db.get(&key).and_then(|data| data.expire_time) {
+        // the expire time was cleared without going through the index
+        None => self.expires.remove(&key),
+        Some(expire_time) if now > expire_time => {
+          self.delete_expired(&key);
+          expired += 1;
+        }
+        Some(expire_time) => {
+          ttl_sum += expire_time - now;
+          ttl_samples += 1;
+        }
+      }
+    }
+
+    let mut hashes_sampled = 0;
+    while hashes_sampled < KEYS_PER_STEP && self.hash_field_expires.len() > 0 {
+      hashes_sampled += 1;
+      let position = self.rng.below(self.hash_field_expires.len());
+      let key = self.hash_field_expires.keys[position].clone();
+      if self.expire_hash_fields(&key) > 0 {
+        expired += 1;
+      }
+    }
+    sampled += hashes_sampled;
+
+    if let Some(avg_ttl) = ttl_sum.checked_div(ttl_samples) {
+      // weigh the new estimate lightly, like Redis does
+      self.stats.avg_ttl = if self.stats.avg_ttl == 0 {
+        avg_ttl
+      } else {
+        self.stats.avg_ttl / 50 * 49 + avg_ttl / 50
+      };
+    }
+    (sampled, expired)
+  }
+}
+
+#[cfg(test)]
+mod tests_active_expire {
+  use super::*;
+  use crate::database::{Data, ManualClock, Value};
+
+  fn data(expire_time: Option<u128>) -> Data {
+    Data {
+      value: Value::from(Bytes::from("value")),
+      expire_time,
+    }
+  }
+
+  #[test]
+  fn should_keep_index_consistent_after_removal() {
+    let mut index = ExpireIndex::default();
+    for key in ["a", "b", "c"] {
+      index.insert(Bytes::from(key));
+    }
+    index.insert(Bytes::from("a"));
+    index.remove(b"a");
+    index.remove(b"missing");
+
+    assert_eq!(index.len(), 2);
+    for (position, key) in index.keys.iter().enumerate() {
+      assert_eq!(index.positions[key], position);
+    }
+  }
+
+  #[test]
+  fn should_delete_expired_keys_without_access() {
+    let clock = Arc::new(ManualClock::new(1_000));
+    let db = Mutex::new(Database::with_clock(clock.clone()));
+    {
+      let mut db = db.lock().unwrap();
+      for i in 0..100 {
+        db.set(format!("expiring{}", i).as_bytes(), &data(Some(1_100)));
+      }
+      db.set(b"alive", &data(Some(10_000)));
+      db.set(b"persistent", &data(None));
+    }
+
+    active_expire_cycle(&db);
+    assert_eq!(db.lock().unwrap().len(), 102);
+
+    clock.advance(Duration::from_millis(200));
+    active_expire_cycle(&db);
+
+    let db = db.lock().unwrap();
+    assert_eq!(db.len(), 2);
+    assert_eq!(db.expires_len(), 1);
+    assert_eq!(db.stats().expired_keys, 100);
+  }
+
+  #[test]
+  fn should_skip_cycle_when_active_expire_is_disabled() {
+    let clock = Arc::new(ManualClock::new(1_000));
+    let db = Mutex::new(Database::with_clock(clock));
+    db.lock().unwrap().set(b"expired", &data(Some(1)));
+    db.lock().unwrap().set_active_expire(false);
+
+    active_expire_cycle(&db);
+    assert_eq!(db.lock().unwrap().len(), 1);
+
+    db.lock().unwrap().set_active_expire(true);
+    active_expire_cycle(&db);
+    assert_eq!(db.lock().unwrap().len(), 0);
+  }
+}
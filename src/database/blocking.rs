@@ -0,0 +1,184 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bytes::Bytes;
+use tokio::sync::oneshot;
+
+use super::Database;
+use crate::resp_server::Reply;
+
+/// Tries to serve a blocked client once one of its keys changed.
+/// `None` means the client has to keep waiting.
+pub type ServeFn = Box<dyn FnMut(&mut Database) -> Option<Reply> + Send>;
+
+struct Waiter {
+  keys: Vec<Bytes>,
+  serve: ServeFn,
+  sender: oneshot::Sender<Reply>,
+}
+
+/// Clients blocked on keys, served first come, first served on each key.
+#[derive(Default)]
+pub struct BlockedClients {
+  waiters: HashMap<u64, Waiter>,
+  queues: HashMap<Bytes, VecDeque<u64>>,
+  /// keys that changed while someone was waiting on them, in the order they changed
+  ready_keys: VecDeque<Bytes>,
+  ready_set: HashSet<Bytes>,
+  next_id: u64,
+}
+
+impl std::fmt::Debug for BlockedClients {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("BlockedClients")
+      .field("waiters", &self.waiters.len())
+      .field("ready_keys", &self.ready_keys)
+      .finish()
+  }
+}
+
+impl BlockedClients {
+  fn remove(&mut self, id: u64, keys: &[Bytes]) {
+    for key in keys {
+      if let Some(queue) = self.queues.get_mut(key) {
+        queue.retain(|waiter_id| *waiter_id != id);
+        if queue.is_empty() {
+          self.queues.remove(key);
+        }
+      }
+    }
+  }
+}
+
+impl Database {
+  /// Parks a client on `keys`. The receiver gets the reply once `serve` succeeds after one of the
+  /// keys changed. Returns an id to pass to `unblock` when the client stops waiting.
+  pub fn block(&mut self, keys: Vec<Bytes>, serve: ServeFn) -> (u64, oneshot::Receiver<Reply>) {
+    let blocked = &mut self.blocked;
+    let id = blocked.next_id;
+    blocked.next_id += 1;
+    for key in &keys {
+      blocked.queues.entry(key.clone()).or_default().push_back(id);
+    }
+    let (sender, receiver) = oneshot::channel();
+    blocked.waiters.insert(
+      id,
+      Waiter {
+        keys,
+        serve,
+        sender,
+      },
+    );
+    (id, receiver)
+  }
+
+  /// Stops waiting, after a timeout or when the client went away.
+  pub fn unblock(&mut self, id: u64) {
+    if let Some(waiter) = self.blocked.waiters.remove(&id) {
+      self.blocked.remove(id, &waiter.keys);
+    }
+  }
+
+  /// Records that `key` may now serve clients blocked on it.
+  pub fn signal_key_as_ready(&mut self, key: &[u8]) {
+    let blocked = &mut self.blocked;
+    if blocked.queues.contains_key(key) && !blocked.ready_set.contains(key) {
+      let key = Bytes::copy_from_slice(key);
+      blocked.ready_set.insert(key.clone());
+      blocked.ready_keys.push_back(key);
+    }
+  }
+
+  /// Serves clients blocked on the keys signaled as ready, in the order they blocked.
+  /// Serving a client may make more keys ready, which are served in turn.
+  pub fn serve_blocked_clients(&mut self) {
+    while let Some(key) = self.blocked.ready_keys.pop_front() {
+      self.blocked.ready_set.remove(&key);
+      let queue = self.blocked.queues.get(&key).cloned().unwrap_or_default();
+      for id in queue {
+        let Some(mut waiter) = self.blocked.waiters.remove(&id) else {
+          continue;
+        };
+        // don't take anything for a client that can't receive it anymore
+        if waiter.sender.is_closed() {
+          self.blocked.remove(id, &waiter.keys);
+          continue;
+        }
+        match (waiter.serve)(self) {
+          Some(reply) => {
+            self.blocked.remove(id, &waiter.keys);
+            let _ = waiter.sender.send(reply);
+          }
+          None => {
+            // keep going: a stream reader waiting for later entries doesn't stop readers of the
+            // entries that are already there
+            self.blocked.waiters.insert(id, waiter);
+          }
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests_blocking {
+  use super::*;
+  use crate::database::{Data, StringValue};
+
+  fn string(value: &[u8]) -> Data {
+    Data {
+      value: Bytes::copy_from_slice(value).into(),
+      expire_time: None,
+    }
+  }
+
+  /// Serves a client by popping the first byte of the string at `key`.
+  fn pop_byte(key: &'static [u8]) -> ServeFn {
+    Box::new(move |db: &mut Database| {
+      let value = db.get_value::<StringValue>(key).ok()??.to_bytes();
+      let (first, rest) = value.split_first()?;
+      db.set(key, &string(rest));
+      Some(Reply::BulkString(Bytes::copy_from_slice(&[*first])))
+    })
+  }
+
+  #[test]
+  fn should_serve_clients_in_the_order_they_blocked() {
+    let mut db = Database::new();
+    let (_, mut first) = db.block(vec![Bytes::from("k")], pop_byte(b"k"));
+    let (_, mut second) = db.block(vec![Bytes::from("k")], pop_byte(b"k"));
+    let (_, mut third) = db.block(vec![Bytes::from("k")], pop_byte(b"k"));
+
+    db.set(b"k", &string(b"ab"));
+    db.serve_blocked_clients();
+
+    assert_eq!(
+      first.try_recv().unwrap(),
+      Reply::BulkString(Bytes::from("a"))
+    );
+    assert_eq!(
+      second.try_recv().unwrap(),
+      Reply::BulkString(Bytes::from("b"))
+    );
+    assert!(third.try_recv().is_err());
+  }
+
+  #[test]
+  fn should_skip_clients_that_stopped_waiting() {
+    let mut db = Database::new();
+    // the receiver is dropped right away, like when the client disconnects
+    db.block(vec![Bytes::from("k")], pop_byte(b"k"));
+    let (timed_out, _timed_out_receiver) = db.block(vec![Bytes::from("k")], pop_byte(b"k"));
+    let (_, mut waiting) = db.block(vec![Bytes::from("k"), Bytes::from("j")], pop_byte(b"k"));
+    db.unblock(timed_out);
+
+    db.set(b"k", &string(b"a"));
+    db.serve_blocked_clients();
+
+    assert_eq!(
+      waiting.try_recv().unwrap(),
+      Reply::BulkString(Bytes::from("a"))
+    );
+    assert!(db.blocked.waiters.is_empty());
+    assert!(db.blocked.queues.is_empty());
+  }
+}
@@ -0,0 +1,96 @@
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+use super::ExpireTime;
+
+/// Source of the current time for everything expire related.
+pub trait Clock: Debug + Send + Sync {
+  /// Milliseconds since the Unix epoch, the unit of `ExpireTime`.
+  fn now(&self) -> ExpireTime;
+}
+
+/// Reads the wall clock once and then advances with a monotonic clock, so that wall clock jumps
+/// (NTP corrections, manual changes) can't expire keys early or bring expired keys back.
+#[derive(Debug)]
+pub struct MonotonicClock {
+  start_time: ExpireTime,
+  start_instant: Instant,
+}
+
+impl MonotonicClock {
+  pub fn new() -> Self {
+    // duration since UNIX EPOCH(1970-01-01 00:00:00 UTC)
+    let start_time = SystemTime::now()
+      .duration_since(SystemTime::UNIX_EPOCH)
+      .map_or(0, |elapsed| elapsed.as_millis());
+    MonotonicClock {
+      start_time,
+      start_instant: Instant::now(),
+    }
+  }
+}
+
+impl Default for MonotonicClock {
+  fn default() -> Self {
+    MonotonicClock::new()
+  }
+}
+
+impl Clock for MonotonicClock {
+  fn now(&self) -> ExpireTime {
+    self.start_time + self.start_instant.elapsed().as_millis()
+  }
+}
+
+/// A clock that only moves when told to, for tests.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+  now: AtomicU64,
+}
+
+impl ManualClock {
+  pub fn new(now: ExpireTime) -> Self {
+    ManualClock {
+      now: AtomicU64::new(now as u64),
+    }
+  }
+
+  pub fn set(&self, now: ExpireTime) {
+    self.now.store(now as u64, Ordering::SeqCst);
+  }
+
+  pub fn advance(&self, duration: Duration) {
+    self
+      .now
+      .fetch_add(duration.as_millis() as u64, Ordering::SeqCst);
+  }
+}
+
+impl Clock for ManualClock {
+  fn now(&self) -> ExpireTime {
+    self.now.load(Ordering::SeqCst) as ExpireTime
+  }
+}
+
+#[cfg(test)]
+mod tests_clock {
+  use super::*;
+
+  #[test]
+  fn should_advance_manual_clock_only_when_told() {
+    let clock = ManualClock::new(1_000);
+    assert_eq!(clock.now(), 1_000);
+    clock.advance(Duration::from_secs(2));
+    assert_eq!(clock.now(), 3_000);
+    clock.set(10);
+    assert_eq!(clock.now(), 10);
+  }
+
+  #[test]
+  fn should_never_go_backwards() {
+    let clock = MonotonicClock::new();
+    let before = clock.now();
+    assert!(clock.now() >= before);
+  }
+}
@@ -0,0 +1,232 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use bytes::Bytes;
+
+use super::{Stream, StreamFields, StreamId};
+
+/// A delivered entry that its consumer did not acknowledge yet.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct PendingEntry {
+  pub consumer: Bytes,
+  /// when the entry was last delivered, in milliseconds
+  pub delivery_time: u128,
+  pub delivery_count: u64,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Consumer {
+  /// when the consumer last tried to read or claim, in milliseconds
+  pub seen_time: u128,
+  /// when the consumer last actually got entries, if ever
+  pub active_time: Option<u128>,
+  pending: BTreeSet<StreamId>,
+}
+
+impl Consumer {
+  pub fn pending_len(&self) -> usize {
+    self.pending.len()
+  }
+
+  /// IDs of the entries delivered to this consumer and not acknowledged yet, in order.
+  pub fn pending(&self) -> impl Iterator<Item = &StreamId> {
+    self.pending.iter()
+  }
+}
+
+/// A consumer group of a stream: the last entry it delivered, and the entries its consumers did
+/// not acknowledge yet, known as the pending entries list.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct ConsumerGroup {
+  pub last_delivered_id: StreamId,
+  /// how many entries of the stream the group read, when it is known
+  pub entries_read: Option<u64>,
+  pending: BTreeMap<StreamId, PendingEntry>,
+  consumers: BTreeMap<Bytes, Consumer>,
+}
+
+impl ConsumerGroup {
+  pub fn new(last_delivered_id: StreamId, entries_read: Option<u64>) -> Self {
+    ConsumerGroup {
+      last_delivered_id,
+      entries_read,
+      ..ConsumerGroup::default()
+    }
+  }
+
+  pub fn consumers(&self) -> impl Iterator<Item = (&Bytes, &Consumer)> {
+    self.consumers.iter()
+  }
+
+  pub fn consumer(&self, name: &[u8]) -> Option<&Consumer> {
+    self.consumers.get(name)
+  }
+
+  /// Adds a consumer without pending entries. Returns whether it did not exist yet.
+  pub fn create_consumer(&mut self, name: &Bytes, now: u128) -> bool {
+    if self.consumers.contains_key(name) {
+      return false;
+    }
+    let consumer = Consumer {
+      seen_time: now,
+      ..Consumer::default()
+    };
+    self.consumers.insert(name.clone(), consumer);
+    true
+  }
+
+  /// Records that the consumer `name` showed up, creating it if needed.
+  pub fn touch_consumer(&mut self, name: &Bytes, now: u128) -> &mut Consumer {
+    let consumer = self.consumers.entry(name.clone()).or_default();
+    consumer.seen_time = now;
+    consumer
+  }
+
+  /// Deletes a consumer and its pending entries. Returns how many were pending, or `None` if
+  /// there was no such consumer.
+  pub fn remove_consumer(&mut self, name: &[u8]) -> Option<usize> {
+    let consumer = self.consumers.remove(name)?;
+    for id in &consumer.pending {
+      self.pending.remove(id);
+    }
+    Some(consumer.pending.len())
+  }
+
+  pub fn pending_len(&self) -> usize {
+    self.pending.len()
+  }
+
+  pub fn pending_entry(&self, id: &StreamId) -> Option<&PendingEntry> {
+    self.pending.get(id)
+  }
+
+  /// Pending entries with an ID from `start` to `end`, in order.
+  pub fn pending_range(
+    &self,
+    start: StreamId,
+    end: StreamId,
+  ) -> Box<dyn Iterator<Item = (&StreamId, &PendingEntry)> + '_> {
+    if start > end {
+      return Box::new(std::iter::empty());
+    }
+    Box::new(self.pending.range(start..=end))
+  }
+
+  /// Makes `id` pending for `consumer`, taking it from the consumer it was pending for if any.
+  pub fn assign(
+    &mut self,
+    id: StreamId,
+    consumer: &Bytes,
+    delivery_time: u128,
+    delivery_count: u64,
+  ) {
+    self.acknowledge(&id);
+    let owner = self.consumers.entry(consumer.clone()).or_default();
+    owner.pending.insert(id);
+    let pending = PendingEntry {
+      consumer: consumer.clone(),
+      delivery_time,
+      delivery_count,
+    };
+    self.pending.insert(id, pending);
+  }
+
+  /// Removes `id` from the pending entries. Returns whether it was pending.
+  pub fn acknowledge(&mut self, id: &StreamId) -> bool {
+    let Some(pending) = self.pending.remove(id) else {
+      return false;
+    };
+    if let Some(consumer) = self.consumers.get_mut(&pending.consumer) {
+      consumer.pending.remove(id);
+    }
+    true
+  }
+}
+
+impl Stream {
+  /// Delivers up to `count` entries the group `group` did not deliver yet to `consumer`, adding
+  /// them to the pending entries unless `noack`. Returns `None` if there is no such group.
+  pub fn read_group(
+    &mut self,
+    group: &[u8],
+    consumer: &Bytes,
+    count: usize,
+    noack: bool,
+    now: u128,
+  ) -> Option<Vec<(StreamId, StreamFields)>> {
+    let ConsumerGroup {
+      last_delivered_id,
+      entries_read,
+      ..
+    } = *self.group(group)?;
+    let entries = match last_delivered_id.next() {
+      Some(start) => self
+        .range(start..=StreamId::MAX, false)
+        .take(count)
+        .map(|(id, fields)| (*id, fields.clone()))
+        .collect::<Vec<_>>(),
+      None => vec![],
+    };
+    let entries_read = match (entries.last(), entries_read) {
+      (None, entries_read) => entries_read,
+      // no entry after the last delivered one was deleted, so none was skipped
+      (Some(_), Some(read)) if self.max_deleted_id() < last_delivered_id => {
+        Some(read + entries.len() as u64)
+      }
+      (Some((id, _)), _) => self.entries_read_at(*id),
+    };
+
+    let group = self.group_mut(group).expect("checked above");
+    let reader = group.touch_consumer(consumer, now);
+    let Some((last_id, _)) = entries.last() else {
+      return Some(entries);
+    };
+    reader.active_time = Some(now);
+    group.last_delivered_id = *last_id;
+    group.entries_read = entries_read;
+    if !noack {
+      for (id, _) in &entries {
+        group.assign(*id, consumer, now, 1);
+      }
+    }
+    Some(entries)
+  }
+
+  /// How many entries the group `group` has yet to deliver, or `None` when deleted entries make
+  /// that unknown.
+  pub fn group_lag(&self, group: &ConsumerGroup) -> Option<u64> {
+    if self.entries_added() == 0 {
+      return Some(0);
+    }
+    let entries_read = match group.entries_read {
+      Some(read) if self.max_deleted_id() < group.last_delivered_id => Some(read),
+      _ => self.entries_read_at(group.last_delivered_id),
+    };
+    entries_read.map(|read| self.entries_added().saturating_sub(read))
+  }
+}
+
+#[cfg(test)]
+mod tests_consumer_group {
+  use super::*;
+
+  fn id(ms: u64) -> StreamId {
+    StreamId { ms, seq: 0 }
+  }
+
+  #[test]
+  fn should_move_pending_entries_between_consumers() {
+    let (alice, bob) = (Bytes::from("alice"), Bytes::from("bob"));
+    let mut group = ConsumerGroup::default();
+    group.assign(id(1), &alice, 10, 1);
+    group.assign(id(2), &alice, 10, 1);
+    group.assign(id(1), &bob, 20, 2);
+
+    assert_eq!(group.consumer(b"alice").unwrap().pending_len(), 1);
+    assert_eq!(group.pending_entry(&id(1)).unwrap().consumer, bob);
+    assert!(group.acknowledge(&id(2)));
+    assert!(!group.acknowledge(&id(2)));
+    assert_eq!(group.remove_consumer(b"bob"), Some(1));
+    assert_eq!(group.pending_len(), 0);
+    assert_eq!(group.remove_consumer(b"bob"), None);
+  }
+}
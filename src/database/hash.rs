@@ -0,0 +1,144 @@
+use std::collections::hash_map::Iter;
+use std::collections::{BTreeSet, HashMap};
+
+use bytes::Bytes;
+
+use super::ExpireTime;
+
+/// Fields of a hash with their values. A field can also have its own expire time, which `insert`
+/// and `remove` clear.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Hash {
+  fields: HashMap<Bytes, Bytes>,
+  expire_times: HashMap<Bytes, ExpireTime>,
+  /// the same expire times ordered by time, so that expired fields are found without a scan
+  expire_order: BTreeSet<(ExpireTime, Bytes)>,
+}
+
+impl Hash {
+  pub fn get(&self, field: &[u8]) -> Option<&Bytes> {
+    self.fields.get(field)
+  }
+
+  pub fn contains_key(&self, field: &[u8]) -> bool {
+    self.fields.contains_key(field)
+  }
+
+  /// Sets the value of `field` and clears its expire time, like `HSET` does.
+  pub fn insert(&mut self, field: Bytes, value: Bytes) -> Option<Bytes> {
+    self.set_expire_time(&field, None);
+    self.fields.insert(field, value)
+  }
+
+  /// Sets the value of `field` but keeps its expire time, like `HINCRBY` does.
+  pub fn update(&mut self, field: Bytes, value: Bytes) {
+    self.fields.insert(field, value);
+  }
+
+  pub fn remove(&mut self, field: &[u8]) -> Option<Bytes> {
+    self.set_expire_time(field, None);
+    self.fields.remove(field)
+  }
+
+  pub fn len(&self) -> usize {
+    self.fields.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.fields.is_empty()
+  }
+
+  pub fn iter(&self) -> Iter<'_, Bytes, Bytes> {
+    self.fields.iter()
+  }
+
+  pub fn expire_time(&self, field: &[u8]) -> Option<ExpireTime> {
+    self.expire_times.get(field).copied()
+  }
+
+  /// Sets or clears the expire time of `field`, which is expected to exist when setting one.
+  pub fn set_expire_time(&mut self, field: &[u8], expire_time: Option<ExpireTime>) {
+    if let Some(old) = self.expire_times.remove(field) {
+      self
+        .expire_order
+        .remove(&(old, Bytes::copy_from_slice(field)));
+    }
+    if let Some(expire_time) = expire_time {
+      let field = Bytes::copy_from_slice(field);
+      self.expire_times.insert(field.clone(), expire_time);
+      self.expire_order.insert((expire_time, field));
+    }
+  }
+
+  /// Whether some field has an expire time.
+  pub fn has_expiring_fields(&self) -> bool {
+    !self.expire_order.is_empty()
+  }
+
+  /// Removes the fields whose expire time is before `now` and returns how many there were.
+  pub fn remove_expired(&mut self, now: ExpireTime) -> usize {
+    let mut removed = 0;
+    while let Some((expire_time, _)) = self.expire_order.first() {
+      if *expire_time >= now {
+        break;
+      }
+      let (_, field) = self.expire_order.pop_first().expect("checked above");
+      self.expire_times.remove(&field);
+      self.fields.remove(&field);
+      removed += 1;
+    }
+    removed
+  }
+}
+
+impl<'a> IntoIterator for &'a Hash {
+  type Item = (&'a Bytes, &'a Bytes);
+  type IntoIter = Iter<'a, Bytes, Bytes>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+#[cfg(test)]
+mod tests_hash {
+  use super::*;
+
+  fn hash(fields: &[&'static str]) -> Hash {
+    let mut hash = Hash::default();
+    for field in fields {
+      hash.insert(Bytes::from(*field), Bytes::from("value"));
+    }
+    hash
+  }
+
+  #[test]
+  fn should_remove_only_expired_fields() {
+    let mut hash = hash(&["a", "b", "c"]);
+    hash.set_expire_time(b"a", Some(100));
+    hash.set_expire_time(b"b", Some(200));
+    hash.set_expire_time(b"b", Some(50));
+
+    assert_eq!(hash.remove_expired(100), 1);
+    assert!(!hash.contains_key(b"b"));
+    assert_eq!(hash.remove_expired(101), 1);
+    assert_eq!(hash.len(), 1);
+    assert!(!hash.has_expiring_fields());
+  }
+
+  #[test]
+  fn should_clear_expire_time_when_field_is_overwritten_or_removed() {
+    let mut hash = hash(&["a", "b"]);
+    hash.set_expire_time(b"a", Some(100));
+    hash.set_expire_time(b"b", Some(100));
+
+    hash.insert(Bytes::from("a"), Bytes::from("new"));
+    assert_eq!(hash.expire_time(b"a"), None);
+    hash.remove(b"b");
+    assert!(!hash.has_expiring_fields());
+
+    hash.set_expire_time(b"a", Some(100));
+    hash.update(Bytes::from("a"), Bytes::from("newer"));
+    assert_eq!(hash.expire_time(b"a"), Some(100));
+  }
+}
@@ -0,0 +1,44 @@
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, OnceLock};
+
+use super::{Data, SetValue, Value};
+
+/// Values that take more effort than this to free are dropped on the background thread,
+/// like Redis' `LAZYFREE_THRESHOLD`.
+pub const LAZYFREE_THRESHOLD: usize = 64;
+
+/// Strings are freed in one allocation, but large ones are returned to the OS page by page,
+/// so every this many bytes count as one unit of effort.
+const STRING_EFFORT_UNIT: usize = 4 * 1024;
+
+/// Roughly how many allocations dropping `data` releases, like Redis' `lazyfreeGetFreeEffort`.
+pub fn free_effort(data: &Data) -> usize {
+  match &data.value {
+    Value::String(string) => 1 + string.len() / STRING_EFFORT_UNIT,
+    Value::List(list) => list.len(),
+    Value::Hash(hash) => hash.len(),
+    // an intset is a single allocation
+    Value::Set(SetValue::IntSet(_)) => 1,
+    Value::Set(set) => set.len(),
+    Value::ZSet(zset) => zset.len(),
+    Value::Stream(stream) => stream.len(),
+  }
+}
+
+/// Drops `data` on a dedicated thread so that freeing it does not hold up the command path.
+pub fn free_in_background(data: Data) {
+  static SENDER: OnceLock<Mutex<Sender<Data>>> = OnceLock::new();
+
+  let sender = SENDER.get_or_init(|| {
+    let (sender, receiver) = mpsc::channel::<Data>();
+    std::thread::Builder::new()
+      .name("lazyfree".to_owned())
+      .spawn(move || receiver.into_iter().for_each(drop))
+      .expect("failed to spawn lazyfree thread");
+    Mutex::new(sender)
+  });
+
+  if let Err(mpsc::SendError(data)) = sender.lock().unwrap().send(data) {
+    drop(data);
+  }
+}
@@ -0,0 +1,163 @@
+use std::collections::HashSet;
+
+use bytes::Bytes;
+
+use super::string::as_integer;
+
+/// Sets with only integer members stay in the compact encoding up to this many members, like
+/// Redis' default `set-max-intset-entries`.
+pub const SET_MAX_INTSET_ENTRIES: usize = 512;
+
+/// Members of a set. Small sets of integers are kept as a sorted array of integers, like Redis
+/// intsets, and converted to a hash table for good once a member doesn't fit that encoding.
+#[derive(PartialEq, Debug, Clone)]
+pub enum SetValue {
+  IntSet(Vec<i64>),
+  HashTable(HashSet<Bytes>),
+}
+
+impl Default for SetValue {
+  fn default() -> Self {
+    SetValue::IntSet(Vec::new())
+  }
+}
+
+impl SetValue {
+  pub fn len(&self) -> usize {
+    match self {
+      SetValue::IntSet(integers) => integers.len(),
+      SetValue::HashTable(members) => members.len(),
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// The name `OBJECT ENCODING` would report for this set.
+  pub fn encoding(&self) -> &'static str {
+    match self {
+      SetValue::IntSet(_) => "intset",
+      SetValue::HashTable(_) => "hashtable",
+    }
+  }
+
+  pub fn contains(&self, member: &[u8]) -> bool {
+    match self {
+      SetValue::IntSet(integers) => {
+        as_integer(member).is_some_and(|integer| integers.binary_search(&integer).is_ok())
+      }
+      SetValue::HashTable(members) => members.contains(member),
+    }
+  }
+
+  /// Adds `member` and returns whether it was not in the set yet.
+  pub fn insert(&mut self, member: Bytes) -> bool {
+    if let SetValue::IntSet(integers) = self {
+      if let Some(integer) = as_integer(&member) {
+        let Err(position) = integers.binary_search(&integer) else {
+          return false;
+        };
+        if integers.len() < SET_MAX_INTSET_ENTRIES {
+          integers.insert(position, integer);
+          return true;
+        }
+      }
+      self.convert_to_hash_table();
+    }
+    match self {
+      SetValue::HashTable(members) => members.insert(member),
+      SetValue::IntSet(_) => unreachable!("converted above"),
+    }
+  }
+
+  /// Removes `member` and returns whether it was in the set.
+  pub fn remove(&mut self, member: &[u8]) -> bool {
+    match self {
+      SetValue::IntSet(integers) => {
+        let position = as_integer(member).and_then(|integer| integers.binary_search(&integer).ok());
+        position.map(|position| integers.remove(position)).is_some()
+      }
+      SetValue::HashTable(members) => members.remove(member),
+    }
+  }
+
+  /// The members in no particular order, integers formatted as decimal strings.
+  pub fn members(&self) -> Vec<Bytes> {
+    match self {
+      SetValue::IntSet(integers) => integers
+        .iter()
+        .map(|integer| Bytes::from(integer.to_string()))
+        .collect(),
+      SetValue::HashTable(members) => members.iter().cloned().collect(),
+    }
+  }
+
+  fn convert_to_hash_table(&mut self) {
+    if let SetValue::IntSet(integers) = self {
+      let members = integers
+        .iter()
+        .map(|integer| Bytes::from(integer.to_string()))
+        .collect();
+      *self = SetValue::HashTable(members);
+    }
+  }
+}
+
+impl FromIterator<Bytes> for SetValue {
+  fn from_iter<I: IntoIterator<Item = Bytes>>(members: I) -> Self {
+    let mut set = SetValue::default();
+    for member in members {
+      set.insert(member);
+    }
+    set
+  }
+}
+
+/// The integer `member` spells, if formatting it back gives the same bytes, so that the intset
+/// encoding never changes how a member reads.
+#[cfg(test)]
+mod tests_set {
+  use super::*;
+
+  fn set(members: &[&'static str]) -> SetValue {
+    members.iter().map(|member| Bytes::from(*member)).collect()
+  }
+
+  #[test]
+  fn should_keep_integers_sorted_in_intset() {
+    let mut set = set(&["3", "-1", "2", "3"]);
+    assert_eq!(set, SetValue::IntSet(vec![-1, 2, 3]));
+    assert!(set.contains(b"2"));
+    assert!(!set.contains(b"02"));
+    assert!(set.remove(b"-1"));
+    assert!(!set.remove(b"-1"));
+    assert_eq!(set.members(), vec![Bytes::from("2"), Bytes::from("3")]);
+  }
+
+  #[test]
+  fn should_convert_to_hash_table_for_non_integers() {
+    let mut set = set(&["1", "2"]);
+    assert!(set.insert(Bytes::from("01")));
+    assert_eq!(set.encoding(), "hashtable");
+    assert!(set.contains(b"1") && set.contains(b"01"));
+    assert_eq!(set.len(), 3);
+
+    set.remove(b"01");
+    assert_eq!(set.encoding(), "hashtable", "a set never converts back");
+  }
+
+  #[test]
+  fn should_convert_to_hash_table_when_intset_grows_too_large() {
+    let mut set = (0..SET_MAX_INTSET_ENTRIES)
+      .map(|integer| Bytes::from(integer.to_string()))
+      .collect::<SetValue>();
+    assert_eq!(set.encoding(), "intset");
+    assert!(!set.insert(Bytes::from("0")));
+    assert_eq!(set.encoding(), "intset");
+
+    assert!(set.insert(Bytes::from("-1")));
+    assert_eq!(set.encoding(), "hashtable");
+    assert_eq!(set.len(), SET_MAX_INTSET_ENTRIES + 1);
+  }
+}
@@ -0,0 +1,399 @@
+use std::fmt;
+
+use super::active_expire::Rng;
+
+/// Enough levels for 4^32 elements, like in Redis.
+const MAX_LEVEL: usize = 32;
+
+/// The node every search starts from, which holds no element.
+const HEAD: usize = 0;
+
+/// An ordered list of distinct elements where each link also counts the elements it skips, like
+/// Redis' `zskiplist`. Searching, inserting, removing, finding the rank of an element and finding
+/// the element at a rank all take logarithmic time.
+///
+/// Nodes live in a vector and link to each other by index; removed nodes are reused.
+#[derive(Clone)]
+pub struct SkipList<T> {
+  nodes: Vec<Node<T>>,
+  /// removed nodes, to be reused by the next insertions
+  free: Vec<usize>,
+  tail: Option<usize>,
+  /// how many levels are in use, at least one
+  level: usize,
+  len: usize,
+  rng: Rng,
+}
+
+#[derive(Clone)]
+struct Node<T> {
+  /// `None` for the head and for removed nodes
+  element: Option<T>,
+  levels: Vec<Link>,
+  backward: Option<usize>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Link {
+  next: Option<usize>,
+  /// how many elements following this link moves forward by
+  span: usize,
+}
+
+impl<T> Default for SkipList<T> {
+  fn default() -> Self {
+    let head = Node {
+      element: None,
+      levels: vec![Link::default(); MAX_LEVEL],
+      backward: None,
+    };
+    SkipList {
+      nodes: vec![head],
+      free: vec![],
+      tail: None,
+      level: 1,
+      len: 0,
+      rng: Rng::default(),
+    }
+  }
+}
+
+impl<T: Ord + Clone> SkipList<T> {
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Inserts an element that isn't in the list yet.
+  pub fn insert(&mut self, element: T) {
+    let mut update = [HEAD; MAX_LEVEL];
+    // the rank of `update[i]`, counting the head as 0
+    let mut rank = [0; MAX_LEVEL];
+    let mut x = HEAD;
+    for i in (0..self.level).rev() {
+      rank[i] = if i + 1 == self.level { 0 } else { rank[i + 1] };
+      while let Some(next) = self.nodes[x].levels[i].next {
+        if *self.element(next) >= element {
+          break;
+        }
+        rank[i] += self.nodes[x].levels[i].span;
+        x = next;
+      }
+      update[i] = x;
+    }
+
+    let level = self.random_level();
+    if level > self.level {
+      for i in self.level..level {
+        rank[i] = 0;
+        update[i] = HEAD;
+        self.nodes[HEAD].levels[i].span = self.len;
+      }
+      self.level = level;
+    }
+
+    let node = self.allocate(element, level);
+    for i in 0..level {
+      let previous = self.nodes[update[i]].levels[i];
+      self.nodes[node].levels[i] = Link {
+        next: previous.next,
+        span: previous.span - (rank[0] - rank[i]),
+      };
+      self.nodes[update[i]].levels[i] = Link {
+        next: Some(node),
+        span: rank[0] - rank[i] + 1,
+      };
+    }
+    // the links above the new node now skip over it too
+    for (i, &previous) in update.iter().enumerate().take(self.level).skip(level) {
+      self.nodes[previous].levels[i].span += 1;
+    }
+
+    self.nodes[node].backward = (update[0] != HEAD).then_some(update[0]);
+    match self.nodes[node].levels[0].next {
+      Some(next) => self.nodes[next].backward = Some(node),
+      None => self.tail = Some(node),
+    }
+    self.len += 1;
+  }
+
+  /// Removes `element`, returning it if it was in the list.
+  pub fn remove(&mut self, element: &T) -> Option<T> {
+    let mut update = [HEAD; MAX_LEVEL];
+    let mut x = HEAD;
+    for i in (0..self.level).rev() {
+      while let Some(next) = self.nodes[x].levels[i].next {
+        if self.element(next) >= element {
+          break;
+        }
+        x = next;
+      }
+      update[i] = x;
+    }
+    let node = self.nodes[x].levels[0].next?;
+    if self.element(node) != element {
+      return None;
+    }
+
+    for (i, &previous) in update.iter().enumerate().take(self.level) {
+      // only the links to the removed node are replaced, the ones above it skip one less
+      if self.nodes[previous].levels[i].next == Some(node) {
+        let removed = self.nodes[node].levels[i];
+        let link = &mut self.nodes[previous].levels[i];
+        *link = Link {
+          next: removed.next,
+          span: link.span + removed.span - 1,
+        };
+      } else {
+        self.nodes[previous].levels[i].span -= 1;
+      }
+    }
+    let backward = self.nodes[node].backward;
+    match self.nodes[node].levels[0].next {
+      Some(next) => self.nodes[next].backward = backward,
+      None => self.tail = backward,
+    }
+    while self.level > 1 && self.nodes[HEAD].levels[self.level - 1].next.is_none() {
+      self.level -= 1;
+    }
+    self.len -= 1;
+
+    self.free.push(node);
+    self.nodes[node].element.take()
+  }
+
+  pub fn pop_first(&mut self) -> Option<T> {
+    let first = self.element(self.nodes[HEAD].levels[0].next?).clone();
+    self.remove(&first)
+  }
+
+  pub fn pop_last(&mut self) -> Option<T> {
+    let last = self.element(self.tail?).clone();
+    self.remove(&last)
+  }
+
+  /// How many elements from the start of the list satisfy `before`, which must hold for a prefix
+  /// of the list and not after it. The rank of an element is how many elements are less than it.
+  pub fn rank_where(&self, before: impl Fn(&T) -> bool) -> usize {
+    let mut rank = 0;
+    let mut x = HEAD;
+    for i in (0..self.level).rev() {
+      while let Some(next) = self.nodes[x].levels[i].next {
+        if !before(self.element(next)) {
+          break;
+        }
+        rank += self.nodes[x].levels[i].span;
+        x = next;
+      }
+    }
+    rank
+  }
+
+  /// The elements from the `start`th one, counting from 0, up to before the `end`th one.
+  pub fn range(&self, start: usize, end: usize) -> Iter<'_, T> {
+    let end = end.min(self.len);
+    if start >= end {
+      return Iter {
+        list: self,
+        front: None,
+        back: None,
+        front_rank: 0,
+        remaining: 0,
+      };
+    }
+    Iter {
+      list: self,
+      front: self.node_at(start),
+      back: self.node_at(end - 1),
+      front_rank: start,
+      remaining: end - start,
+    }
+  }
+
+  pub fn iter(&self) -> Iter<'_, T> {
+    self.range(0, self.len)
+  }
+
+  /// The node holding the `rank`th element, counting from 0.
+  fn node_at(&self, rank: usize) -> Option<usize> {
+    // spans count the head as rank 0
+    let target = rank + 1;
+    let mut traversed = 0;
+    let mut x = HEAD;
+    for i in (0..self.level).rev() {
+      while let Some(next) = self.nodes[x].levels[i].next {
+        if traversed + self.nodes[x].levels[i].span > target {
+          break;
+        }
+        traversed += self.nodes[x].levels[i].span;
+        x = next;
+      }
+      if traversed == target {
+        return Some(x);
+      }
+    }
+    None
+  }
+
+  fn element(&self, node: usize) -> &T {
+    self.nodes[node]
+      .element
+      .as_ref()
+      .expect("linked nodes hold an element")
+  }
+
+  fn allocate(&mut self, element: T, level: usize) -> usize {
+    let node = Node {
+      element: Some(element),
+      levels: vec![Link::default(); level],
+      backward: None,
+    };
+    match self.free.pop() {
+      Some(index) => {
+        self.nodes[index] = node;
+        index
+      }
+      None => {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+      }
+    }
+  }
+
+  /// Each level is used by a quarter of the nodes of the level below it, like in Redis.
+  fn random_level(&mut self) -> usize {
+    let mut level = 1;
+    while level < MAX_LEVEL && self.rng.below(4) == 0 {
+      level += 1;
+    }
+    level
+  }
+}
+
+impl<T: Ord + Clone> PartialEq for SkipList<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.len == other.len && self.iter().eq(other.iter())
+  }
+}
+
+impl<T: Ord + Clone + fmt::Debug> fmt::Debug for SkipList<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+/// Elements of a range of a [`SkipList`], in order from either end. Skipping ahead with `nth`
+/// takes logarithmic time.
+pub struct Iter<'a, T> {
+  list: &'a SkipList<T>,
+  front: Option<usize>,
+  back: Option<usize>,
+  front_rank: usize,
+  remaining: usize,
+}
+
+impl<'a, T: Ord + Clone> Iterator for Iter<'a, T> {
+  type Item = &'a T;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.remaining == 0 {
+      return None;
+    }
+    let node = self.front?;
+    self.front = self.list.nodes[node].levels[0].next;
+    self.front_rank += 1;
+    self.remaining -= 1;
+    Some(self.list.element(node))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.remaining, Some(self.remaining))
+  }
+
+  fn nth(&mut self, n: usize) -> Option<Self::Item> {
+    if n >= self.remaining {
+      self.remaining = 0;
+      return None;
+    }
+    if n > 0 {
+      self.front_rank += n;
+      self.remaining -= n;
+      self.front = self.list.node_at(self.front_rank);
+    }
+    self.next()
+  }
+}
+
+impl<T: Ord + Clone> DoubleEndedIterator for Iter<'_, T> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.remaining == 0 {
+      return None;
+    }
+    let node = self.back?;
+    self.back = self.list.nodes[node].backward;
+    self.remaining -= 1;
+    Some(self.list.element(node))
+  }
+}
+
+impl<T: Ord + Clone> ExactSizeIterator for Iter<'_, T> {}
+
+#[cfg(test)]
+mod tests_skiplist {
+  use std::collections::BTreeSet;
+
+  use super::*;
+
+  #[test]
+  fn should_keep_ranks_in_step_with_an_ordered_set() {
+    let mut list = SkipList::default();
+    let mut expected = BTreeSet::new();
+    let mut rng = Rng::default();
+    for _ in 0..2_000 {
+      let element = rng.below(500);
+      if rng.below(3) == 0 {
+        assert_eq!(list.remove(&element), expected.take(&element));
+      } else if expected.insert(element) {
+        list.insert(element);
+      }
+    }
+
+    let expected = expected.into_iter().collect::<Vec<_>>();
+    assert_eq!(list.len(), expected.len());
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), expected);
+    assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), {
+      let mut reversed = expected.clone();
+      reversed.reverse();
+      reversed
+    });
+    for (rank, element) in expected.iter().enumerate() {
+      assert_eq!(list.rank_where(|other| other < element), rank);
+      assert_eq!(list.range(rank, rank + 1).next(), Some(element));
+    }
+  }
+
+  #[test]
+  fn should_iterate_ranges_from_both_ends() {
+    let mut list = SkipList::default();
+    for element in [5, 1, 4, 2, 3] {
+      list.insert(element);
+    }
+
+    assert_eq!(list.range(1, 4).copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+    assert_eq!(
+      list.range(1, 4).rev().copied().collect::<Vec<_>>(),
+      vec![4, 3, 2]
+    );
+    assert_eq!(list.range(0, 9).nth(3), Some(&4));
+    assert_eq!(list.range(3, 2).next(), None);
+
+    let mut range = list.range(0, 5);
+    assert_eq!((range.next(), range.next_back()), (Some(&1), Some(&5)));
+    assert_eq!(range.nth(1), Some(&3));
+    assert_eq!(range.next_back(), Some(&4));
+    assert_eq!(range.next(), None);
+
+    assert_eq!((list.pop_first(), list.pop_last()), (Some(1), Some(5)));
+    assert_eq!(list.remove(&9), None);
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+  }
+}
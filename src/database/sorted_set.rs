@@ -0,0 +1,260 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use bytes::Bytes;
+
+use super::skiplist::SkipList;
+
+/// Members of a sorted set with their scores. Members are also kept ordered by score, then by
+/// member, in a skiplist that counts what its links skip, so that ranges come out in order
+/// without sorting, and ranks and index ranges are found in logarithmic time.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct SortedSet {
+  scores: HashMap<Bytes, f64>,
+  order: SkipList<(Score, Bytes)>,
+}
+
+/// A score that is never NaN, so that scores can be totally ordered.
+#[derive(PartialEq, Debug, Clone, Copy)]
+struct Score(f64);
+
+impl Eq for Score {}
+
+impl PartialOrd for Score {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for Score {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.0.total_cmp(&other.0)
+  }
+}
+
+/// One end of a `BYSCORE` range, like `1.5`, `(1.5` or `-inf`.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct ScoreBound {
+  pub score: f64,
+  pub exclusive: bool,
+}
+
+/// One end of a `BYLEX` range: `-`, `+`, `[member` or `(member`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum LexBound {
+  Min,
+  Max,
+  Inclusive(Bytes),
+  Exclusive(Bytes),
+}
+
+impl SortedSet {
+  pub fn len(&self) -> usize {
+    self.order.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.scores.is_empty()
+  }
+
+  pub fn score(&self, member: &[u8]) -> Option<f64> {
+    self.scores.get(member).copied()
+  }
+
+  /// Adds `member` or updates its score, which must not be NaN. Returns the previous score.
+  pub fn insert(&mut self, member: Bytes, score: f64) -> Option<f64> {
+    // -0 and 0 are the same score, as in Redis
+    let score = score + 0.0;
+    let old = self.remove(&member);
+    self.order.insert((Score(score), member.clone()));
+    self.scores.insert(member, score);
+    old
+  }
+
+  pub fn remove(&mut self, member: &[u8]) -> Option<f64> {
+    let (member, score) = self.scores.remove_entry(member)?;
+    self.order.remove(&(Score(score), member));
+    Some(score)
+  }
+
+  /// The position of `member` counting from the lowest score, or from the highest when `rev`.
+  pub fn rank(&self, member: &[u8], rev: bool) -> Option<usize> {
+    let score = self.score(member)?;
+    let entry = (Score(score), Bytes::copy_from_slice(member));
+    let rank = self.order.rank_where(|other| *other < entry);
+    Some(if rev { self.len() - 1 - rank } else { rank })
+  }
+
+  /// Members with their scores, from the lowest score to the highest.
+  pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&Bytes, f64)> {
+    self
+      .order
+      .iter()
+      .map(|(Score(score), member)| (member, *score))
+  }
+
+  /// Members with a score between `min` and `max`, from the lowest score, or from the highest
+  /// when `rev`.
+  pub fn range_by_score(
+    &self,
+    min: ScoreBound,
+    max: ScoreBound,
+    rev: bool,
+  ) -> Box<dyn Iterator<Item = (&Bytes, f64)> + '_> {
+    let above_min = |score: f64| score > min.score || (!min.exclusive && score == min.score);
+    let below_max = |score: f64| score < max.score || (!max.exclusive && score == max.score);
+    let start = self
+      .order
+      .rank_where(|(Score(score), _)| !above_min(*score));
+    let end = self.order.rank_where(|(Score(score), _)| below_max(*score));
+    self.entries(start, end, rev)
+  }
+
+  /// Members between `min` and `max` in lexicographical order, or in reverse when `rev`.
+  /// Like in Redis, the result is only meaningful when all members have the same score.
+  pub fn range_by_lex(
+    &self,
+    min: &LexBound,
+    max: &LexBound,
+    rev: bool,
+  ) -> Box<dyn Iterator<Item = (&Bytes, f64)> + '_> {
+    let above_min = |member: &Bytes| match min {
+      LexBound::Min => true,
+      LexBound::Max => false,
+      LexBound::Inclusive(min) => member >= min,
+      LexBound::Exclusive(min) => member > min,
+    };
+    let below_max = |member: &Bytes| match max {
+      LexBound::Min => false,
+      LexBound::Max => true,
+      LexBound::Inclusive(max) => member <= max,
+      LexBound::Exclusive(max) => member < max,
+    };
+    let start = self.order.rank_where(|(_, member)| !above_min(member));
+    let end = self.order.rank_where(|(_, member)| below_max(member));
+    self.entries(start, end, rev)
+  }
+
+  /// Members from the `start`th to the `stop`th inclusive, counting from 0 at the lowest score,
+  /// or at the highest when `rev`.
+  pub fn range_by_rank(
+    &self,
+    start: usize,
+    stop: usize,
+    rev: bool,
+  ) -> Box<dyn Iterator<Item = (&Bytes, f64)> + '_> {
+    if rev {
+      let len = self.len();
+      self.entries(
+        len.saturating_sub(stop + 1),
+        len.saturating_sub(start),
+        true,
+      )
+    } else {
+      self.entries(start, stop + 1, false)
+    }
+  }
+
+  /// Members from the `start`th up to before the `end`th, counting from the lowest score, in
+  /// reverse when `rev`.
+  fn entries(
+    &self,
+    start: usize,
+    end: usize,
+    rev: bool,
+  ) -> Box<dyn Iterator<Item = (&Bytes, f64)> + '_> {
+    let entries = self
+      .order
+      .range(start, end)
+      .map(|(Score(score), member)| (member, *score));
+    if rev {
+      Box::new(entries.rev())
+    } else {
+      Box::new(entries)
+    }
+  }
+
+  /// Removes and returns the member with the lowest score, or the highest when `max`.
+  pub fn pop(&mut self, max: bool) -> Option<(Bytes, f64)> {
+    let (Score(score), member) = if max {
+      self.order.pop_last()?
+    } else {
+      self.order.pop_first()?
+    };
+    self.scores.remove(&member);
+    Some((member, score))
+  }
+}
+
+#[cfg(test)]
+mod tests_sorted_set {
+  use super::*;
+
+  fn zset(entries: &[(&'static str, f64)]) -> SortedSet {
+    let mut zset = SortedSet::default();
+    for (member, score) in entries {
+      zset.insert(Bytes::from(*member), *score);
+    }
+    zset
+  }
+
+  fn members<'a>(entries: impl Iterator<Item = (&'a Bytes, f64)>) -> Vec<&'a [u8]> {
+    entries.map(|(member, _)| &member[..]).collect()
+  }
+
+  #[test]
+  fn should_order_by_score_then_member() {
+    let mut zset = zset(&[("b", 1.0), ("a", 1.0), ("c", 0.5), ("d", f64::INFINITY)]);
+    assert_eq!(members(zset.iter()), vec![b"c", b"a", b"b", b"d"]);
+
+    assert_eq!(zset.insert(Bytes::from("d"), -1.0), Some(f64::INFINITY));
+    assert_eq!(members(zset.iter()), vec![b"d", b"c", b"a", b"b"]);
+    assert_eq!(zset.rank(b"a", false), Some(2));
+    assert_eq!(zset.rank(b"a", true), Some(1));
+    assert_eq!(zset.rank(b"missing", false), None);
+
+    assert_eq!(zset.pop(true), Some((Bytes::from("b"), 1.0)));
+    assert_eq!(zset.remove(b"d"), Some(-1.0));
+    assert_eq!(zset.len(), 2);
+  }
+
+  #[test]
+  fn should_select_ranges_by_rank() {
+    let zset = zset(&[("a", 1.0), ("b", 2.0), ("c", 3.0), ("d", 4.0)]);
+    assert_eq!(members(zset.range_by_rank(1, 2, false)), vec![b"b", b"c"]);
+    assert_eq!(members(zset.range_by_rank(0, 1, true)), vec![b"d", b"c"]);
+    assert_eq!(members(zset.range_by_rank(2, 9, true)), vec![b"b", b"a"]);
+    assert_eq!(zset.rank(b"d", false), Some(3));
+    assert_eq!(zset.rank(b"d", true), Some(0));
+  }
+
+  #[test]
+  fn should_treat_negative_zero_as_zero() {
+    let zset = zset(&[("a", -0.0), ("b", 0.0)]);
+    assert_eq!(members(zset.iter()), vec![b"a", b"b"]);
+    assert!(zset.score(b"a").unwrap().is_sign_positive());
+  }
+
+  #[test]
+  fn should_select_ranges_by_score_and_lex() {
+    let scored = zset(&[("a", 1.0), ("b", 2.0), ("c", 2.0), ("d", 3.0)]);
+    let bound = |score, exclusive| ScoreBound { score, exclusive };
+
+    let range = scored.range_by_score(bound(2.0, false), bound(f64::INFINITY, false), false);
+    assert_eq!(members(range), vec![b"b", b"c", b"d"]);
+    let range = scored.range_by_score(bound(1.0, true), bound(3.0, true), true);
+    assert_eq!(members(range), vec![b"c", b"b"]);
+
+    let lex = zset(&[("c", 0.0), ("a", 0.0), ("b", 0.0)]);
+    let (min, max) = (LexBound::Exclusive(Bytes::from("a")), LexBound::Max);
+    assert_eq!(
+      members(lex.range_by_lex(&min, &max, false)),
+      vec![b"b", b"c"]
+    );
+    let (min, max) = (LexBound::Min, LexBound::Inclusive(Bytes::from("b")));
+    assert_eq!(
+      members(lex.range_by_lex(&min, &max, true)),
+      vec![b"b", b"a"]
+    );
+  }
+}
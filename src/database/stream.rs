@@ -0,0 +1,292 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use bytes::Bytes;
+
+use super::ConsumerGroup;
+
+/// Entries per node of the radix tree Redis stores streams in, its default
+/// `stream-node-max-entries`. Approximate trimming only removes whole nodes.
+pub const STREAM_NODE_MAX_ENTRIES: usize = 100;
+
+/// The ID of a stream entry: a millisecond timestamp and a sequence number within it.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Default, Hash)]
+pub struct StreamId {
+  pub ms: u64,
+  pub seq: u64,
+}
+
+impl StreamId {
+  pub const MIN: StreamId = StreamId { ms: 0, seq: 0 };
+  pub const MAX: StreamId = StreamId {
+    ms: u64::MAX,
+    seq: u64::MAX,
+  };
+
+  /// The smallest ID after this one, if any.
+  pub fn next(self) -> Option<StreamId> {
+    match self {
+      StreamId::MAX => None,
+      StreamId { ms, seq: u64::MAX } => Some(StreamId { ms: ms + 1, seq: 0 }),
+      StreamId { ms, seq } => Some(StreamId { ms, seq: seq + 1 }),
+    }
+  }
+
+  /// The largest ID before this one, if any.
+  pub fn prev(self) -> Option<StreamId> {
+    match self {
+      StreamId::MIN => None,
+      StreamId { ms, seq: 0 } => Some(StreamId {
+        ms: ms - 1,
+        seq: u64::MAX,
+      }),
+      StreamId { ms, seq } => Some(StreamId { ms, seq: seq - 1 }),
+    }
+  }
+
+  pub fn to_bytes(self) -> Bytes {
+    Bytes::from(self.to_string())
+  }
+}
+
+impl fmt::Display for StreamId {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}-{}", self.ms, self.seq)
+  }
+}
+
+/// The field-value pairs of a stream entry, in the order they were given.
+pub type StreamFields = Vec<(Bytes, Bytes)>;
+
+/// Which old entries a trim removes.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum TrimStrategy {
+  /// keep at most this many entries
+  MaxLen(usize),
+  /// remove the entries with a smaller ID
+  MinId(StreamId),
+}
+
+/// Entries of a stream ordered by ID. Unlike other collections, a stream stays around when its
+/// last entry is deleted, since it still remembers the last ID it generated.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Stream {
+  entries: BTreeMap<StreamId, StreamFields>,
+  /// the ID of the last entry ever added, which new IDs must be greater than
+  last_id: StreamId,
+  /// the largest ID of an entry deleted by `XDEL`
+  max_deleted_id: StreamId,
+  /// how many entries were ever added
+  entries_added: u64,
+  groups: BTreeMap<Bytes, ConsumerGroup>,
+}
+
+impl Stream {
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  pub fn last_id(&self) -> StreamId {
+    self.last_id
+  }
+
+  pub fn max_deleted_id(&self) -> StreamId {
+    self.max_deleted_id
+  }
+
+  pub fn entries_added(&self) -> u64 {
+    self.entries_added
+  }
+
+  pub fn first_entry(&self) -> Option<(&StreamId, &StreamFields)> {
+    self.entries.first_key_value()
+  }
+
+  pub fn last_entry(&self) -> Option<(&StreamId, &StreamFields)> {
+    self.entries.last_key_value()
+  }
+
+  pub fn get(&self, id: &StreamId) -> Option<&StreamFields> {
+    self.entries.get(id)
+  }
+
+  /// The ID `XADD *` would give an entry added at `now_ms`: the current time, or the next
+  /// sequence number when the clock did not move past the last ID. `None` once IDs ran out.
+  pub fn next_id(&self, now_ms: u64) -> Option<StreamId> {
+    if now_ms > self.last_id.ms {
+      Some(StreamId { ms: now_ms, seq: 0 })
+    } else {
+      self.last_id.next()
+    }
+  }
+
+  /// The ID `XADD ms-*` would give an entry: the next sequence number of `ms`, which must not
+  /// be before the last ID. `None` when no such ID is greater than the last one.
+  pub fn next_id_in(&self, ms: u64) -> Option<StreamId> {
+    match ms.cmp(&self.last_id.ms) {
+      std::cmp::Ordering::Less => None,
+      std::cmp::Ordering::Equal => {
+        let seq = self.last_id.seq.checked_add(1)?;
+        Some(StreamId { ms, seq })
+      }
+      // 0-0 is never a valid ID
+      std::cmp::Ordering::Greater => Some(StreamId {
+        ms,
+        seq: u64::from(ms == 0),
+      }),
+    }
+  }
+
+  /// Appends an entry, whose ID the caller made sure is greater than the last one.
+  pub fn insert(&mut self, id: StreamId, fields: StreamFields) {
+    debug_assert!(id > self.last_id);
+    self.entries.insert(id, fields);
+    self.last_id = id;
+    self.entries_added += 1;
+  }
+
+  /// Deletes the entry with `id` and returns whether it existed.
+  pub fn remove(&mut self, id: &StreamId) -> bool {
+    let removed = self.entries.remove(id).is_some();
+    if removed {
+      self.max_deleted_id = self.max_deleted_id.max(*id);
+    }
+    removed
+  }
+
+  /// Entries with an ID in `range`, from the smallest ID, or from the largest when `rev`.
+  pub fn range(
+    &self,
+    range: RangeInclusive<StreamId>,
+    rev: bool,
+  ) -> Box<dyn Iterator<Item = (&StreamId, &StreamFields)> + '_> {
+    if range.start() > range.end() {
+      return Box::new(std::iter::empty());
+    }
+    let entries = self.entries.range(range);
+    if rev {
+      Box::new(entries.rev())
+    } else {
+      Box::new(entries)
+    }
+  }
+
+  /// Removes the oldest entries as `strategy` asks and returns how many there were. An
+  /// approximate trim, like `MAXLEN ~`, only removes whole nodes of `STREAM_NODE_MAX_ENTRIES`
+  /// entries like Redis does, so it may keep a few more entries than asked. At most `limit`
+  /// entries are removed.
+  pub fn trim(&mut self, strategy: TrimStrategy, approx: bool, limit: Option<usize>) -> usize {
+    let excess = match strategy {
+      TrimStrategy::MaxLen(max_len) => self.len().saturating_sub(max_len),
+      TrimStrategy::MinId(min_id) => self.entries.range(..min_id).count(),
+    };
+    let mut count = excess.min(limit.unwrap_or(usize::MAX));
+    if approx {
+      count -= count % STREAM_NODE_MAX_ENTRIES;
+    }
+    for _ in 0..count {
+      self.entries.pop_first();
+    }
+    count
+  }
+
+  /// How many entries were added up to `id`, or `None` when entries deleted before it make that
+  /// unknown. Consumer groups use it to tell how far behind they are.
+  pub fn entries_read_at(&self, id: StreamId) -> Option<u64> {
+    if id >= self.last_id {
+      return Some(self.entries_added);
+    }
+    let trimmed = self.entries_added - self.len() as u64;
+    let first_id = self.first_entry().map(|(first_id, _)| *first_id);
+    if self.max_deleted_id == StreamId::MIN || Some(self.max_deleted_id) < first_id {
+      // trimming only removes the oldest entries, so all of them came before `id`
+      Some(trimmed + self.entries.range(..=id).count() as u64)
+    } else {
+      None
+    }
+  }
+
+  pub fn group(&self, name: &[u8]) -> Option<&ConsumerGroup> {
+    self.groups.get(name)
+  }
+
+  pub fn group_mut(&mut self, name: &[u8]) -> Option<&mut ConsumerGroup> {
+    self.groups.get_mut(name)
+  }
+
+  /// Consumer groups ordered by name.
+  pub fn groups(&self) -> impl Iterator<Item = (&Bytes, &ConsumerGroup)> {
+    self.groups.iter()
+  }
+
+  /// Adds a consumer group. Returns whether there was no group with that name yet.
+  pub fn create_group(&mut self, name: Bytes, group: ConsumerGroup) -> bool {
+    if self.groups.contains_key(&name) {
+      return false;
+    }
+    self.groups.insert(name, group);
+    true
+  }
+
+  /// Deletes a consumer group. Returns whether it existed.
+  pub fn destroy_group(&mut self, name: &[u8]) -> bool {
+    self.groups.remove(name).is_some()
+  }
+}
+
+#[cfg(test)]
+mod tests_stream {
+  use super::*;
+
+  fn id(ms: u64, seq: u64) -> StreamId {
+    StreamId { ms, seq }
+  }
+
+  fn stream(len: u64) -> Stream {
+    let mut stream = Stream::default();
+    for ms in 1..=len {
+      stream.insert(id(ms, 0), vec![]);
+    }
+    stream
+  }
+
+  #[test]
+  fn should_generate_increasing_ids() {
+    let mut stream = Stream::default();
+    assert_eq!(stream.next_id(5), Some(id(5, 0)));
+    assert_eq!(stream.next_id_in(0), Some(id(0, 1)));
+
+    stream.insert(id(5, 0), vec![]);
+    // the clock went backwards, so the sequence number grows instead
+    assert_eq!(stream.next_id(3), Some(id(5, 1)));
+    assert_eq!(stream.next_id_in(5), Some(id(5, 1)));
+    assert_eq!(stream.next_id_in(4), None);
+
+    stream.insert(StreamId::MAX, vec![]);
+    assert_eq!(stream.next_id(u64::MAX), None);
+    assert_eq!(id(1, u64::MAX).next(), Some(id(2, 0)));
+    assert_eq!(id(2, 0).prev(), Some(id(1, u64::MAX)));
+  }
+
+  #[test]
+  fn should_trim_exactly_or_by_whole_nodes() {
+    let mut exact = stream(250);
+    assert_eq!(exact.trim(TrimStrategy::MaxLen(10), false, None), 240);
+    assert_eq!(exact.first_entry().unwrap().0, &id(241, 0));
+    assert_eq!(exact.entries_read_at(id(245, 0)), Some(245));
+
+    let mut approx = stream(250);
+    assert_eq!(approx.trim(TrimStrategy::MaxLen(10), true, None), 200);
+    assert_eq!(approx.trim(TrimStrategy::MinId(id(240, 0)), true, None), 0);
+    assert_eq!(
+      approx.trim(TrimStrategy::MinId(id(240, 0)), false, Some(5)),
+      5
+    );
+    assert_eq!(approx.len(), 45);
+  }
+}
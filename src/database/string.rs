@@ -0,0 +1,83 @@
+use bytes::Bytes;
+
+/// A string value. Strings holding an integer in canonical form, like counters, are kept as a
+/// 64-bit integer, like Redis' `int` encoding; any other string is kept as its bytes.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum StringValue {
+  Int(i64),
+  Raw(Bytes),
+}
+
+impl Default for StringValue {
+  fn default() -> Self {
+    StringValue::Raw(Bytes::new())
+  }
+}
+
+impl From<Bytes> for StringValue {
+  fn from(bytes: Bytes) -> Self {
+    match as_integer(&bytes) {
+      Some(integer) => StringValue::Int(integer),
+      None => StringValue::Raw(bytes),
+    }
+  }
+}
+
+impl StringValue {
+  pub fn to_bytes(&self) -> Bytes {
+    match self {
+      StringValue::Int(integer) => Bytes::from(integer.to_string()),
+      StringValue::Raw(bytes) => bytes.clone(),
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    match self {
+      StringValue::Int(integer) => integer.to_string().len(),
+      StringValue::Raw(bytes) => bytes.len(),
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// The integer this string holds, if it holds one in canonical form: no sign but a leading
+  /// `-`, no leading zeros and no spaces, like Redis' `string2ll`.
+  pub fn as_integer(&self) -> Option<i64> {
+    match self {
+      StringValue::Int(integer) => Some(*integer),
+      StringValue::Raw(bytes) => as_integer(bytes),
+    }
+  }
+
+  /// The name `OBJECT ENCODING` would report for this string.
+  pub fn encoding(&self) -> &'static str {
+    match self {
+      StringValue::Int(_) => "int",
+      StringValue::Raw(_) => "raw",
+    }
+  }
+}
+
+/// Parses `bytes` as an integer only if printing it back gives the same bytes.
+pub(super) fn as_integer(bytes: &[u8]) -> Option<i64> {
+  let integer = std::str::from_utf8(bytes).ok()?.parse::<i64>().ok()?;
+  (integer.to_string().as_bytes() == bytes).then_some(integer)
+}
+
+#[cfg(test)]
+mod tests_string {
+  use super::*;
+
+  #[test]
+  fn should_only_encode_canonical_integers_as_int() {
+    assert_eq!(StringValue::from(Bytes::from("-42")), StringValue::Int(-42));
+    for raw in ["042", "+1", " 1", "1.0", "9223372036854775808", ""] {
+      let value = StringValue::from(Bytes::from(raw));
+      assert_eq!(value.encoding(), "raw", "{raw:?}");
+      assert_eq!(value.to_bytes(), raw);
+    }
+    assert_eq!(StringValue::Int(i64::MIN).len(), 20);
+  }
+}
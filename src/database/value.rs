@@ -0,0 +1,89 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+
+use super::{Hash, SetValue, SortedSet, Stream, StringValue};
+
+/// A value stored under a key, one variant per Redis data type.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Value {
+  String(StringValue),
+  List(VecDeque<Bytes>),
+  Hash(Hash),
+  Set(SetValue),
+  ZSet(SortedSet),
+  Stream(Stream),
+}
+
+impl Value {
+  /// The name `TYPE` reports for this value.
+  pub fn type_name(&self) -> &'static str {
+    match self {
+      Value::String(_) => "string",
+      Value::List(_) => "list",
+      Value::Hash(_) => "hash",
+      Value::Set(_) => "set",
+      Value::ZSet(_) => "zset",
+      Value::Stream(_) => "stream",
+    }
+  }
+
+  /// Whether this is a collection without elements. A string never counts as empty, nor does a
+  /// stream, which outlives its entries.
+  pub fn is_empty(&self) -> bool {
+    match self {
+      Value::String(_) => false,
+      Value::List(list) => list.is_empty(),
+      Value::Hash(hash) => hash.is_empty(),
+      Value::Set(set) => set.is_empty(),
+      Value::ZSet(zset) => zset.is_empty(),
+      Value::Stream(_) => false,
+    }
+  }
+}
+
+/// The Rust type held by one `Value` variant, so that `Database` can hand out values of the
+/// type a command expects and reject the others with WRONGTYPE.
+pub trait ValueKind: Default + Into<Value> {
+  fn from_value(value: &Value) -> Option<&Self>;
+  fn from_value_mut(value: &mut Value) -> Option<&mut Self>;
+}
+
+impl From<Bytes> for Value {
+  fn from(value: Bytes) -> Self {
+    Value::String(value.into())
+  }
+}
+
+macro_rules! value_kind {
+  ($variant:ident, $type:ty) => {
+    impl From<$type> for Value {
+      fn from(value: $type) -> Self {
+        Value::$variant(value)
+      }
+    }
+
+    impl ValueKind for $type {
+      fn from_value(value: &Value) -> Option<&Self> {
+        match value {
+          Value::$variant(value) => Some(value),
+          _ => None,
+        }
+      }
+
+      fn from_value_mut(value: &mut Value) -> Option<&mut Self> {
+        match value {
+          Value::$variant(value) => Some(value),
+          _ => None,
+        }
+      }
+    }
+  };
+}
+
+value_kind!(String, StringValue);
+value_kind!(List, VecDeque<Bytes>);
+value_kind!(Hash, Hash);
+value_kind!(Set, SetValue);
+value_kind!(ZSet, SortedSet);
+value_kind!(Stream, Stream);
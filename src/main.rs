@@ -1,21 +1,24 @@
+pub mod config;
+pub mod connection;
 pub mod database;
 pub mod resp_server;
 
+use std::cell::RefCell;
 use std::sync::{Arc, Mutex};
 
-use anyhow::{bail, Context, Result};
-use bytes::{BufMut, BytesMut};
-use database::Database;
-use resp_server::generate_response;
-use tokio::{
-  io::AsyncReadExt,
-  io::AsyncWriteExt,
-  net::{TcpListener, TcpStream},
-};
+use anyhow::{Context, Result};
+use config::Config;
+use connection::Connection;
+use database::{run_active_expire_cycle, Database};
+use resp_server::{error_reply, execute_all, generate_response, Blocked, Reply, Session};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::sleep_until;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+  let config = Config::from_args(std::env::args().skip(1)).context("invalid arguments")?;
   let db = Arc::new(Mutex::new(Database::new()));
+  tokio::spawn(run_active_expire_cycle(Arc::clone(&db)));
 
   let listener = TcpListener::bind("127.0.0.1:6379")
     .await
@@ -27,8 +30,9 @@ async fn main() -> Result<()> {
         println!("accepted new connection from {}", addr);
 
         let db = Arc::clone(&db);
+        let config = config.clone();
         tokio::spawn(async move {
-          if let Err(e) = handle_connection(connection, &db)
+          if let Err(e) = handle_connection(connection, &db, &config)
             .await
             .context("failed to handle connection")
           {
@@ -43,47 +47,97 @@ async fn main() -> Result<()> {
   }
 }
 
-async fn handle_connection(mut connection: TcpStream, db: &Arc<Mutex<Database>>) -> Result<()> {
-  let mut recv_buf = BytesMut::zeroed(1024);
+async fn handle_connection(
+  stream: TcpStream,
+  db: &Arc<Mutex<Database>>,
+  config: &Config,
+) -> Result<()> {
+  let mut connection = Connection::new(stream, config.max_request_size);
+  let session = RefCell::new(Session::new());
   loop {
-    match connection.read(&mut recv_buf).await {
-      Ok(0) => {
+    let request = match connection.read_frames().await {
+      Ok(Some(request)) => request,
+      Ok(None) => {
         println!("connection closed");
         return Ok(());
       }
-      Ok(n) => {
-        println!("read {} bytes", n);
+      Err(err) => {
+        // the stream can't be resynchronized after a framing error, so report it and hang up
+        let reply = error_reply(&err).encode(session.borrow().protocol);
+        let _ = connection.write_all(&reply).await;
+        return Err(err);
+      }
+    };
 
-        let request = &recv_buf[..n];
-        let response =
-          generate_response(request, db).context("failed to generate response from request");
+    let response = match generate_response(&request, db, &session) {
+      Ok(responses) => responses.concat(),
+      Err(err) => error_reply(&err.context("failed to generate response from request"))
+        .encode(session.borrow().protocol),
+    };
 
-        match response {
-          Ok(response) => {
-            connection
-              .write_all(&response)
-              .await
-              .context("failed to write response message to stream")?;
-          }
-          Err(err) => {
-            let mut err_buf = BytesMut::with_capacity(4096);
+    connection
+      .write_all(&response)
+      .await
+      .context("failed to write response message to stream")?;
 
-            err_buf.put(format!("-ERR {}\n", err).as_bytes());
-            for cause in err.chain().skip(1) {
-              err_buf.put(format!("\tCaused by: {}\n", cause).as_bytes());
-            }
-            err_buf.put("\r\n".as_bytes());
+    // a blocked command may be followed by commands that block again once they run
+    loop {
+      let blocked = session.borrow_mut().blocked.take();
+      let Some(mut blocked) = blocked else { break };
+      let pending = std::mem::take(&mut blocked.pending);
+      let Some(reply) = wait_until_unblocked(&mut connection, db, blocked).await? else {
+        println!("connection closed while blocked");
+        return Ok(());
+      };
 
-            connection
-              .write_all(&err_buf)
-              .await
-              .context("failed to write error message to stream")?;
-          }
-        }
-        connection.flush().await.context("failed to flush stream")?;
+      let mut response = reply.encode(session.borrow().protocol);
+      response.extend(execute_all(pending, db, &session).concat());
+      connection
+        .write_all(&response)
+        .await
+        .context("failed to write response message to stream")?;
+    }
+  }
+}
+
+/// Waits for the reply of a blocked command, or for its timeout. `None` means the client
+/// disconnected in the meantime, in which case it stops waiting on its keys.
+async fn wait_until_unblocked(
+  connection: &mut Connection,
+  db: &Arc<Mutex<Database>>,
+  blocked: Blocked,
+) -> Result<Option<Reply>> {
+  let Blocked {
+    id,
+    mut receiver,
+    deadline,
+    timeout_reply,
+    ..
+  } = blocked;
+
+  let timeout = async {
+    match deadline {
+      Some(deadline) => sleep_until(deadline).await,
+      None => std::future::pending().await,
+    }
+  };
+  tokio::pin!(timeout);
+
+  loop {
+    tokio::select! {
+      reply = &mut receiver => {
+        return Ok(Some(reply.unwrap_or(timeout_reply)));
       }
-      Err(e) => {
-        bail!("failed to read from stream: {}", e);
+      _ = &mut timeout => {
+        db.lock().unwrap().unblock(id);
+        // the client may have been served right before it was unblocked
+        return Ok(Some(receiver.try_recv().unwrap_or(timeout_reply)));
+      }
+      open = connection.read_more() => {
+        if !matches!(open, Ok(true)) {
+          db.lock().unwrap().unblock(id);
+          return open.map(|_| None);
+        }
       }
     }
   }
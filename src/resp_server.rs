@@ -1,27 +1,71 @@
 mod command;
+mod command_table;
+mod error;
 mod interpreter;
 mod parser;
+mod reply;
 mod response;
+mod session;
 mod tokenizer;
 
 pub use command::*;
-pub use response::generate_response;
+pub use error::RedisError;
+pub use reply::{format_double, Reply};
+pub use response::{error_reply, execute_all, generate_response};
+pub use session::{Blocked, Protocol, Session};
+pub use tokenizer::frame_len;
 
 use anyhow::{bail, Context, Result};
+use bytes::Bytes;
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum Token {
   Array(usize),
+  NullArray,
   BulkString(usize),
   NullBulkString,
-  StringValue(String),
+  StringValue(Bytes),
+  SimpleString(Bytes),
+  Error(Bytes),
+  Integer(i64),
+  Null,
+  Double(f64),
+  Boolean(bool),
+  BigNumber(String),
+  VerbatimString(usize),
+  Map(usize),
+  Set(usize),
+  Attribute(usize),
+  Push(usize),
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum RespValue {
   Array(Vec<RespValue>),
-  BulkString(String),
+  NullArray,
+  BulkString(Bytes),
   NullBulkString,
+  SimpleString(Bytes),
+  Error(Bytes),
+  Integer(i64),
+  Null,
+  Double(f64),
+  Boolean(bool),
+  BigNumber(String),
+  VerbatimString {
+    format: Bytes,
+    text: Bytes,
+  },
+  Map(Vec<(RespValue, RespValue)>),
+  Set(Vec<RespValue>),
+  Attribute {
+    attributes: Vec<(RespValue, RespValue)>,
+    value: Box<RespValue>,
+  },
+  Push(Vec<RespValue>),
 }
 
 const NULL_BULK_STRING: &str = "$-1";
+
+/// The Redis version advertised to clients, which use it to decide which features to rely on.
+pub const REDIS_VERSION: &str = "7.4.0";
@@ -1,41 +1,447 @@
+mod append;
+mod blmove;
+mod blocking;
+mod bpop;
+mod bzpop;
+mod command_cmd;
+mod copy;
+mod debug;
+mod del;
 mod echo;
+mod exists;
+mod expire;
+mod expire_time_cmd;
 mod get;
+mod getdel;
+mod getex;
+mod getrange;
+mod hdel;
+mod hello;
+mod hexists;
+mod hexpire;
+mod hexpiretime;
+mod hget;
+mod hgetall;
+mod hincrby;
+mod hincrbyfloat;
+mod hkeys;
+mod hlen;
+mod hmget;
+mod hpersist;
+mod hrandfield;
+mod hset;
+mod hsetnx;
+mod hstrlen;
+mod httl;
+mod incrby;
+mod incrbyfloat;
+mod info;
+mod lcs;
+mod lindex;
+mod linsert;
+mod llen;
+mod lmove;
+mod lmpop;
+mod lpos;
+mod lrange;
+mod lrem;
+mod lset;
+mod ltrim;
+mod mget;
+mod mset;
+mod persist;
 mod ping;
+mod pop;
+mod push;
+mod rename;
+mod sadd;
+mod scard;
 mod set;
+mod set_algebra;
+mod setnx;
+mod setrange;
+mod sintercard;
+mod sismember;
+mod smembers;
+mod smismember;
+mod smove;
+mod spop;
+mod srandmember;
+mod srem;
+mod stream_id;
+mod strlen;
+mod touch;
+mod ttl;
+mod type_cmd;
+mod unlink;
+mod xack;
+mod xadd;
+mod xautoclaim;
+mod xclaim;
+mod xdel;
+mod xgroup;
+mod xinfo;
+mod xlen;
+mod xpending;
+mod xrange;
+mod xread;
+mod xreadgroup;
+mod xtrim;
+mod zadd;
+mod zcard;
+mod zcount;
+mod zincrby;
+mod zmpop;
+mod zpop;
+mod zrange;
+mod zrank;
+mod zrem;
+mod zscore;
+mod zset_algebra;
 
+use std::cell::RefCell;
 use std::sync::{Arc, Mutex};
 
+pub use append::*;
+pub use blmove::*;
+pub use blocking::*;
+pub use bpop::*;
+pub use bzpop::*;
+pub use command_cmd::*;
+pub use copy::*;
+pub use debug::*;
+pub use del::*;
 pub use echo::*;
+pub use exists::*;
+pub use expire::*;
+pub use expire_time_cmd::*;
 pub use get::*;
+pub use getdel::*;
+pub use getex::*;
+pub use getrange::*;
+pub use hdel::*;
+pub use hello::*;
+pub use hexists::*;
+pub use hexpire::*;
+pub use hexpiretime::*;
+pub use hget::*;
+pub use hgetall::*;
+pub use hincrby::*;
+pub use hincrbyfloat::*;
+pub use hkeys::*;
+pub use hlen::*;
+pub use hmget::*;
+pub use hpersist::*;
+pub use hrandfield::*;
+pub use hset::*;
+pub use hsetnx::*;
+pub use hstrlen::*;
+pub use httl::*;
+pub use incrby::*;
+pub use incrbyfloat::*;
+pub use info::*;
+pub use lcs::*;
+pub use lindex::*;
+pub use linsert::*;
+pub use llen::*;
+pub use lmove::*;
+pub use lmpop::*;
+pub use lpos::*;
+pub use lrange::*;
+pub use lrem::*;
+pub use lset::*;
+pub use ltrim::*;
+pub use mget::*;
+pub use mset::*;
+pub use persist::*;
 pub use ping::*;
+pub use pop::*;
+pub use push::*;
+pub use rename::*;
+pub use sadd::*;
+pub use scard::*;
 pub use set::*;
+pub use set_algebra::*;
+pub use setnx::*;
+pub use setrange::*;
+pub use sintercard::*;
+pub use sismember::*;
+pub use smembers::*;
+pub use smismember::*;
+pub use smove::*;
+pub use spop::*;
+pub use srandmember::*;
+pub use srem::*;
+pub use stream_id::*;
+pub use strlen::*;
+pub use touch::*;
+pub use ttl::*;
+pub use type_cmd::*;
+pub use unlink::*;
+pub use xack::*;
+pub use xadd::*;
+pub use xautoclaim::*;
+pub use xclaim::*;
+pub use xdel::*;
+pub use xgroup::*;
+pub use xinfo::*;
+pub use xlen::*;
+pub use xpending::*;
+pub use xrange::*;
+pub use xread::*;
+pub use xreadgroup::*;
+pub use xtrim::*;
+pub use zadd::*;
+pub use zcard::*;
+pub use zcount::*;
+pub use zincrby::*;
+pub use zmpop::*;
+pub use zpop::*;
+pub use zrange::*;
+pub use zrank::*;
+pub use zrem::*;
+pub use zscore::*;
+pub use zset_algebra::*;
+
+use bytes::Bytes;
 
 use crate::database::Database;
-use crate::resp_server::Result;
+use crate::resp_server::{bail, RedisError, Reply, Result, Session};
+
+/// Builds a command from the arguments following its name.
+/// The arity declared in the command table is checked before this is called.
+pub trait Parse: Sized {
+  fn parse(args: &[Bytes]) -> Result<Self>;
+}
+
+/// Parses an integer argument, failing with the error Redis replies for it.
+pub fn parse_integer(arg: &[u8]) -> Result<i64> {
+  let Ok(integer) = String::from_utf8_lossy(arg).parse() else {
+    bail!(RedisError::NotInteger);
+  };
+  Ok(integer)
+}
+
+/// Parses a float argument the way Redis does: `inf` and `-inf` are accepted, NaN is not.
+pub fn parse_float(arg: &[u8]) -> Result<f64> {
+  match String::from_utf8_lossy(arg).parse::<f64>() {
+    Ok(float) if !float.is_nan() => Ok(float),
+    _ => bail!(RedisError::NotFloat),
+  }
+}
 
 pub trait Execute {
-  fn execute(&self, ctx: &ExecutionContext) -> Result<Vec<u8>>;
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply>;
 }
 
 pub struct ExecutionContext<'a> {
   pub db: &'a Arc<Mutex<Database>>,
+  pub session: &'a RefCell<Session>,
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum Command {
   Ping(Ping),
   Echo(Echo),
   Set(Set),
   Get(Get),
+  Hello(Hello),
+  Command(CommandCmd),
+  Del(Del),
+  Unlink(Unlink),
+  Exists(Exists),
+  Touch(Touch),
+  Type(TypeCmd),
+  Rename(Rename),
+  Copy(Copy),
+  Expire(Expire),
+  ExpireTime(ExpireTimeCmd),
+  Persist(Persist),
+  Ttl(Ttl),
+  Info(Info),
+  Debug(DebugCmd),
+  Lindex(Lindex),
+  Linsert(Linsert),
+  Llen(Llen),
+  Lmove(Lmove),
+  Lpos(Lpos),
+  Lrange(Lrange),
+  Lrem(Lrem),
+  Lset(Lset),
+  Ltrim(Ltrim),
+  Pop(Pop),
+  Push(Push),
+  Blmove(Blmove),
+  Bpop(Bpop),
+  Lmpop(Lmpop),
+  Hdel(Hdel),
+  Hexists(Hexists),
+  Hget(Hget),
+  Hgetall(Hgetall),
+  Hincrby(Hincrby),
+  Hincrbyfloat(Hincrbyfloat),
+  Hkeys(Hkeys),
+  Hlen(Hlen),
+  Hmget(Hmget),
+  Hrandfield(Hrandfield),
+  Hset(Hset),
+  Hsetnx(Hsetnx),
+  Hstrlen(Hstrlen),
+  Hexpire(Hexpire),
+  Hexpiretime(Hexpiretime),
+  Hpersist(Hpersist),
+  Httl(Httl),
+  Sadd(Sadd),
+  Scard(Scard),
+  SetAlgebra(SetAlgebra),
+  Sintercard(Sintercard),
+  Sismember(Sismember),
+  Smembers(Smembers),
+  Smismember(Smismember),
+  Smove(Smove),
+  Spop(Spop),
+  Srandmember(Srandmember),
+  Srem(Srem),
+  Zadd(Zadd),
+  Zcard(Zcard),
+  Zcount(Zcount),
+  Zincrby(Zincrby),
+  Zpop(Zpop),
+  Zrange(Zrange),
+  Zrank(Zrank),
+  Zrem(Zrem),
+  Zscore(Zscore),
+  ZsetAlgebra(ZsetAlgebra),
+  Bzpop(Bzpop),
+  Zmpop(Zmpop),
+  Xadd(Xadd),
+  Xdel(Xdel),
+  Xlen(Xlen),
+  Xrange(Xrange),
+  Xread(Xread),
+  Xtrim(Xtrim),
+  Xack(Xack),
+  Xautoclaim(Xautoclaim),
+  Xclaim(Xclaim),
+  Xgroup(Xgroup),
+  Xinfo(Xinfo),
+  Xpending(Xpending),
+  Xreadgroup(Xreadgroup),
+  Incrby(Incrby),
+  Incrbyfloat(Incrbyfloat),
+  Append(Append),
+  Strlen(Strlen),
+  Getrange(Getrange),
+  Setrange(Setrange),
+  Lcs(Lcs),
+  Mset(Mset),
+  Mget(Mget),
+  Getdel(Getdel),
+  Getex(Getex),
+  Setnx(Setnx),
 }
 
 impl Execute for Command {
-  fn execute(&self, ctx: &ExecutionContext) -> Result<Vec<u8>> {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
     match self {
       Command::Ping(ping) => ping.execute(ctx),
       Command::Echo(echo) => echo.execute(ctx),
       Command::Set(set) => set.execute(ctx),
       Command::Get(get) => get.execute(ctx),
+      Command::Hello(hello) => hello.execute(ctx),
+      Command::Command(command) => command.execute(ctx),
+      Command::Del(del) => del.execute(ctx),
+      Command::Unlink(unlink) => unlink.execute(ctx),
+      Command::Exists(exists) => exists.execute(ctx),
+      Command::Touch(touch) => touch.execute(ctx),
+      Command::Type(type_cmd) => type_cmd.execute(ctx),
+      Command::Rename(rename) => rename.execute(ctx),
+      Command::Copy(copy) => copy.execute(ctx),
+      Command::Expire(expire) => expire.execute(ctx),
+      Command::ExpireTime(expire_time_cmd) => expire_time_cmd.execute(ctx),
+      Command::Persist(persist) => persist.execute(ctx),
+      Command::Ttl(ttl) => ttl.execute(ctx),
+      Command::Info(info) => info.execute(ctx),
+      Command::Debug(debug_cmd) => debug_cmd.execute(ctx),
+      Command::Lindex(lindex) => lindex.execute(ctx),
+      Command::Linsert(linsert) => linsert.execute(ctx),
+      Command::Llen(llen) => llen.execute(ctx),
+      Command::Lmove(lmove) => lmove.execute(ctx),
+      Command::Lpos(lpos) => lpos.execute(ctx),
+      Command::Lrange(lrange) => lrange.execute(ctx),
+      Command::Lrem(lrem) => lrem.execute(ctx),
+      Command::Lset(lset) => lset.execute(ctx),
+      Command::Ltrim(ltrim) => ltrim.execute(ctx),
+      Command::Pop(pop) => pop.execute(ctx),
+      Command::Push(push) => push.execute(ctx),
+      Command::Blmove(blmove) => blmove.execute(ctx),
+      Command::Bpop(bpop) => bpop.execute(ctx),
+      Command::Lmpop(lmpop) => lmpop.execute(ctx),
+      Command::Hdel(hdel) => hdel.execute(ctx),
+      Command::Hexists(hexists) => hexists.execute(ctx),
+      Command::Hget(hget) => hget.execute(ctx),
+      Command::Hgetall(hgetall) => hgetall.execute(ctx),
+      Command::Hincrby(hincrby) => hincrby.execute(ctx),
+      Command::Hincrbyfloat(hincrbyfloat) => hincrbyfloat.execute(ctx),
+      Command::Hkeys(hkeys) => hkeys.execute(ctx),
+      Command::Hlen(hlen) => hlen.execute(ctx),
+      Command::Hmget(hmget) => hmget.execute(ctx),
+      Command::Hrandfield(hrandfield) => hrandfield.execute(ctx),
+      Command::Hset(hset) => hset.execute(ctx),
+      Command::Hsetnx(hsetnx) => hsetnx.execute(ctx),
+      Command::Hstrlen(hstrlen) => hstrlen.execute(ctx),
+      Command::Hexpire(hexpire) => hexpire.execute(ctx),
+      Command::Hexpiretime(hexpiretime) => hexpiretime.execute(ctx),
+      Command::Hpersist(hpersist) => hpersist.execute(ctx),
+      Command::Httl(httl) => httl.execute(ctx),
+      Command::Sadd(sadd) => sadd.execute(ctx),
+      Command::Scard(scard) => scard.execute(ctx),
+      Command::SetAlgebra(set_algebra) => set_algebra.execute(ctx),
+      Command::Sintercard(sintercard) => sintercard.execute(ctx),
+      Command::Sismember(sismember) => sismember.execute(ctx),
+      Command::Smembers(smembers) => smembers.execute(ctx),
+      Command::Smismember(smismember) => smismember.execute(ctx),
+      Command::Smove(smove) => smove.execute(ctx),
+      Command::Spop(spop) => spop.execute(ctx),
+      Command::Srandmember(srandmember) => srandmember.execute(ctx),
+      Command::Srem(srem) => srem.execute(ctx),
+      Command::Zadd(zadd) => zadd.execute(ctx),
+      Command::Zcard(zcard) => zcard.execute(ctx),
+      Command::Zcount(zcount) => zcount.execute(ctx),
+      Command::Zincrby(zincrby) => zincrby.execute(ctx),
+      Command::Zpop(zpop) => zpop.execute(ctx),
+      Command::Zrange(zrange) => zrange.execute(ctx),
+      Command::Zrank(zrank) => zrank.execute(ctx),
+      Command::Zrem(zrem) => zrem.execute(ctx),
+      Command::Zscore(zscore) => zscore.execute(ctx),
+      Command::ZsetAlgebra(zset_algebra) => zset_algebra.execute(ctx),
+      Command::Bzpop(bzpop) => bzpop.execute(ctx),
+      Command::Zmpop(zmpop) => zmpop.execute(ctx),
+      Command::Xadd(xadd) => xadd.execute(ctx),
+      Command::Xdel(xdel) => xdel.execute(ctx),
+      Command::Xlen(xlen) => xlen.execute(ctx),
+      Command::Xrange(xrange) => xrange.execute(ctx),
+      Command::Xread(xread) => xread.execute(ctx),
+      Command::Xtrim(xtrim) => xtrim.execute(ctx),
+      Command::Xack(xack) => xack.execute(ctx),
+      Command::Xautoclaim(xautoclaim) => xautoclaim.execute(ctx),
+      Command::Xclaim(xclaim) => xclaim.execute(ctx),
+      Command::Xgroup(xgroup) => xgroup.execute(ctx),
+      Command::Xinfo(xinfo) => xinfo.execute(ctx),
+      Command::Xpending(xpending) => xpending.execute(ctx),
+      Command::Xreadgroup(xreadgroup) => xreadgroup.execute(ctx),
+      Command::Incrby(incrby) => incrby.execute(ctx),
+      Command::Incrbyfloat(incrbyfloat) => incrbyfloat.execute(ctx),
+      Command::Append(append) => append.execute(ctx),
+      Command::Strlen(strlen) => strlen.execute(ctx),
+      Command::Getrange(getrange) => getrange.execute(ctx),
+      Command::Setrange(setrange) => setrange.execute(ctx),
+      Command::Lcs(lcs) => lcs.execute(ctx),
+      Command::Mset(mset) => mset.execute(ctx),
+      Command::Mget(mget) => mget.execute(ctx),
+      Command::Getdel(getdel) => getdel.execute(ctx),
+      Command::Getex(getex) => getex.execute(ctx),
+      Command::Setnx(setnx) => setnx.execute(ctx),
     }
   }
 }
@@ -0,0 +1,45 @@
+use bytes::Bytes;
+
+use crate::database::{Data, StringValue, Value};
+use crate::resp_server::{Reply, Result};
+
+use super::{check_string_length, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Append {
+  pub key: Bytes,
+  pub value: Bytes,
+}
+
+impl Execute for Append {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Append { key, value } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(string) = db.get_value_mut::<StringValue>(key)? else {
+      let data = Data {
+        value: Value::from(value.clone()),
+        expire_time: None,
+      };
+      db.set(key, &data);
+      return Ok(Reply::Integer(value.len() as i64));
+    };
+    check_string_length(string.len() + value.len())?;
+
+    let mut appended = string.to_bytes().to_vec();
+    appended.extend_from_slice(value);
+    let len = appended.len();
+    *string = StringValue::Raw(Bytes::from(appended));
+    Ok(Reply::Integer(len as i64))
+  }
+}
+
+impl Parse for Append {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Append {
+      key: args[0].clone(),
+      value: args[1].clone(),
+    })
+  }
+}
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use crate::database::Database;
+use crate::resp_server::{Reply, Result};
+
+use super::{move_element, parse_timeout, serve_or_block, Execute, ExecutionContext, ListEnd};
+use super::{Lmove, Parse};
+
+/// `BLMOVE`, which `BRPOPLPUSH` is a special case of.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Blmove {
+  pub lmove: Lmove,
+  /// `None` means waiting forever
+  pub timeout: Option<Duration>,
+}
+
+impl Execute for Blmove {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Blmove { lmove, timeout } = self;
+
+    let Lmove {
+      source,
+      destination,
+      from,
+      to,
+    } = lmove.clone();
+    let serve = move |db: &mut Database| {
+      let element = move_element(db, &source, &destination, from, to)?;
+      Ok(element.map(Reply::BulkString))
+    };
+    serve_or_block(
+      ctx,
+      std::slice::from_ref(&lmove.source),
+      *timeout,
+      Reply::NullBulkString,
+      serve,
+    )
+  }
+}
+
+impl Parse for Blmove {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Blmove {
+      lmove: Lmove::parse(&args[..4])?,
+      timeout: parse_timeout(&args[4])?,
+    })
+  }
+}
+
+impl Blmove {
+  /// `BRPOPLPUSH source destination timeout`
+  pub fn parse_brpoplpush(args: &[Bytes]) -> Result<Self> {
+    Ok(Blmove {
+      lmove: Lmove {
+        source: args[0].clone(),
+        destination: args[1].clone(),
+        from: ListEnd::Right,
+        to: ListEnd::Left,
+      },
+      timeout: parse_timeout(&args[2])?,
+    })
+  }
+}
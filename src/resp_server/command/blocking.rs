@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use tokio::time::Instant;
+
+use crate::database::Database;
+use crate::resp_server::{bail, error_reply, Blocked, RedisError, Reply, Result};
+
+use super::ExecutionContext;
+
+/// The longest a blocking command may wait, the same bound as the expire times: `i64::MAX`
+/// milliseconds.
+const MAX_TIMEOUT: Duration = Duration::from_millis(i64::MAX as u64);
+
+/// Parses the timeout of a blocking command, in seconds with an optional fraction.
+/// `None` means waiting forever, which is what a timeout of 0 asks for.
+pub fn parse_timeout(arg: &[u8]) -> Result<Option<Duration>> {
+  let Ok(timeout) = String::from_utf8_lossy(arg).parse::<f64>() else {
+    bail!(RedisError::Generic(
+      "timeout is not a float or out of range".to_owned()
+    ));
+  };
+  if timeout < 0.0 {
+    bail!(RedisError::Generic("timeout is negative".to_owned()));
+  }
+  if timeout == 0.0 {
+    return Ok(None);
+  }
+  match Duration::try_from_secs_f64(timeout) {
+    Ok(timeout) if timeout <= MAX_TIMEOUT => Ok(Some(timeout)),
+    _ => bail!(RedisError::Generic("timeout is out of range".to_owned())),
+  }
+}
+
+/// Parses the timeout of a command that takes it in milliseconds, like `XREAD BLOCK`.
+/// `None` means waiting forever, which is what a timeout of 0 asks for.
+pub fn parse_timeout_millis(arg: &[u8]) -> Result<Option<Duration>> {
+  let Ok(timeout) = String::from_utf8_lossy(arg).parse::<i64>() else {
+    bail!(RedisError::Generic(
+      "timeout is not an integer or out of range".to_owned()
+    ));
+  };
+  match u64::try_from(timeout) {
+    Err(_) => bail!(RedisError::Generic("timeout is negative".to_owned())),
+    Ok(0) => Ok(None),
+    Ok(timeout) => Ok(Some(Duration::from_millis(timeout))),
+  }
+}
+
+/// Replies right away when `serve` can. Otherwise the client is parked on `keys` until a write by
+/// another client lets `serve` succeed, or until `timeout` passes and it gets `timeout_reply`.
+pub fn serve_or_block<F>(
+  ctx: &ExecutionContext,
+  keys: &[Bytes],
+  timeout: Option<Duration>,
+  timeout_reply: Reply,
+  mut serve: F,
+) -> Result<Reply>
+where
+  F: FnMut(&mut Database) -> Result<Option<Reply>> + Send + 'static,
+{
+  let ExecutionContext { db, session } = ctx;
+
+  // resolved before taking the lock, so an unreachable deadline can't panic while holding it
+  let deadline = match timeout {
+    Some(timeout) => match Instant::now().checked_add(timeout) {
+      Some(deadline) => Some(deadline),
+      None => bail!(RedisError::Generic("timeout is out of range".to_owned())),
+    },
+    None => None,
+  };
+  let mut db = db.lock().unwrap();
+  if let Some(reply) = serve(&mut db)? {
+    return Ok(reply);
+  }
+
+  let (id, receiver) = db.block(
+    keys.to_vec(),
+    Box::new(move |db| match serve(db) {
+      Ok(reply) => reply,
+      // like in Redis, a key holding another type only means there's nothing to serve yet
+      Err(err) if matches!(err.downcast_ref(), Some(RedisError::WrongType)) => None,
+      Err(err) => Some(error_reply(&err)),
+    }),
+  );
+  session.borrow_mut().blocked = Some(Blocked {
+    id,
+    receiver,
+    deadline,
+    timeout_reply,
+    pending: vec![],
+  });
+  // never sent: the client gets the reply `serve` produces once it is unblocked
+  Ok(Reply::Null)
+}
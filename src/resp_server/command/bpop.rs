@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use crate::database::Database;
+use crate::resp_server::{Reply, Result};
+
+use super::{parse_timeout, pop_from_first_list, serve_or_block, Execute, ExecutionContext};
+use super::{ListEnd, Parse};
+
+/// `BLPOP`, or `BRPOP` when `end` is `Right`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Bpop {
+  pub keys: Vec<Bytes>,
+  pub end: ListEnd,
+  /// `None` means waiting forever
+  pub timeout: Option<Duration>,
+}
+
+impl Execute for Bpop {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Bpop { keys, end, timeout } = self;
+
+    let (keys_to_pop, end) = (keys.clone(), *end);
+    let serve = move |db: &mut Database| {
+      let popped = pop_from_first_list(db, &keys_to_pop, end, 1)?;
+      Ok(popped.map(|(key, elements)| {
+        let element = elements.into_iter().next().expect("lists are never empty");
+        Reply::Array(vec![Reply::BulkString(key), Reply::BulkString(element)])
+      }))
+    };
+    serve_or_block(ctx, keys, *timeout, Reply::NullArray, serve)
+  }
+}
+
+impl Parse for Bpop {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let (timeout, keys) = args.split_last().expect("arity is checked");
+    Ok(Bpop {
+      keys: keys.to_vec(),
+      end: ListEnd::Left,
+      timeout: parse_timeout(timeout)?,
+    })
+  }
+}
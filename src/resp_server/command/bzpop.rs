@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use crate::database::Database;
+use crate::resp_server::{Reply, Result};
+
+use super::Parse;
+use super::{parse_timeout, pop_from_first_zset, serve_or_block, Execute, ExecutionContext};
+
+/// `BZPOPMIN`, or `BZPOPMAX` when `max` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Bzpop {
+  pub keys: Vec<Bytes>,
+  pub max: bool,
+  /// `None` means waiting forever
+  pub timeout: Option<Duration>,
+}
+
+impl Execute for Bzpop {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Bzpop { keys, max, timeout } = self;
+
+    let (keys_to_pop, max) = (keys.clone(), *max);
+    let serve = move |db: &mut Database| {
+      let popped = pop_from_first_zset(db, &keys_to_pop, max, 1)?;
+      Ok(popped.map(|(key, members)| {
+        let (member, score) = members
+          .into_iter()
+          .next()
+          .expect("sorted sets are never empty");
+        Reply::Array(vec![
+          Reply::BulkString(key),
+          Reply::BulkString(member),
+          Reply::Double(score),
+        ])
+      }))
+    };
+    serve_or_block(ctx, keys, *timeout, Reply::NullArray, serve)
+  }
+}
+
+impl Parse for Bzpop {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let (timeout, keys) = args.split_last().expect("arity is checked");
+    Ok(Bzpop {
+      keys: keys.to_vec(),
+      max: false,
+      timeout: parse_timeout(timeout)?,
+    })
+  }
+}
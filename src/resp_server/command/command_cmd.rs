@@ -0,0 +1,96 @@
+use bytes::Bytes;
+
+use crate::resp_server::command_table::{lookup, COMMAND_TABLE};
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+/// `COMMAND` and its subcommands, all served from the command table.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum CommandCmd {
+  List,
+  Count,
+  Info(Vec<Bytes>),
+  Docs(Vec<Bytes>),
+  GetKeys(Vec<Bytes>),
+}
+
+impl Execute for CommandCmd {
+  fn execute(&self, _ctx: &ExecutionContext) -> Result<Reply> {
+    Ok(match self {
+      CommandCmd::List => Reply::Array(COMMAND_TABLE.iter().map(|spec| spec.info()).collect()),
+      CommandCmd::Count => Reply::Integer(COMMAND_TABLE.len() as i64),
+      CommandCmd::Info(names) if names.is_empty() => {
+        Reply::Array(COMMAND_TABLE.iter().map(|spec| spec.info()).collect())
+      }
+      CommandCmd::Info(names) => Reply::Array(
+        names
+          .iter()
+          .map(|name| lookup(name).map_or(Reply::NullArray, |spec| spec.info()))
+          .collect(),
+      ),
+      CommandCmd::Docs(names) => {
+        let specs: Vec<_> = if names.is_empty() {
+          COMMAND_TABLE.iter().collect()
+        } else {
+          names.iter().filter_map(|name| lookup(name)).collect()
+        };
+        Reply::Map(
+          specs
+            .into_iter()
+            .map(|spec| {
+              let name = Reply::BulkString(Bytes::from_static(spec.name.as_bytes()));
+              (name, spec.docs())
+            })
+            .collect(),
+        )
+      }
+      CommandCmd::GetKeys(argv) => {
+        let Some(spec) = lookup(&argv[0]) else {
+          bail!(RedisError::Generic("Invalid command specified".to_owned()));
+        };
+        if !spec.accepts_arity(argv.len()) {
+          bail!(RedisError::Generic(
+            "Invalid number of arguments specified for command".to_owned()
+          ));
+        }
+        let keys = spec.keys(argv);
+        if keys.is_empty() {
+          bail!(RedisError::Generic(
+            "The command has no key arguments".to_owned()
+          ));
+        }
+        Reply::Array(
+          keys
+            .into_iter()
+            .map(|key| Reply::BulkString(key.clone()))
+            .collect(),
+        )
+      }
+    })
+  }
+}
+
+impl Parse for CommandCmd {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let Some((subcommand, rest)) = args.split_first() else {
+      return Ok(CommandCmd::List);
+    };
+
+    let subcommand_str = String::from_utf8_lossy(subcommand).to_uppercase();
+    match (subcommand_str.as_str(), rest.len()) {
+      ("COUNT", 0) => Ok(CommandCmd::Count),
+      ("INFO", _) => Ok(CommandCmd::Info(rest.to_vec())),
+      ("DOCS", _) => Ok(CommandCmd::Docs(rest.to_vec())),
+      ("GETKEYS", 1..) => Ok(CommandCmd::GetKeys(rest.to_vec())),
+      ("COUNT" | "GETKEYS", _) => bail!(RedisError::WrongArity(format!(
+        "command|{}",
+        subcommand_str.to_lowercase()
+      ))),
+      _ => bail!(RedisError::Generic(format!(
+        "unknown subcommand '{}'. Try COMMAND HELP.",
+        String::from_utf8_lossy(subcommand)
+      ))),
+    }
+  }
+}
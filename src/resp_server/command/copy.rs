@@ -0,0 +1,70 @@
+use bytes::Bytes;
+
+use super::{Execute, ExecutionContext, Parse};
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Copy {
+  pub source: Bytes,
+  pub destination: Bytes,
+  pub replace: bool,
+}
+
+impl Execute for Copy {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Copy {
+      source,
+      destination,
+      replace,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+    let mut db = db.lock().unwrap();
+
+    if source == destination {
+      bail!(RedisError::Generic(
+        "source and destination objects are the same".to_owned()
+      ));
+    }
+    let Some(data) = db.get(source).cloned() else {
+      return Ok(Reply::Integer(0));
+    };
+    if !replace && db.contains_key(destination) {
+      return Ok(Reply::Integer(0));
+    }
+
+    db.set(destination, &data);
+    Ok(Reply::Integer(1))
+  }
+}
+
+impl Parse for Copy {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let mut copy_cmd = Copy {
+      source: args[0].clone(),
+      destination: args[1].clone(),
+      replace: false,
+    };
+
+    let mut cmd_iter = args[2..].iter();
+    while let Some(option) = cmd_iter.next() {
+      match option.to_ascii_uppercase().as_slice() {
+        b"REPLACE" => copy_cmd.replace = true,
+        b"DB" => {
+          let Some(db_index) = cmd_iter.next() else {
+            bail!(RedisError::Syntax);
+          };
+          let Ok(db_index) = String::from_utf8_lossy(db_index).parse::<i64>() else {
+            bail!(RedisError::NotInteger);
+          };
+          // only the default database exists
+          if db_index != 0 {
+            bail!(RedisError::Generic("DB index is out of range".to_owned()));
+          }
+        }
+        _ => bail!(RedisError::Syntax),
+      }
+    }
+
+    Ok(copy_cmd)
+  }
+}
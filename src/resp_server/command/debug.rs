@@ -0,0 +1,41 @@
+use bytes::Bytes;
+
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+/// `DEBUG`, limited to the subcommands tests rely on.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum DebugCmd {
+  /// `DEBUG SET-ACTIVE-EXPIRE <0|1>` turns the active expire cycle off or on,
+  /// leaving expired keys to be deleted on access only
+  SetActiveExpire(bool),
+}
+
+impl Execute for DebugCmd {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let ExecutionContext { db, .. } = ctx;
+    match self {
+      DebugCmd::SetActiveExpire(enabled) => db.lock().unwrap().set_active_expire(*enabled),
+    }
+    Ok(Reply::ok())
+  }
+}
+
+impl Parse for DebugCmd {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let subcommand = String::from_utf8_lossy(&args[0]).to_uppercase();
+    match (subcommand.as_str(), &args[1..]) {
+      ("SET-ACTIVE-EXPIRE", [enabled]) => {
+        let Ok(enabled) = String::from_utf8_lossy(enabled).parse::<i64>() else {
+          bail!(RedisError::NotInteger);
+        };
+        Ok(DebugCmd::SetActiveExpire(enabled != 0))
+      }
+      _ => bail!(RedisError::Generic(format!(
+        "unknown subcommand or wrong number of arguments for '{}'. Try DEBUG HELP.",
+        String::from_utf8_lossy(&args[0])
+      ))),
+    }
+  }
+}
@@ -0,0 +1,27 @@
+use bytes::Bytes;
+
+use super::{Execute, ExecutionContext, Parse};
+use crate::resp_server::{Reply, Result};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Del {
+  pub keys: Vec<Bytes>,
+}
+
+impl Execute for Del {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Del { keys } = self;
+    let ExecutionContext { db, .. } = ctx;
+    let mut db = db.lock().unwrap();
+    let count = keys.iter().filter(|key| db.remove(key).is_some()).count();
+    Ok(Reply::Integer(count as i64))
+  }
+}
+
+impl Parse for Del {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Del {
+      keys: args.to_vec(),
+    })
+  }
+}
@@ -1,13 +1,23 @@
-use super::Result;
-use super::{Execute, ExecutionContext};
+use bytes::Bytes;
+
+use super::{Execute, ExecutionContext, Parse};
+use super::{Reply, Result};
 
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Echo {
-  pub message: String,
+  pub message: Bytes,
 }
 
 impl Execute for Echo {
-  fn execute(&self, _ctx: &ExecutionContext) -> Result<Vec<u8>> {
-    Ok(format!("+{}\r\n", self.message).into_bytes())
+  fn execute(&self, _ctx: &ExecutionContext) -> Result<Reply> {
+    Ok(Reply::BulkString(self.message.clone()))
+  }
+}
+
+impl Parse for Echo {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Echo {
+      message: args[0].clone(),
+    })
   }
 }
@@ -0,0 +1,27 @@
+use bytes::Bytes;
+
+use super::{Execute, ExecutionContext, Parse};
+use crate::resp_server::{Reply, Result};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Exists {
+  pub keys: Vec<Bytes>,
+}
+
+impl Execute for Exists {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Exists { keys } = self;
+    let ExecutionContext { db, .. } = ctx;
+    let mut db = db.lock().unwrap();
+    let count = keys.iter().filter(|key| db.contains_key(key)).count();
+    Ok(Reply::Integer(count as i64))
+  }
+}
+
+impl Parse for Exists {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Exists {
+      keys: args.to_vec(),
+    })
+  }
+}
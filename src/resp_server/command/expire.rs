@@ -0,0 +1,129 @@
+use bytes::Bytes;
+
+use crate::database::ExpireTime;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{calculate_expire_time, Execute, ExecutionContext, Parse};
+
+/// `EXPIRE`, `PEXPIRE`, `EXPIREAT` or `PEXPIREAT`, depending on `unit`, which takes the same
+/// values as the matching `SET` options: `EX`, `PX`, `EXAT` or `PXAT`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Expire {
+  pub key: Bytes,
+  pub unit: String,
+  pub amount: i64,
+  pub condition: ExpireCondition,
+}
+
+/// The `NX`, `XX`, `GT` and `LT` flags shared by the commands that set an expire time.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct ExpireCondition {
+  pub nx: bool,
+  pub xx: bool,
+  pub gt: bool,
+  pub lt: bool,
+}
+
+impl Execute for Expire {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Expire {
+      key,
+      unit,
+      amount,
+      condition,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let now = db.now();
+    let expire_time = match u64::try_from(*amount) {
+      Ok(amount) => calculate_expire_time(now, unit, amount).ok(),
+      // a negative time is always in the past
+      Err(_) => Some(0),
+    };
+    let Some(expire_time) = expire_time else {
+      bail!(RedisError::InvalidExpireTime(
+        self.command_name().to_owned()
+      ));
+    };
+
+    let Some(current_expire_time) = db.get(key).map(|data| data.expire_time) else {
+      return Ok(Reply::Integer(0));
+    };
+    if !condition.allows(current_expire_time, expire_time) {
+      return Ok(Reply::Integer(0));
+    }
+
+    if expire_time <= now {
+      db.remove(key);
+    } else {
+      db.set_expire_time(key, Some(expire_time));
+    }
+    Ok(Reply::Integer(1))
+  }
+}
+
+impl Parse for Expire {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let Ok(amount) = String::from_utf8_lossy(&args[1]).parse::<i64>() else {
+      bail!(RedisError::NotInteger);
+    };
+    let mut condition = ExpireCondition::default();
+    for option in &args[2..] {
+      condition.set_option(option)?;
+    }
+    Ok(Expire {
+      key: args[0].clone(),
+      unit: "EX".to_owned(),
+      amount,
+      condition,
+    })
+  }
+}
+
+impl Expire {
+  fn command_name(&self) -> &'static str {
+    match self.unit.as_str() {
+      "PX" => "pexpire",
+      "EXAT" => "expireat",
+      "PXAT" => "pexpireat",
+      _ => "expire",
+    }
+  }
+}
+
+impl ExpireCondition {
+  pub fn set_option(&mut self, option: &[u8]) -> Result<()> {
+    let option_str = String::from_utf8_lossy(option).to_uppercase();
+    match option_str.as_str() {
+      "NX" => self.nx = true,
+      "XX" => self.xx = true,
+      "GT" => self.gt = true,
+      "LT" => self.lt = true,
+      _ => bail!(RedisError::Generic(format!(
+        "Unsupported option {}",
+        String::from_utf8_lossy(option)
+      ))),
+    }
+
+    if self.nx && (self.xx || self.gt || self.lt) {
+      bail!(RedisError::Generic(
+        "NX and XX, GT or LT options at the same time are not compatible".to_owned()
+      ));
+    }
+    if self.gt && self.lt {
+      bail!(RedisError::Generic(
+        "GT and LT options at the same time are not compatible".to_owned()
+      ));
+    }
+    Ok(())
+  }
+
+  /// Whether `new` may replace `current`; a missing expire time counts as an infinite one.
+  pub fn allows(&self, current: Option<ExpireTime>, new: ExpireTime) -> bool {
+    match current {
+      None => !self.xx && !self.gt,
+      Some(current) => !self.nx && (!self.gt || new > current) && (!self.lt || new < current),
+    }
+  }
+}
@@ -0,0 +1,43 @@
+use bytes::Bytes;
+
+use crate::resp_server::{Context, Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+/// `EXPIRETIME`, or `PEXPIRETIME` when `in_millis` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ExpireTimeCmd {
+  pub key: Bytes,
+  pub in_millis: bool,
+}
+
+impl Execute for ExpireTimeCmd {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let ExpireTimeCmd { key, in_millis } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let expire_time = match db.get(key) {
+      None => -2,
+      Some(data) => match data.expire_time {
+        None => -1,
+        Some(expire_time) if *in_millis => {
+          i64::try_from(expire_time).context("expire time out of range")?
+        }
+        Some(expire_time) => {
+          i64::try_from(expire_time / 1000).context("expire time out of range")?
+        }
+      },
+    };
+    Ok(Reply::Integer(expire_time))
+  }
+}
+
+impl Parse for ExpireTimeCmd {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(ExpireTimeCmd {
+      key: args[0].clone(),
+      in_millis: false,
+    })
+  }
+}
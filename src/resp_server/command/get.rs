@@ -1,20 +1,31 @@
-use crate::resp_server::NULL_BULK_STRING;
+use bytes::Bytes;
 
-use super::{Execute, ExecutionContext};
-use crate::resp_server::Result;
+use super::{Execute, ExecutionContext, Parse};
+use crate::database::StringValue;
+use crate::resp_server::{Reply, Result};
 
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Get {
-  pub key: String,
+  pub key: Bytes,
 }
 
 impl Execute for Get {
-  fn execute(&self, ctx: &ExecutionContext) -> Result<Vec<u8>> {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
     let Get { key } = self;
-    let ExecutionContext { db } = ctx;
-    Ok(match db.lock().unwrap().get(key) {
-      Some(data) => format!("+{}\r\n", data.value).into_bytes(),
-      None => format!("{}\r\n", NULL_BULK_STRING).into_bytes(),
+    let ExecutionContext { db, .. } = ctx;
+    let value = db
+      .lock()
+      .unwrap()
+      .get_value::<StringValue>(key)?
+      .map(StringValue::to_bytes);
+    Ok(Reply::from(value))
+  }
+}
+
+impl Parse for Get {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Get {
+      key: args[0].clone(),
     })
   }
 }
@@ -0,0 +1,33 @@
+use bytes::Bytes;
+
+use crate::database::StringValue;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Getdel {
+  pub key: Bytes,
+}
+
+impl Execute for Getdel {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Getdel { key } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let value = db.get_value::<StringValue>(key)?.map(StringValue::to_bytes);
+    if value.is_some() {
+      db.remove(key);
+    }
+    Ok(Reply::from(value))
+  }
+}
+
+impl Parse for Getdel {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Getdel {
+      key: args[0].clone(),
+    })
+  }
+}
@@ -0,0 +1,72 @@
+use bytes::Bytes;
+
+use crate::database::StringValue;
+use crate::resp_server::{bail, Context, RedisError, Reply, Result};
+
+use super::{calculate_expire_time, Execute, ExecutionContext, Parse, Set};
+
+/// `GETEX`, which returns a string and changes its expire time like `SET` would, or removes it.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Getex {
+  pub key: Bytes,
+  /// the `EX`, `PX`, `EXAT` or `PXAT` option with its amount, resolved against the clock on execution
+  pub expire: Option<(String, u64)>,
+  pub persist: bool,
+}
+
+impl Execute for Getex {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Getex {
+      key,
+      expire,
+      persist,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let now = db.now();
+    let Some(value) = db.get_value::<StringValue>(key)?.map(StringValue::to_bytes) else {
+      return Ok(Reply::NullBulkString);
+    };
+    if let Some((option, amount)) = expire {
+      let expire_time = calculate_expire_time(now, option, *amount)
+        .context(RedisError::InvalidExpireTime("getex".to_owned()))?;
+      if expire_time <= now {
+        db.remove(key);
+      } else {
+        db.set_expire_time(key, Some(expire_time));
+      }
+    } else if *persist {
+      db.set_expire_time(key, None);
+    }
+    Ok(Reply::BulkString(value))
+  }
+}
+
+impl Parse for Getex {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let (persist, options): (Vec<_>, Vec<_>) = args[1..]
+      .iter()
+      .cloned()
+      .partition(|option| option.eq_ignore_ascii_case(b"PERSIST"));
+    // the expire options are the same as SET's, but its other options aren't allowed
+    let mut set_cmd = Set::default();
+    set_cmd.set_option(&mut options.iter()).map_err(|err| {
+      match err.downcast_ref::<RedisError>() {
+        Some(RedisError::InvalidExpireTime(_)) => {
+          RedisError::InvalidExpireTime("getex".to_owned()).into()
+        }
+        _ => err,
+      }
+    })?;
+    let persist = !persist.is_empty();
+    if set_cmd.get || set_cmd.nx_or_xx.is_some() || (persist && set_cmd.expire.is_some()) {
+      bail!(RedisError::Syntax);
+    }
+    Ok(Getex {
+      key: args[0].clone(),
+      expire: set_cmd.expire,
+      persist,
+    })
+  }
+}
@@ -0,0 +1,42 @@
+use bytes::Bytes;
+
+use crate::database::StringValue;
+use crate::resp_server::{Reply, Result};
+
+use super::{normalize_range, parse_integer, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Getrange {
+  pub key: Bytes,
+  /// inclusive byte offsets, counting from the end when negative
+  pub start: i64,
+  pub end: i64,
+}
+
+impl Execute for Getrange {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Getrange { key, start, end } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(string) = db.get_value::<StringValue>(key)? else {
+      return Ok(Reply::BulkString(Bytes::new()));
+    };
+    let string = string.to_bytes();
+    let range = match normalize_range(*start, *end, string.len()) {
+      Some((start, end)) => string.slice(start..=end),
+      None => Bytes::new(),
+    };
+    Ok(Reply::BulkString(range))
+  }
+}
+
+impl Parse for Getrange {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Getrange {
+      key: args[0].clone(),
+      start: parse_integer(&args[1])?,
+      end: parse_integer(&args[2])?,
+    })
+  }
+}
@@ -0,0 +1,39 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hdel {
+  pub key: Bytes,
+  pub fields: Vec<Bytes>,
+}
+
+impl Execute for Hdel {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hdel { key, fields } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(hash) = db.get_value_mut::<Hash>(key)? else {
+      return Ok(Reply::Integer(0));
+    };
+    let deleted = fields
+      .iter()
+      .filter(|field| hash.remove(field).is_some())
+      .count();
+    db.remove_if_empty(key);
+    Ok(Reply::Integer(deleted as i64))
+  }
+}
+
+impl Parse for Hdel {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Hdel {
+      key: args[0].clone(),
+      fields: args[1..].to_vec(),
+    })
+  }
+}
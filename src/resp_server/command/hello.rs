@@ -0,0 +1,123 @@
+use bytes::Bytes;
+
+use crate::resp_server::{bail, Result};
+use crate::resp_server::{Context, Protocol, RedisError, Reply, REDIS_VERSION};
+
+use super::{Execute, ExecutionContext, Parse};
+
+/// The only user that exists while no ACL is configured.
+const DEFAULT_USER: &[u8] = b"default";
+
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Hello {
+  pub protocol_version: Option<i64>,
+  pub auth: Option<(Bytes, Bytes)>,
+  pub client_name: Option<Bytes>,
+}
+
+impl Execute for Hello {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hello {
+      protocol_version,
+      auth,
+      client_name,
+    } = self;
+    let ExecutionContext { session, .. } = ctx;
+
+    let protocol = match protocol_version {
+      None => None,
+      Some(2) => Some(Protocol::Resp2),
+      Some(3) => Some(Protocol::Resp3),
+      Some(_) => bail!(RedisError::NoProto),
+    };
+
+    if let Some((username, _password)) = auth {
+      // without ACL the default user has no password, so any password is accepted for it
+      if username != DEFAULT_USER {
+        bail!(RedisError::WrongPass);
+      }
+    }
+
+    if let Some(name) = client_name {
+      if !is_valid_client_name(name) {
+        bail!(RedisError::Generic(
+          "Client names cannot contain spaces, newlines or special characters.".to_owned()
+        ));
+      }
+    }
+
+    let mut session = session.borrow_mut();
+    if let Some(protocol) = protocol {
+      session.protocol = protocol;
+    }
+    if let Some(name) = client_name {
+      session.name = Some(name.clone());
+    }
+
+    Ok(Reply::Map(vec![
+      (bulk("server"), bulk("redis")),
+      (bulk("version"), bulk(REDIS_VERSION)),
+      (bulk("proto"), Reply::Integer(session.protocol.version())),
+      (bulk("id"), Reply::Integer(session.id as i64)),
+      (bulk("mode"), bulk("standalone")),
+      (bulk("role"), bulk("master")),
+      (bulk("modules"), Reply::Array(vec![])),
+    ]))
+  }
+}
+
+impl Parse for Hello {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let mut hello_cmd = Hello::default();
+    let mut cmd_iter = args.iter();
+    if let Some(protocol_version) = cmd_iter.next() {
+      let Ok(protocol_version) = String::from_utf8_lossy(protocol_version).parse() else {
+        bail!(RedisError::Generic(
+          "Protocol version is not an integer or out of range".to_owned()
+        ));
+      };
+      hello_cmd.protocol_version = Some(protocol_version);
+      hello_cmd
+        .set_option(&mut cmd_iter)
+        .context("failed to set option")?;
+    }
+    Ok(hello_cmd)
+  }
+}
+
+impl Hello {
+  pub fn set_option(&mut self, cmd_iter: &mut std::slice::Iter<Bytes>) -> Result<()> {
+    while let Some(option) = cmd_iter.next() {
+      let option_str = String::from_utf8_lossy(option).to_uppercase();
+
+      match option_str.as_str() {
+        "AUTH" => {
+          let (Some(username), Some(password)) = (cmd_iter.next(), cmd_iter.next()) else {
+            bail!(hello_syntax_error(&option_str));
+          };
+          self.auth = Some((username.clone(), password.clone()));
+        }
+        "SETNAME" => {
+          let Some(name) = cmd_iter.next() else {
+            bail!(hello_syntax_error(&option_str));
+          };
+          self.client_name = Some(name.clone());
+        }
+        _ => bail!(hello_syntax_error(&option_str)),
+      }
+    }
+    Ok(())
+  }
+}
+
+fn hello_syntax_error(option: &str) -> RedisError {
+  RedisError::Generic(format!("Syntax error in HELLO option '{}'", option))
+}
+
+fn is_valid_client_name(name: &[u8]) -> bool {
+  name.iter().all(|b| (b'!'..=b'~').contains(b))
+}
+
+fn bulk(string: &str) -> Reply {
+  Reply::BulkString(Bytes::copy_from_slice(string.as_bytes()))
+}
@@ -0,0 +1,34 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hexists {
+  pub key: Bytes,
+  pub field: Bytes,
+}
+
+impl Execute for Hexists {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hexists { key, field } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let exists = db
+      .get_value::<Hash>(key)?
+      .is_some_and(|hash| hash.contains_key(field));
+    Ok(Reply::Integer(exists as i64))
+  }
+}
+
+impl Parse for Hexists {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Hexists {
+      key: args[0].clone(),
+      field: args[1].clone(),
+    })
+  }
+}
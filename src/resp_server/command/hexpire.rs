@@ -0,0 +1,133 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{
+  calculate_expire_time, parse_integer, Execute, ExecutionContext, ExpireCondition, Parse,
+};
+
+/// `HEXPIRE`, `HPEXPIRE`, `HEXPIREAT` or `HPEXPIREAT`, depending on `unit`, which takes the same
+/// values as in `Expire`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hexpire {
+  pub key: Bytes,
+  pub unit: String,
+  pub amount: i64,
+  pub condition: ExpireCondition,
+  pub fields: Vec<Bytes>,
+}
+
+impl Execute for Hexpire {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hexpire {
+      key,
+      unit,
+      amount,
+      condition,
+      fields,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let Ok(amount) = u64::try_from(*amount) else {
+      bail!(RedisError::Generic(
+        "invalid expire time, must be >= 0".to_owned()
+      ));
+    };
+    let mut db = db.lock().unwrap();
+    let now = db.now();
+    let expire_time = calculate_expire_time(now, unit, amount).ok();
+    let Some(expire_time) = expire_time else {
+      bail!(RedisError::InvalidExpireTime(
+        self.command_name().to_owned()
+      ));
+    };
+
+    let Some(hash) = db.get_value_mut::<Hash>(key)? else {
+      return Ok(Reply::Array(vec![Reply::Integer(-2); fields.len()]));
+    };
+    let mut any_set = false;
+    let replies = fields
+      .iter()
+      .map(|field| {
+        let reply = if !hash.contains_key(field) {
+          -2
+        } else if !condition.allows(hash.expire_time(field), expire_time) {
+          0
+        } else if expire_time <= now {
+          hash.remove(field);
+          2
+        } else {
+          hash.set_expire_time(field, Some(expire_time));
+          any_set = true;
+          1
+        };
+        Reply::Integer(reply)
+      })
+      .collect();
+
+    db.remove_if_empty(key);
+    if any_set {
+      db.track_hash_field_expires(key);
+    }
+    Ok(Reply::Array(replies))
+  }
+}
+
+impl Parse for Hexpire {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let amount = parse_integer(&args[1])?;
+    let mut condition = ExpireCondition::default();
+    let fields_at = match String::from_utf8_lossy(&args[2]).to_uppercase().as_str() {
+      "NX" | "XX" | "GT" | "LT" => {
+        condition.set_option(&args[2])?;
+        3
+      }
+      _ => 2,
+    };
+    Ok(Hexpire {
+      key: args[0].clone(),
+      unit: "EX".to_owned(),
+      amount,
+      condition,
+      fields: parse_fields(&args[fields_at..])?,
+    })
+  }
+}
+
+impl Hexpire {
+  fn command_name(&self) -> &'static str {
+    match self.unit.as_str() {
+      "PX" => "hpexpire",
+      "EXAT" => "hexpireat",
+      "PXAT" => "hpexpireat",
+      _ => "hexpire",
+    }
+  }
+}
+
+/// Parses the `FIELDS numfields field [field ...]` arguments that the commands reading or setting
+/// expire times of hash fields end with.
+pub fn parse_fields(args: &[Bytes]) -> Result<Vec<Bytes>> {
+  if !args
+    .first()
+    .is_some_and(|arg| arg.eq_ignore_ascii_case(b"FIELDS"))
+  {
+    bail!(RedisError::Generic(
+      "Mandatory argument FIELDS is missing or not at the right position".to_owned()
+    ));
+  }
+  let numfields = match args.get(1).map(|arg| parse_integer(arg)) {
+    Some(Ok(numfields @ 1..)) => numfields as usize,
+    _ => bail!(RedisError::Generic(
+      "Parameter `numFields` should be greater than 0".to_owned()
+    )),
+  };
+  let fields = &args[2..];
+  if fields.len() != numfields {
+    bail!(RedisError::Generic(
+      "The `numfields` parameter must match the number of arguments".to_owned()
+    ));
+  }
+  Ok(fields.to_vec())
+}
@@ -0,0 +1,53 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{Reply, Result};
+
+use super::{parse_fields, Execute, ExecutionContext, Parse};
+
+/// `HEXPIRETIME`, or `HPEXPIRETIME` when `in_millis` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hexpiretime {
+  pub key: Bytes,
+  pub in_millis: bool,
+  pub fields: Vec<Bytes>,
+}
+
+impl Execute for Hexpiretime {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hexpiretime {
+      key,
+      in_millis,
+      fields,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let hash = db.get_value::<Hash>(key)?;
+    let replies = fields
+      .iter()
+      .map(|field| {
+        let expire_time = match hash.filter(|hash| hash.contains_key(field)) {
+          None => -2,
+          Some(hash) => match hash.expire_time(field) {
+            None => -1,
+            Some(expire_time) if *in_millis => expire_time as i64,
+            Some(expire_time) => (expire_time / 1000) as i64,
+          },
+        };
+        Reply::Integer(expire_time)
+      })
+      .collect();
+    Ok(Reply::Array(replies))
+  }
+}
+
+impl Parse for Hexpiretime {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Hexpiretime {
+      key: args[0].clone(),
+      in_millis: false,
+      fields: parse_fields(&args[1..])?,
+    })
+  }
+}
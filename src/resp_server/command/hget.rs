@@ -0,0 +1,35 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hget {
+  pub key: Bytes,
+  pub field: Bytes,
+}
+
+impl Execute for Hget {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hget { key, field } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let value = db
+      .get_value::<Hash>(key)?
+      .and_then(|hash| hash.get(field))
+      .cloned();
+    Ok(Reply::from(value))
+  }
+}
+
+impl Parse for Hget {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Hget {
+      key: args[0].clone(),
+      field: args[1].clone(),
+    })
+  }
+}
@@ -0,0 +1,41 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+/// Replies with a map, which RESP2 clients receive as a flat array of fields and values.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hgetall {
+  pub key: Bytes,
+}
+
+impl Execute for Hgetall {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hgetall { key } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let entries = db
+      .get_value::<Hash>(key)?
+      .into_iter()
+      .flatten()
+      .map(|(field, value)| {
+        (
+          Reply::BulkString(field.clone()),
+          Reply::BulkString(value.clone()),
+        )
+      })
+      .collect();
+    Ok(Reply::Map(entries))
+  }
+}
+
+impl Parse for Hgetall {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Hgetall {
+      key: args[0].clone(),
+    })
+  }
+}
@@ -0,0 +1,53 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_integer, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hincrby {
+  pub key: Bytes,
+  pub field: Bytes,
+  pub increment: i64,
+}
+
+impl Execute for Hincrby {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hincrby {
+      key,
+      field,
+      increment,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let hash = db.get_value_or_default::<Hash>(key)?;
+    let current = match hash.get(field) {
+      Some(value) => match String::from_utf8_lossy(value).parse::<i64>() {
+        Ok(current) => current,
+        Err(_) => bail!(RedisError::Generic(
+          "hash value is not an integer".to_owned()
+        )),
+      },
+      None => 0,
+    };
+    let Some(new) = current.checked_add(*increment) else {
+      bail!(RedisError::Generic(
+        "increment or decrement would overflow".to_owned()
+      ));
+    };
+    hash.update(field.clone(), Bytes::from(new.to_string()));
+    Ok(Reply::Integer(new))
+  }
+}
+
+impl Parse for Hincrby {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Hincrby {
+      key: args[0].clone(),
+      field: args[1].clone(),
+      increment: parse_integer(&args[2])?,
+    })
+  }
+}
@@ -0,0 +1,53 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{bail, format_double, RedisError, Reply, Result};
+
+use super::{parse_float, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Hincrbyfloat {
+  pub key: Bytes,
+  pub field: Bytes,
+  pub increment: f64,
+}
+
+impl Execute for Hincrbyfloat {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hincrbyfloat {
+      key,
+      field,
+      increment,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let hash = db.get_value_or_default::<Hash>(key)?;
+    let current = match hash.get(field) {
+      Some(value) => match parse_float(value) {
+        Ok(current) => current,
+        Err(_) => bail!(RedisError::Generic("hash value is not a float".to_owned())),
+      },
+      None => 0.0,
+    };
+    let new = current + increment;
+    if !new.is_finite() {
+      bail!(RedisError::Generic(
+        "increment would produce NaN or Infinity".to_owned()
+      ));
+    }
+    let new = Bytes::from(format_double(new));
+    hash.update(field.clone(), new.clone());
+    Ok(Reply::BulkString(new))
+  }
+}
+
+impl Parse for Hincrbyfloat {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Hincrbyfloat {
+      key: args[0].clone(),
+      field: args[1].clone(),
+      increment: parse_float(&args[2])?,
+    })
+  }
+}
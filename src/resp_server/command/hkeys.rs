@@ -0,0 +1,38 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+/// `HKEYS`, or `HVALS` when `values` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hkeys {
+  pub key: Bytes,
+  pub values: bool,
+}
+
+impl Execute for Hkeys {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hkeys { key, values } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let elements = db
+      .get_value::<Hash>(key)?
+      .into_iter()
+      .flatten()
+      .map(|(field, value)| if *values { value } else { field }.clone())
+      .collect::<Vec<_>>();
+    Ok(Reply::from(elements))
+  }
+}
+
+impl Parse for Hkeys {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Hkeys {
+      key: args[0].clone(),
+      values: false,
+    })
+  }
+}
@@ -0,0 +1,30 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hlen {
+  pub key: Bytes,
+}
+
+impl Execute for Hlen {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hlen { key } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let len = db.get_value::<Hash>(key)?.map_or(0, |hash| hash.len());
+    Ok(Reply::Integer(len as i64))
+  }
+}
+
+impl Parse for Hlen {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Hlen {
+      key: args[0].clone(),
+    })
+  }
+}
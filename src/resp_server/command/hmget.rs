@@ -0,0 +1,36 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hmget {
+  pub key: Bytes,
+  pub fields: Vec<Bytes>,
+}
+
+impl Execute for Hmget {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hmget { key, fields } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let hash = db.get_value::<Hash>(key)?;
+    let values = fields
+      .iter()
+      .map(|field| Reply::from(hash.and_then(|hash| hash.get(field)).cloned()))
+      .collect();
+    Ok(Reply::Array(values))
+  }
+}
+
+impl Parse for Hmget {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Hmget {
+      key: args[0].clone(),
+      fields: args[1..].to_vec(),
+    })
+  }
+}
@@ -0,0 +1,48 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{Reply, Result};
+
+use super::{parse_fields, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hpersist {
+  pub key: Bytes,
+  pub fields: Vec<Bytes>,
+}
+
+impl Execute for Hpersist {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hpersist { key, fields } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(hash) = db.get_value_mut::<Hash>(key)? else {
+      return Ok(Reply::Array(vec![Reply::Integer(-2); fields.len()]));
+    };
+    let replies = fields
+      .iter()
+      .map(|field| {
+        let persisted = if !hash.contains_key(field) {
+          -2
+        } else if hash.expire_time(field).is_none() {
+          -1
+        } else {
+          hash.set_expire_time(field, None);
+          1
+        };
+        Reply::Integer(persisted)
+      })
+      .collect();
+    Ok(Reply::Array(replies))
+  }
+}
+
+impl Parse for Hpersist {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Hpersist {
+      key: args[0].clone(),
+      fields: parse_fields(&args[1..])?,
+    })
+  }
+}
@@ -0,0 +1,127 @@
+use bytes::Bytes;
+
+use crate::database::{Database, Hash};
+use crate::resp_server::{bail, Protocol, RedisError, Reply, Result};
+
+use super::{parse_integer, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hrandfield {
+  pub key: Bytes,
+  /// a negative count allows the same field to be returned more than once
+  pub count: Option<i64>,
+  pub with_values: bool,
+}
+
+impl Execute for Hrandfield {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hrandfield {
+      key,
+      count,
+      with_values,
+    } = self;
+    let ExecutionContext { db, session } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(hash) = db.get_value::<Hash>(key)? else {
+      return Ok(match count {
+        Some(_) => Reply::Array(vec![]),
+        None => Reply::NullBulkString,
+      });
+    };
+    let entries = hash
+      .iter()
+      .map(|(field, value)| (field.clone(), value.clone()))
+      .collect::<Vec<_>>();
+
+    let Some(count) = *count else {
+      let (field, _) = &entries[db.random_below(entries.len())];
+      return Ok(Reply::BulkString(field.clone()));
+    };
+    let picked = if count < 0 {
+      pick_with_repetition(&mut db, &entries, count.unsigned_abs())?
+    } else {
+      // a partial Fisher-Yates shuffle picks distinct fields
+      let mut entries = entries;
+      let count = (count as usize).min(entries.len());
+      for i in 0..count {
+        let j = i + db.random_below(entries.len() - i);
+        entries.swap(i, j);
+      }
+      entries.truncate(count);
+      entries
+    };
+
+    if !*with_values {
+      return Ok(Reply::from(
+        picked
+          .into_iter()
+          .map(|(field, _)| field)
+          .collect::<Vec<_>>(),
+      ));
+    }
+    let pairs = picked
+      .into_iter()
+      .map(|(field, value)| [Reply::BulkString(field), Reply::BulkString(value)]);
+    // RESP3 clients get each field with its value in a nested array, RESP2 clients a flat array
+    Ok(Reply::Array(match session.borrow().protocol {
+      Protocol::Resp2 => pairs.flatten().collect(),
+      Protocol::Resp3 => pairs.map(|pair| Reply::Array(pair.to_vec())).collect(),
+    }))
+  }
+}
+
+/// Picks `count` random items, the same one possibly more than once. The picks are collected one
+/// by one rather than into an allocation sized by `count` up front, so a count too large to reply
+/// fails instead of aborting the server.
+pub fn pick_with_repetition<T: Clone>(
+  db: &mut Database,
+  items: &[T],
+  count: u64,
+) -> Result<Vec<T>> {
+  let mut picked = vec![];
+  for _ in 0..count {
+    if picked.try_reserve(1).is_err() {
+      bail!(RedisError::Generic("reply is too large".to_owned()));
+    }
+    picked.push(items[db.random_below(items.len())].clone());
+  }
+  Ok(picked)
+}
+
+/// Parses the count of `HRANDFIELD`, `SRANDMEMBER` and the like within the range Redis accepts,
+/// which leaves out `i64::MIN` so that a negative count can always be negated.
+pub fn parse_random_count(arg: &[u8]) -> Result<i64> {
+  let count = parse_integer(arg)?;
+  if count < -i64::MAX {
+    bail!(RedisError::Generic(format!(
+      "value is out of range, value must between {} and {}",
+      -i64::MAX,
+      i64::MAX
+    )));
+  }
+  Ok(count)
+}
+
+impl Parse for Hrandfield {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let (count, with_values) = match &args[1..] {
+      [] => (None, false),
+      [count] => (Some(parse_random_count(count)?), false),
+      [count, option] if option.eq_ignore_ascii_case(b"WITHVALUES") => {
+        let count = parse_random_count(count)?;
+        // each pick is replied as two elements
+        if count < -i64::MAX / 2 {
+          bail!(RedisError::Generic("value is out of range".to_owned()));
+        }
+        (Some(count), true)
+      }
+      _ => bail!(RedisError::Syntax),
+    };
+    Ok(Hrandfield {
+      key: args[0].clone(),
+      count,
+      with_values,
+    })
+  }
+}
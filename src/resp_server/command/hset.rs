@@ -0,0 +1,43 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hset {
+  pub key: Bytes,
+  pub pairs: Vec<(Bytes, Bytes)>,
+}
+
+impl Execute for Hset {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hset { key, pairs } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let hash = db.get_value_or_default::<Hash>(key)?;
+    let added = pairs
+      .iter()
+      .filter(|(field, value)| hash.insert(field.clone(), value.clone()).is_none())
+      .count();
+    Ok(Reply::Integer(added as i64))
+  }
+}
+
+impl Parse for Hset {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let pairs = &args[1..];
+    if !pairs.len().is_multiple_of(2) {
+      bail!(RedisError::WrongArity("hset".to_owned()));
+    }
+    Ok(Hset {
+      key: args[0].clone(),
+      pairs: pairs
+        .chunks_exact(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect(),
+    })
+  }
+}
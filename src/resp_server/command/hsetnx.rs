@@ -0,0 +1,38 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hsetnx {
+  pub key: Bytes,
+  pub field: Bytes,
+  pub value: Bytes,
+}
+
+impl Execute for Hsetnx {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hsetnx { key, field, value } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let hash = db.get_value_or_default::<Hash>(key)?;
+    if hash.contains_key(field) {
+      return Ok(Reply::Integer(0));
+    }
+    hash.insert(field.clone(), value.clone());
+    Ok(Reply::Integer(1))
+  }
+}
+
+impl Parse for Hsetnx {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Hsetnx {
+      key: args[0].clone(),
+      field: args[1].clone(),
+      value: args[2].clone(),
+    })
+  }
+}
@@ -0,0 +1,35 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Hstrlen {
+  pub key: Bytes,
+  pub field: Bytes,
+}
+
+impl Execute for Hstrlen {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Hstrlen { key, field } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let len = db
+      .get_value::<Hash>(key)?
+      .and_then(|hash| hash.get(field))
+      .map_or(0, |value| value.len());
+    Ok(Reply::Integer(len as i64))
+  }
+}
+
+impl Parse for Hstrlen {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Hstrlen {
+      key: args[0].clone(),
+      field: args[1].clone(),
+    })
+  }
+}
@@ -0,0 +1,61 @@
+use bytes::Bytes;
+
+use crate::database::Hash;
+use crate::resp_server::{Reply, Result};
+
+use super::{parse_fields, Execute, ExecutionContext, Parse};
+
+/// `HTTL`, or `HPTTL` when `in_millis` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Httl {
+  pub key: Bytes,
+  pub in_millis: bool,
+  pub fields: Vec<Bytes>,
+}
+
+impl Execute for Httl {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Httl {
+      key,
+      in_millis,
+      fields,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let now = db.now();
+    let hash = db.get_value::<Hash>(key)?;
+    let replies = fields
+      .iter()
+      .map(|field| {
+        let ttl = match hash.filter(|hash| hash.contains_key(field)) {
+          None => -2,
+          Some(hash) => match hash.expire_time(field) {
+            None => -1,
+            Some(expire_time) => {
+              let millis = expire_time.saturating_sub(now) as i64;
+              // unlike `TTL`, Redis rounds the seconds left of a field up
+              if *in_millis {
+                millis
+              } else {
+                (millis + 999) / 1000
+              }
+            }
+          },
+        };
+        Reply::Integer(ttl)
+      })
+      .collect();
+    Ok(Reply::Array(replies))
+  }
+}
+
+impl Parse for Httl {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Httl {
+      key: args[0].clone(),
+      in_millis: false,
+      fields: parse_fields(&args[1..])?,
+    })
+  }
+}
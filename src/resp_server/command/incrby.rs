@@ -0,0 +1,62 @@
+use bytes::Bytes;
+
+use crate::database::StringValue;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_integer, Execute, ExecutionContext, Parse};
+
+/// `INCRBY`, which `INCR`, `DECR` and `DECRBY` are made of too.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Incrby {
+  pub key: Bytes,
+  pub increment: i64,
+}
+
+impl Execute for Incrby {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Incrby { key, increment } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let current = match db.get_value::<StringValue>(key)? {
+      Some(value) => match value.as_integer() {
+        Some(current) => current,
+        None => bail!(RedisError::NotInteger),
+      },
+      None => 0,
+    };
+    let Some(new) = current.checked_add(*increment) else {
+      bail!(RedisError::Generic(
+        "increment or decrement would overflow".to_owned()
+      ));
+    };
+    // updated in place, so that the key keeps its expire time
+    *db.get_value_or_default::<StringValue>(key)? = StringValue::Int(new);
+    Ok(Reply::Integer(new))
+  }
+}
+
+impl Parse for Incrby {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Incrby::parse_from(args, false)
+  }
+}
+
+impl Incrby {
+  /// `INCRBY key increment`, or `DECRBY key decrement` when `decrement` is set.
+  pub fn parse_from(args: &[Bytes], decrement: bool) -> Result<Self> {
+    let increment = parse_integer(&args[1])?;
+    let increment = if decrement {
+      match increment.checked_neg() {
+        Some(increment) => increment,
+        None => bail!(RedisError::Generic("decrement would overflow".to_owned())),
+      }
+    } else {
+      increment
+    };
+    Ok(Incrby {
+      key: args[0].clone(),
+      increment,
+    })
+  }
+}
@@ -0,0 +1,45 @@
+use bytes::Bytes;
+
+use crate::database::StringValue;
+use crate::resp_server::{bail, format_double, RedisError, Reply, Result};
+
+use super::{parse_float, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Incrbyfloat {
+  pub key: Bytes,
+  pub increment: f64,
+}
+
+impl Execute for Incrbyfloat {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Incrbyfloat { key, increment } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let current = match db.get_value::<StringValue>(key)? {
+      Some(StringValue::Int(current)) => *current as f64,
+      Some(StringValue::Raw(value)) => parse_float(value)?,
+      None => 0.0,
+    };
+    let new = current + increment;
+    if !new.is_finite() {
+      bail!(RedisError::Generic(
+        "increment would produce NaN or Infinity".to_owned()
+      ));
+    }
+    let new = Bytes::from(format_double(new));
+    // updated in place, so that the key keeps its expire time
+    *db.get_value_or_default::<StringValue>(key)? = StringValue::from(new.clone());
+    Ok(Reply::BulkString(new))
+  }
+}
+
+impl Parse for Incrbyfloat {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Incrbyfloat {
+      key: args[0].clone(),
+      increment: parse_float(&args[1])?,
+    })
+  }
+}
@@ -0,0 +1,96 @@
+use std::fmt::Write;
+
+use bytes::Bytes;
+
+use crate::database::Database;
+use crate::resp_server::{Reply, Result, REDIS_VERSION};
+
+use super::{Execute, ExecutionContext, Parse};
+
+/// Renders the fields of a section.
+type RenderSection = fn(&Database) -> String;
+
+/// Sections in the order `INFO` reports them.
+const SECTIONS: &[(&str, &str, RenderSection)] = &[
+  ("server", "Server", server_section),
+  ("stats", "Stats", stats_section),
+  ("keyspace", "Keyspace", keyspace_section),
+];
+
+/// `INFO`, limited to the sections this server keeps track of.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Info {
+  /// lowercase section names; empty means the default sections
+  pub sections: Vec<String>,
+}
+
+impl Execute for Info {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Info { sections } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let all_sections = sections.is_empty()
+      || sections
+        .iter()
+        .any(|section| matches!(section.as_str(), "all" | "default" | "everything"));
+
+    let db = db.lock().unwrap();
+    let mut text = String::new();
+    for (name, title, render) in SECTIONS {
+      if all_sections || sections.iter().any(|section| section == name) {
+        if !text.is_empty() {
+          text.push_str("\r\n");
+        }
+        let _ = write!(text, "# {}\r\n{}", title, render(&db));
+      }
+    }
+
+    Ok(Reply::VerbatimString {
+      format: "txt".to_owned(),
+      text: Bytes::from(text),
+    })
+  }
+}
+
+impl Parse for Info {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Info {
+      sections: args
+        .iter()
+        .map(|section| String::from_utf8_lossy(section).to_lowercase())
+        .collect(),
+    })
+  }
+}
+
+fn server_section(_db: &Database) -> String {
+  format!(
+    "redis_version:{}\r\nredis_mode:standalone\r\nprocess_id:{}\r\n",
+    REDIS_VERSION,
+    std::process::id()
+  )
+}
+
+fn stats_section(db: &Database) -> String {
+  let stats = db.stats();
+  format!(
+    "expired_keys:{}\r\nexpired_subkeys:{}\r\nexpired_stale_perc:{:.2}\r\nexpire_cycle_time_milliseconds:{}\r\n",
+    stats.expired_keys,
+    stats.expired_subkeys,
+    stats.expired_stale_perc,
+    stats.expire_cycle_time.as_millis()
+  )
+}
+
+fn keyspace_section(db: &Database) -> String {
+  if db.is_empty() {
+    return String::new();
+  }
+  format!(
+    "db0:keys={},expires={},avg_ttl={},subexpiry={}\r\n",
+    db.len(),
+    db.expires_len(),
+    db.stats().avg_ttl,
+    db.hash_field_expires_len()
+  )
+}
@@ -0,0 +1,181 @@
+use bytes::Bytes;
+
+use crate::database::StringValue;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_integer, Execute, ExecutionContext, Parse};
+
+/// `LCS`, finding the longest common subsequence of two strings.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Lcs {
+  pub key1: Bytes,
+  pub key2: Bytes,
+  /// reply with the length of the subsequence only
+  pub len: bool,
+  /// reply with the ranges of the subsequence in both strings
+  pub idx: bool,
+  /// leave out ranges shorter than this
+  pub min_match_len: usize,
+  pub with_match_len: bool,
+}
+
+/// Where a run of the common subsequence is in each string, as inclusive offsets.
+struct Match {
+  a: (usize, usize),
+  b: (usize, usize),
+}
+
+impl Execute for Lcs {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Lcs {
+      key1,
+      key2,
+      len,
+      idx,
+      min_match_len,
+      with_match_len,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let keys = [key1.clone(), key2.clone()];
+    let Ok(strings) = db.get_values::<StringValue>(&keys) else {
+      bail!(RedisError::Generic(
+        "The specified keys must contain string values".to_owned()
+      ));
+    };
+    let [a, b] = [0, 1].map(|i| strings[i].map_or_else(Bytes::new, StringValue::to_bytes));
+
+    let (subsequence, matches) = longest_common_subsequence(&a, &b);
+    if *len {
+      return Ok(Reply::Integer(subsequence.len() as i64));
+    }
+    if !*idx {
+      return Ok(Reply::BulkString(Bytes::from(subsequence)));
+    }
+
+    let range = |(start, end): (usize, usize)| {
+      Reply::Array(vec![
+        Reply::Integer(start as i64),
+        Reply::Integer(end as i64),
+      ])
+    };
+    let matches = matches
+      .into_iter()
+      .filter(|Match { a, .. }| a.1 - a.0 + 1 >= *min_match_len)
+      .map(|Match { a, b }| {
+        let mut reply = vec![range(a), range(b)];
+        if *with_match_len {
+          reply.push(Reply::Integer((a.1 - a.0 + 1) as i64));
+        }
+        Reply::Array(reply)
+      });
+    Ok(Reply::Map(vec![
+      (
+        Reply::BulkString(Bytes::from_static(b"matches")),
+        Reply::Array(matches.collect()),
+      ),
+      (
+        Reply::BulkString(Bytes::from_static(b"len")),
+        Reply::Integer(subsequence.len() as i64),
+      ),
+    ]))
+  }
+}
+
+/// The longest common subsequence of `a` and `b`, with the runs it is made of from the last one,
+/// walked back through the dynamic programming table like Redis does.
+fn longest_common_subsequence(a: &[u8], b: &[u8]) -> (Vec<u8>, Vec<Match>) {
+  // lengths[i][j] is the length of the longest common subsequence of a[..i] and b[..j]
+  let mut lengths = vec![vec![0u32; b.len() + 1]; a.len() + 1];
+  for i in 1..=a.len() {
+    for j in 1..=b.len() {
+      lengths[i][j] = if a[i - 1] == b[j - 1] {
+        lengths[i - 1][j - 1] + 1
+      } else {
+        lengths[i - 1][j].max(lengths[i][j - 1])
+      };
+    }
+  }
+
+  let mut subsequence = vec![];
+  let mut matches = vec![];
+  let mut current: Option<Match> = None;
+  let (mut i, mut j) = (a.len(), b.len());
+  while i > 0 && j > 0 {
+    if a[i - 1] == b[j - 1] {
+      subsequence.push(a[i - 1]);
+      // walking backwards, a match right before the current run extends it
+      match &mut current {
+        Some(run) if run.a.0 == i && run.b.0 == j => {
+          run.a.0 -= 1;
+          run.b.0 -= 1;
+        }
+        Some(_) => matches.extend(current.take()),
+        None => {
+          current = Some(Match {
+            a: (i - 1, i - 1),
+            b: (j - 1, j - 1),
+          })
+        }
+      }
+      i -= 1;
+      j -= 1;
+      if i == 0 || j == 0 {
+        matches.extend(current.take());
+      }
+    } else {
+      if lengths[i - 1][j] > lengths[i][j - 1] {
+        i -= 1;
+      } else {
+        j -= 1;
+      }
+      matches.extend(current.take());
+    }
+  }
+  subsequence.reverse();
+  (subsequence, matches)
+}
+
+impl Parse for Lcs {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let mut lcs = Lcs {
+      key1: args[0].clone(),
+      key2: args[1].clone(),
+      len: false,
+      idx: false,
+      min_match_len: 0,
+      with_match_len: false,
+    };
+    let mut rest = &args[2..];
+    loop {
+      match rest {
+        [] => break,
+        [option, more @ ..] if option.eq_ignore_ascii_case(b"LEN") => {
+          lcs.len = true;
+          rest = more;
+        }
+        [option, more @ ..] if option.eq_ignore_ascii_case(b"IDX") => {
+          lcs.idx = true;
+          rest = more;
+        }
+        [option, more @ ..] if option.eq_ignore_ascii_case(b"WITHMATCHLEN") => {
+          lcs.with_match_len = true;
+          rest = more;
+        }
+        [option, value, more @ ..] if option.eq_ignore_ascii_case(b"MINMATCHLEN") => {
+          // like in Redis, a negative length filters nothing out
+          lcs.min_match_len = usize::try_from(parse_integer(value)?).unwrap_or(0);
+          rest = more;
+        }
+        _ => bail!(RedisError::Syntax),
+      }
+    }
+    if lcs.len && lcs.idx {
+      bail!(RedisError::Generic(
+        "If you want both the length and indexes, please just use IDX.".to_owned()
+      ));
+    }
+    Ok(lcs)
+  }
+}
@@ -0,0 +1,42 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+
+use crate::resp_server::{Reply, Result};
+
+use super::{parse_integer, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Lindex {
+  pub key: Bytes,
+  pub index: i64,
+}
+
+impl Execute for Lindex {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Lindex { key, index } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let element = db
+      .get_value::<VecDeque<Bytes>>(key)?
+      .and_then(|list| normalize_index(*index, list.len()).map(|index| list[index].clone()));
+    Ok(Reply::from(element))
+  }
+}
+
+impl Parse for Lindex {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Lindex {
+      key: args[0].clone(),
+      index: parse_integer(&args[1])?,
+    })
+  }
+}
+
+/// Turns an index that counts from the end when negative into a position within a sequence of
+/// `len` elements, or `None` if it is out of range.
+pub fn normalize_index(index: i64, len: usize) -> Option<usize> {
+  let index = if index < 0 { index + len as i64 } else { index };
+  (0..len as i64).contains(&index).then_some(index as usize)
+}
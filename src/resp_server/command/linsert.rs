@@ -0,0 +1,54 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Linsert {
+  pub key: Bytes,
+  pub before: bool,
+  pub pivot: Bytes,
+  pub element: Bytes,
+}
+
+impl Execute for Linsert {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Linsert {
+      key,
+      before,
+      pivot,
+      element,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(list) = db.get_value_mut::<VecDeque<Bytes>>(key)? else {
+      return Ok(Reply::Integer(0));
+    };
+    let Some(position) = list.iter().position(|item| item == pivot) else {
+      return Ok(Reply::Integer(-1));
+    };
+    let position = if *before { position } else { position + 1 };
+    list.insert(position, element.clone());
+    Ok(Reply::Integer(list.len() as i64))
+  }
+}
+
+impl Parse for Linsert {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let before = match args[1].to_ascii_uppercase().as_slice() {
+      b"BEFORE" => true,
+      b"AFTER" => false,
+      _ => bail!(RedisError::Syntax),
+    };
+    Ok(Linsert {
+      key: args[0].clone(),
+      before,
+      pivot: args[2].clone(),
+      element: args[3].clone(),
+    })
+  }
+}
@@ -0,0 +1,33 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Llen {
+  pub key: Bytes,
+}
+
+impl Execute for Llen {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Llen { key } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let len = db
+      .get_value::<VecDeque<Bytes>>(key)?
+      .map_or(0, |list| list.len());
+    Ok(Reply::Integer(len as i64))
+  }
+}
+
+impl Parse for Llen {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Llen {
+      key: args[0].clone(),
+    })
+  }
+}
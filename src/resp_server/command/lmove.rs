@@ -0,0 +1,86 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+
+use crate::database::Database;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{push_elements, Execute, ExecutionContext, Parse};
+
+/// The end of a list that a command pushes to or pops from.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ListEnd {
+  Left,
+  Right,
+}
+
+impl ListEnd {
+  pub fn parse(arg: &[u8]) -> Result<Self> {
+    match arg.to_ascii_uppercase().as_slice() {
+      b"LEFT" => Ok(ListEnd::Left),
+      b"RIGHT" => Ok(ListEnd::Right),
+      _ => bail!(RedisError::Syntax),
+    }
+  }
+}
+
+/// `LMOVE`, which `RPOPLPUSH` is a special case of.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Lmove {
+  pub source: Bytes,
+  pub destination: Bytes,
+  pub from: ListEnd,
+  pub to: ListEnd,
+}
+
+impl Execute for Lmove {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Lmove {
+      source,
+      destination,
+      from,
+      to,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let element = move_element(&mut db, source, destination, *from, *to)?;
+    Ok(Reply::from(element))
+  }
+}
+
+impl Parse for Lmove {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Lmove {
+      source: args[0].clone(),
+      destination: args[1].clone(),
+      from: ListEnd::parse(&args[2])?,
+      to: ListEnd::parse(&args[3])?,
+    })
+  }
+}
+
+/// Pops an element from the `from` end of `source` and pushes it to the `to` end of `destination`.
+/// Returns `None` when `source` doesn't exist.
+pub fn move_element(
+  db: &mut Database,
+  source: &[u8],
+  destination: &[u8],
+  from: ListEnd,
+  to: ListEnd,
+) -> Result<Option<Bytes>> {
+  // fail before popping anything when the destination holds another type
+  db.get_value::<VecDeque<Bytes>>(destination)?;
+  let Some(list) = db.get_value_mut::<VecDeque<Bytes>>(source)? else {
+    return Ok(None);
+  };
+  let element = match from {
+    ListEnd::Left => list.pop_front(),
+    ListEnd::Right => list.pop_back(),
+  }
+  .expect("empty lists are removed");
+
+  push_elements(db, destination, to, std::slice::from_ref(&element))?;
+  db.remove_if_empty(source);
+  Ok(Some(element))
+}
@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use crate::database::Database;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{pop_elements, serve_or_block, Execute, ExecutionContext, ListEnd, Parse};
+
+/// `LMPOP`, or `BLMPOP` when `blocking` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Lmpop {
+  pub keys: Vec<Bytes>,
+  pub end: ListEnd,
+  pub count: usize,
+  pub blocking: bool,
+  /// how long `BLMPOP` waits, `None` meaning forever
+  pub timeout: Option<Duration>,
+}
+
+impl Execute for Lmpop {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Lmpop {
+      keys,
+      end,
+      count,
+      blocking,
+      timeout,
+    } = self;
+
+    let (keys_to_pop, end, count) = (keys.clone(), *end, *count);
+    let serve = move |db: &mut Database| {
+      let popped = pop_from_first_list(db, &keys_to_pop, end, count)?;
+      Ok(
+        popped
+          .map(|(key, elements)| Reply::Array(vec![Reply::BulkString(key), Reply::from(elements)])),
+      )
+    };
+
+    if *blocking {
+      return serve_or_block(ctx, keys, *timeout, Reply::NullArray, serve);
+    }
+    let ExecutionContext { db, .. } = ctx;
+    let reply = serve(&mut db.lock().unwrap())?;
+    Ok(reply.unwrap_or(Reply::NullArray))
+  }
+}
+
+impl Parse for Lmpop {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let numkeys = String::from_utf8_lossy(&args[0]).parse::<usize>();
+    let Ok(numkeys @ 1..) = numkeys else {
+      bail!(RedisError::Generic(
+        "numkeys should be greater than 0".to_owned()
+      ));
+    };
+    let Some(keys) = args.get(1..=numkeys) else {
+      bail!(RedisError::Syntax);
+    };
+    let Some(end) = args.get(numkeys + 1) else {
+      bail!(RedisError::Syntax);
+    };
+
+    let count = match &args[numkeys + 2..] {
+      [] => 1,
+      [option, count] if option.eq_ignore_ascii_case(b"COUNT") => {
+        match String::from_utf8_lossy(count).parse::<usize>() {
+          Ok(count @ 1..) => count,
+          _ => bail!(RedisError::Generic(
+            "count should be greater than 0".to_owned()
+          )),
+        }
+      }
+      _ => bail!(RedisError::Syntax),
+    };
+
+    Ok(Lmpop {
+      keys: keys.to_vec(),
+      end: ListEnd::parse(end)?,
+      count,
+      blocking: false,
+      timeout: None,
+    })
+  }
+}
+
+/// Pops up to `count` elements from the first of `keys` that holds a list.
+pub fn pop_from_first_list(
+  db: &mut Database,
+  keys: &[Bytes],
+  end: ListEnd,
+  count: usize,
+) -> Result<Option<(Bytes, Vec<Bytes>)>> {
+  for key in keys {
+    if let Some(elements) = pop_elements(db, key, end, count)? {
+      return Ok(Some((key.clone(), elements)));
+    }
+  }
+  Ok(None)
+}
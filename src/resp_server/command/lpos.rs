@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_integer, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Lpos {
+  pub key: Bytes,
+  pub element: Bytes,
+  /// which match to start from: 1 is the first from the head, -1 the first from the tail
+  pub rank: i64,
+  /// how many positions to reply, 0 meaning all; without it a single position is replied
+  pub count: Option<usize>,
+  /// how many elements to compare at most, 0 meaning the whole list
+  pub maxlen: usize,
+}
+
+impl Execute for Lpos {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Lpos {
+      key,
+      element,
+      rank,
+      count,
+      maxlen,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(list) = db.get_value::<VecDeque<Bytes>>(key)? else {
+      return Ok(match count {
+        Some(_) => Reply::Array(vec![]),
+        None => Reply::NullBulkString,
+      });
+    };
+    let len = list.len();
+    let compared = if *maxlen == 0 { len } else { len.min(*maxlen) };
+    let positions: Box<dyn Iterator<Item = usize>> = if *rank > 0 {
+      Box::new(0..compared)
+    } else {
+      Box::new((len - compared..len).rev())
+    };
+
+    let wanted = match count {
+      Some(0) => usize::MAX,
+      Some(count) => *count,
+      None => 1,
+    };
+    let mut skipped = rank.unsigned_abs() - 1;
+    let mut matches = vec![];
+    for position in positions {
+      if list[position] != element {
+        continue;
+      }
+      if skipped > 0 {
+        skipped -= 1;
+        continue;
+      }
+      matches.push(Reply::Integer(position as i64));
+      if matches.len() >= wanted {
+        break;
+      }
+    }
+
+    Ok(match count {
+      Some(_) => Reply::Array(matches),
+      None => matches.pop().unwrap_or(Reply::NullBulkString),
+    })
+  }
+}
+
+impl Parse for Lpos {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let mut lpos_cmd = Lpos {
+      key: args[0].clone(),
+      element: args[1].clone(),
+      rank: 1,
+      count: None,
+      maxlen: 0,
+    };
+
+    let mut cmd_iter = args[2..].iter();
+    while let Some(option) = cmd_iter.next() {
+      let option = option.to_ascii_uppercase();
+      if !matches!(option.as_slice(), b"RANK" | b"COUNT" | b"MAXLEN") {
+        bail!(RedisError::Syntax);
+      }
+      let Some(value) = cmd_iter.next() else {
+        bail!(RedisError::Syntax);
+      };
+      let value = parse_integer(value)?;
+      match option.as_slice() {
+        b"RANK" if value == 0 => bail!(RedisError::Generic(
+          "RANK can't be zero: use 1 to start from the first match, 2 from the second ... \
+           or use negative to start from the end of the list"
+            .to_owned()
+        )),
+        b"RANK" if value == i64::MIN => {
+          bail!(RedisError::Generic("value is out of range".to_owned()))
+        }
+        b"RANK" => lpos_cmd.rank = value,
+        b"COUNT" if value < 0 => bail!(RedisError::Generic("COUNT can't be negative".to_owned())),
+        b"COUNT" => lpos_cmd.count = Some(value as usize),
+        b"MAXLEN" if value < 0 => bail!(RedisError::Generic("MAXLEN can't be negative".to_owned())),
+        b"MAXLEN" => lpos_cmd.maxlen = value as usize,
+        _ => bail!(RedisError::Syntax),
+      }
+    }
+
+    Ok(lpos_cmd)
+  }
+}
@@ -0,0 +1,61 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+
+use crate::resp_server::{Reply, Result};
+
+use super::{parse_integer, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Lrange {
+  pub key: Bytes,
+  pub start: i64,
+  pub stop: i64,
+}
+
+impl Execute for Lrange {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Lrange { key, start, stop } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(list) = db.get_value::<VecDeque<Bytes>>(key)? else {
+      return Ok(Reply::Array(vec![]));
+    };
+    let elements = match normalize_range(*start, *stop, list.len()) {
+      Some((start, stop)) => list.range(start..=stop).cloned().collect(),
+      None => vec![],
+    };
+    Ok(Reply::from(elements))
+  }
+}
+
+impl Parse for Lrange {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Lrange {
+      key: args[0].clone(),
+      start: parse_integer(&args[1])?,
+      stop: parse_integer(&args[2])?,
+    })
+  }
+}
+
+/// Turns inclusive `start` and `stop` indexes, which may count from the end when negative, into
+/// inclusive positions within a sequence of `len` elements. `None` means the range is empty.
+pub fn normalize_range(start: i64, stop: i64, len: usize) -> Option<(usize, usize)> {
+  let len = len as i64;
+  let start = if start < 0 {
+    (start + len).max(0)
+  } else {
+    start
+  };
+  let stop = if stop < 0 {
+    stop + len
+  } else {
+    stop.min(len - 1)
+  };
+  if start > stop || start >= len {
+    return None;
+  }
+  Some((start as usize, stop as usize))
+}
@@ -0,0 +1,67 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+
+use crate::resp_server::{Reply, Result};
+
+use super::{parse_integer, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Lrem {
+  pub key: Bytes,
+  /// how many occurrences to remove, from the head when positive and from the tail when negative;
+  /// 0 removes all of them
+  pub count: i64,
+  pub element: Bytes,
+}
+
+impl Execute for Lrem {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Lrem {
+      key,
+      count,
+      element,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(list) = db.get_value_mut::<VecDeque<Bytes>>(key)? else {
+      return Ok(Reply::Integer(0));
+    };
+
+    let limit = match *count {
+      0 => usize::MAX,
+      count => count.unsigned_abs() as usize,
+    };
+    let mut removed = 0;
+    if *count >= 0 {
+      list.retain(|item| {
+        let remove = removed < limit && item == element;
+        removed += remove as usize;
+        !remove
+      });
+    } else {
+      let mut position = list.len();
+      while position > 0 && removed < limit {
+        position -= 1;
+        if list[position] == element {
+          list.remove(position);
+          removed += 1;
+        }
+      }
+    }
+
+    db.remove_if_empty(key);
+    Ok(Reply::Integer(removed as i64))
+  }
+}
+
+impl Parse for Lrem {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Lrem {
+      key: args[0].clone(),
+      count: parse_integer(&args[1])?,
+      element: args[2].clone(),
+    })
+  }
+}
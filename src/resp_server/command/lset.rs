@@ -0,0 +1,45 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{normalize_index, parse_integer, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Lset {
+  pub key: Bytes,
+  pub index: i64,
+  pub element: Bytes,
+}
+
+impl Execute for Lset {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Lset {
+      key,
+      index,
+      element,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(list) = db.get_value_mut::<VecDeque<Bytes>>(key)? else {
+      bail!(RedisError::Generic("no such key".to_owned()));
+    };
+    let Some(index) = normalize_index(*index, list.len()) else {
+      bail!(RedisError::Generic("index out of range".to_owned()));
+    };
+    list[index] = element.clone();
+    Ok(Reply::ok())
+  }
+}
+
+impl Parse for Lset {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Lset {
+      key: args[0].clone(),
+      index: parse_integer(&args[1])?,
+      element: args[2].clone(),
+    })
+  }
+}
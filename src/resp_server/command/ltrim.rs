@@ -0,0 +1,45 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+
+use crate::resp_server::{Reply, Result};
+
+use super::{normalize_range, parse_integer, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Ltrim {
+  pub key: Bytes,
+  pub start: i64,
+  pub stop: i64,
+}
+
+impl Execute for Ltrim {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Ltrim { key, start, stop } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(list) = db.get_value_mut::<VecDeque<Bytes>>(key)? else {
+      return Ok(Reply::ok());
+    };
+    match normalize_range(*start, *stop, list.len()) {
+      Some((start, stop)) => {
+        list.truncate(stop + 1);
+        list.drain(..start);
+      }
+      None => list.clear(),
+    }
+    db.remove_if_empty(key);
+    Ok(Reply::ok())
+  }
+}
+
+impl Parse for Ltrim {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Ltrim {
+      key: args[0].clone(),
+      start: parse_integer(&args[1])?,
+      stop: parse_integer(&args[2])?,
+    })
+  }
+}
@@ -0,0 +1,34 @@
+use bytes::Bytes;
+
+use crate::database::StringValue;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Mget {
+  pub keys: Vec<Bytes>,
+}
+
+impl Execute for Mget {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Mget { keys } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    // keys that don't hold a string are reported as missing rather than failing the command
+    let values = keys.iter().map(|key| {
+      let value = db.get_value::<StringValue>(key).ok().flatten();
+      Reply::from(value.map(StringValue::to_bytes))
+    });
+    Ok(Reply::Array(values.collect()))
+  }
+}
+
+impl Parse for Mget {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Mget {
+      keys: args.to_vec(),
+    })
+  }
+}
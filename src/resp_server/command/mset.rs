@@ -0,0 +1,56 @@
+use bytes::Bytes;
+
+use crate::database::{Data, Value};
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+/// `MSET`, or `MSETNX` when `nx` is set, which sets no key at all if any of them exists.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Mset {
+  pub entries: Vec<(Bytes, Bytes)>,
+  pub nx: bool,
+}
+
+impl Execute for Mset {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Mset { entries, nx } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    if *nx && entries.iter().any(|(key, _)| db.contains_key(key)) {
+      return Ok(Reply::Integer(0));
+    }
+    for (key, value) in entries {
+      let data = Data {
+        value: Value::from(value.clone()),
+        expire_time: None,
+      };
+      db.set(key, &data);
+    }
+    Ok(if *nx { Reply::Integer(1) } else { Reply::ok() })
+  }
+}
+
+impl Parse for Mset {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Mset::parse_from(args, false)
+  }
+}
+
+impl Mset {
+  /// `MSET key value [key value ...]`, or `MSETNX` with the same arguments
+  pub fn parse_from(args: &[Bytes], nx: bool) -> Result<Self> {
+    if !args.len().is_multiple_of(2) {
+      let name = if nx { "msetnx" } else { "mset" };
+      bail!(RedisError::WrongArity(name.to_owned()));
+    }
+    Ok(Mset {
+      entries: args
+        .chunks_exact(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect(),
+      nx,
+    })
+  }
+}
@@ -0,0 +1,32 @@
+use bytes::Bytes;
+
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Persist {
+  pub key: Bytes,
+}
+
+impl Execute for Persist {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Persist { key } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let persisted = db.get(key).is_some_and(|data| data.expire_time.is_some());
+    if persisted {
+      db.set_expire_time(key, None);
+    }
+    Ok(Reply::Integer(persisted as i64))
+  }
+}
+
+impl Parse for Persist {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Persist {
+      key: args[0].clone(),
+    })
+  }
+}
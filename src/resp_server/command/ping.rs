@@ -1,16 +1,29 @@
-use super::Result;
-use super::{Execute, ExecutionContext};
+use bytes::Bytes;
+
+use super::{Execute, ExecutionContext, Parse};
+use crate::resp_server::{bail, RedisError, Reply, Result};
 
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Ping {
-  pub message: Option<String>,
+  pub message: Option<Bytes>,
 }
 
 impl Execute for Ping {
-  fn execute(&self, _ctx: &ExecutionContext) -> Result<Vec<u8>> {
+  fn execute(&self, _ctx: &ExecutionContext) -> Result<Reply> {
     Ok(match &self.message {
-      Some(message) => format!("+{}\r\n", message).into_bytes(),
-      None => "+PONG\r\n".to_owned().into_bytes(),
+      Some(message) => Reply::BulkString(message.clone()),
+      None => Reply::SimpleString("PONG".to_owned()),
+    })
+  }
+}
+
+impl Parse for Ping {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    if args.len() > 1 {
+      bail!(RedisError::WrongArity("ping".to_owned()));
+    }
+    Ok(Ping {
+      message: args.first().cloned(),
     })
   }
 }
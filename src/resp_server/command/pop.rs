@@ -0,0 +1,81 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+
+use crate::database::Database;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{Execute, ExecutionContext, ListEnd, Parse};
+
+/// `LPOP` or `RPOP`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Pop {
+  pub key: Bytes,
+  pub end: ListEnd,
+  pub count: Option<usize>,
+}
+
+impl Execute for Pop {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Pop { key, end, count } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let elements = pop_elements(&mut db, key, *end, count.unwrap_or(1))?;
+    Ok(match (count, elements) {
+      (None, None) => Reply::NullBulkString,
+      (None, Some(elements)) => Reply::from(elements.into_iter().next()),
+      (Some(_), None) => Reply::NullArray,
+      (Some(_), Some(elements)) => Reply::from(elements),
+    })
+  }
+}
+
+impl Parse for Pop {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Pop::parse_from(args, ListEnd::Left)
+  }
+}
+
+impl Pop {
+  pub fn parse_from(args: &[Bytes], end: ListEnd) -> Result<Self> {
+    let count = match args {
+      [_] => None,
+      [_, count] => {
+        let Ok(count) = String::from_utf8_lossy(count).parse() else {
+          bail!(RedisError::NotPositive);
+        };
+        Some(count)
+      }
+      _ => {
+        let name = if end == ListEnd::Left { "lpop" } else { "rpop" };
+        bail!(RedisError::WrongArity(name.to_owned()));
+      }
+    };
+    Ok(Pop {
+      key: args[0].clone(),
+      end,
+      count,
+    })
+  }
+}
+
+/// Pops up to `count` elements from the `end` of the list at `key`, deleting the list once it is
+/// empty. Returns `None` when the list doesn't exist.
+pub fn pop_elements(
+  db: &mut Database,
+  key: &[u8],
+  end: ListEnd,
+  count: usize,
+) -> Result<Option<Vec<Bytes>>> {
+  let Some(list) = db.get_value_mut::<VecDeque<Bytes>>(key)? else {
+    return Ok(None);
+  };
+  let count = count.min(list.len());
+  let elements = match end {
+    ListEnd::Left => list.drain(..count).collect(),
+    ListEnd::Right => list.drain(list.len() - count..).rev().collect(),
+  };
+  db.remove_if_empty(key);
+  Ok(Some(elements))
+}
@@ -0,0 +1,67 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+
+use crate::database::Database;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, ListEnd, Parse};
+
+/// `LPUSH` or `RPUSH`, or `LPUSHX`/`RPUSHX` when `only_if_exists` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Push {
+  pub key: Bytes,
+  pub elements: Vec<Bytes>,
+  pub end: ListEnd,
+  pub only_if_exists: bool,
+}
+
+impl Execute for Push {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Push {
+      key,
+      elements,
+      end,
+      only_if_exists,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    if *only_if_exists && db.get_value::<VecDeque<Bytes>>(key)?.is_none() {
+      return Ok(Reply::Integer(0));
+    }
+    let len = push_elements(&mut db, key, *end, elements)?;
+    Ok(Reply::Integer(len as i64))
+  }
+}
+
+impl Parse for Push {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Push {
+      key: args[0].clone(),
+      elements: args[1..].to_vec(),
+      end: ListEnd::Left,
+      only_if_exists: false,
+    })
+  }
+}
+
+/// Pushes `elements` one by one to the `end` of the list at `key`, creating the list if needed.
+/// Returns the length of the list afterwards.
+pub fn push_elements(
+  db: &mut Database,
+  key: &[u8],
+  end: ListEnd,
+  elements: &[Bytes],
+) -> Result<usize> {
+  let list = db.get_value_or_default::<VecDeque<Bytes>>(key)?;
+  for element in elements {
+    match end {
+      ListEnd::Left => list.push_front(element.clone()),
+      ListEnd::Right => list.push_back(element.clone()),
+    }
+  }
+  let len = list.len();
+  db.signal_key_as_ready(key);
+  Ok(len)
+}
@@ -0,0 +1,45 @@
+use bytes::Bytes;
+
+use super::{Execute, ExecutionContext, Parse};
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+/// `RENAME`, or `RENAMENX` when `nx` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Rename {
+  pub key: Bytes,
+  pub new_key: Bytes,
+  pub nx: bool,
+}
+
+impl Execute for Rename {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Rename { key, new_key, nx } = self;
+    let ExecutionContext { db, .. } = ctx;
+    let mut db = db.lock().unwrap();
+
+    if !db.contains_key(key) {
+      bail!(RedisError::Generic("no such key".to_owned()));
+    }
+    if *nx && db.contains_key(new_key) {
+      return Ok(Reply::Integer(0));
+    }
+
+    if key != new_key {
+      // the expire time moves along with the value
+      let data = db.remove(key).expect("key existence checked above");
+      db.set(new_key, &data);
+    }
+
+    Ok(if *nx { Reply::Integer(1) } else { Reply::ok() })
+  }
+}
+
+impl Parse for Rename {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Rename {
+      key: args[0].clone(),
+      new_key: args[1].clone(),
+      nx: false,
+    })
+  }
+}
@@ -0,0 +1,36 @@
+use bytes::Bytes;
+
+use crate::database::SetValue;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Sadd {
+  pub key: Bytes,
+  pub members: Vec<Bytes>,
+}
+
+impl Execute for Sadd {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Sadd { key, members } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let set = db.get_value_or_default::<SetValue>(key)?;
+    let added = members
+      .iter()
+      .filter(|member| set.insert((*member).clone()))
+      .count();
+    Ok(Reply::Integer(added as i64))
+  }
+}
+
+impl Parse for Sadd {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Sadd {
+      key: args[0].clone(),
+      members: args[1..].to_vec(),
+    })
+  }
+}
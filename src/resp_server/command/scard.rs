@@ -0,0 +1,30 @@
+use bytes::Bytes;
+
+use crate::database::SetValue;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Scard {
+  pub key: Bytes,
+}
+
+impl Execute for Scard {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Scard { key } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let len = db.get_value::<SetValue>(key)?.map_or(0, SetValue::len);
+    Ok(Reply::Integer(len as i64))
+  }
+}
+
+impl Parse for Scard {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Scard {
+      key: args[0].clone(),
+    })
+  }
+}
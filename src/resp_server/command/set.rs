@@ -1,134 +1,172 @@
-use std::time::{Duration, SystemTime};
+use bytes::Bytes;
 
-use crate::database::{Data, ExpireTime};
+use crate::database::{Data, ExpireTime, StringValue, Value};
 use crate::resp_server::{bail, Context, Result};
-use crate::resp_server::{RespValue, NULL_BULK_STRING};
+use crate::resp_server::{RedisError, Reply};
 
-use super::{Execute, ExecutionContext};
+use super::{Execute, ExecutionContext, Parse};
 
 #[derive(PartialEq, Eq, Debug, Clone, Default)]
 pub struct Set {
-  pub key: String,
-  pub value: String,
+  pub key: Bytes,
+  pub value: Bytes,
   pub nx_or_xx: Option<String>,
   pub get: bool,
-  pub expire_time: Option<ExpireTime>,
+  /// the `EX`, `PX`, `EXAT` or `PXAT` option with its amount, resolved against the clock on execution
+  pub expire: Option<(String, u64)>,
+  /// parsed from `SETEX` or `PSETEX`, which its errors are about
+  pub setex: bool,
 }
 
 impl Execute for Set {
-  fn execute(&self, ctx: &ExecutionContext) -> Result<Vec<u8>> {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
     let Set {
       key,
       value,
       nx_or_xx,
       get,
-      expire_time,
+      expire,
+      ..
     } = self;
-    let ExecutionContext { db } = ctx;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
 
+    let expire_time = match expire {
+      Some((option, amount)) => Some(calculate_expire_time(db.now(), option, *amount).context(
+        RedisError::InvalidExpireTime(self.command_name().to_owned()),
+      )?),
+      None => None,
+    };
     let data = Data {
-      value: value.to_owned(),
-      expire_time: match expire_time {
-        Some(t) => Some(*t),
-        None => None,
-      },
+      value: Value::from(value.clone()),
+      expire_time,
     };
 
-    let mut db = db.lock().unwrap();
-
-    let result = if *get {
-      let old_data = db.get(key).cloned();
-      match nx_or_xx {
-        Some(opt) if opt == "NX" && old_data.is_none() => db.set(key, &data),
-        Some(opt) if opt == "XX" && old_data.is_some() => db.set(key, &data),
-        None => db.set(key, &data),
-        Some(_) => bail!("unknown SET option: this is unreachable"),
-      };
-      match old_data {
-        Some(data) => format!("+{}", data.value).into_bytes(),
-        None => format!("{}\r\n", NULL_BULK_STRING).into_bytes(),
-      }
+    // SET overwrites a value of any type, but GET can only return a string
+    let old_value = if *get {
+      db.get_value::<StringValue>(key)?.map(StringValue::to_bytes)
     } else {
-      match nx_or_xx {
-        Some(opt) if opt == "NX" && db.get(key).is_none() => db.set(key, &data),
-        Some(opt) if opt == "XX" && db.get(key).is_some() => db.set(key, &data),
-        None => db.set(key, &data),
-        Some(_) => bail!("unknown SET option: this is unreachable"),
-      };
-      format!("+OK\r\n").into_bytes()
+      None
     };
+    let exists = db.contains_key(key);
+    let should_set = match nx_or_xx.as_deref() {
+      Some("NX") => !exists,
+      Some("XX") => exists,
+      None => true,
+      Some(_) => bail!("unknown SET option: this is unreachable"),
+    };
+    if should_set {
+      db.set(key, &data);
+    }
+
+    Ok(if *get {
+      Reply::from(old_value)
+    } else if should_set {
+      Reply::ok()
+    } else {
+      Reply::NullBulkString
+    })
+  }
+}
 
-    Ok(result)
+impl Parse for Set {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let mut set_cmd = Set {
+      key: args[0].clone(),
+      value: args[1].clone(),
+      ..Set::default()
+    };
+    set_cmd
+      .set_option(&mut args[2..].iter())
+      .context("failed to set option")?;
+    Ok(set_cmd)
   }
 }
 
 impl Set {
-  pub fn set_option(&mut self, cmd_iter: &mut std::slice::Iter<RespValue>) -> Result<()> {
+  /// `SETEX key seconds value`, or `PSETEX key milliseconds value` when `option` is `PX`.
+  pub fn parse_setex(args: &[Bytes], option: &str) -> Result<Self> {
+    let Ok(amount) = String::from_utf8_lossy(&args[1]).parse::<i64>() else {
+      bail!(RedisError::NotInteger);
+    };
+    let set_cmd = Set {
+      key: args[0].clone(),
+      value: args[2].clone(),
+      expire: Some((option.to_owned(), amount as u64)),
+      setex: true,
+      ..Set::default()
+    };
+    if amount <= 0 || (option == "EX" && amount > i64::MAX / 1000) {
+      bail!(RedisError::InvalidExpireTime(
+        set_cmd.command_name().to_owned()
+      ));
+    }
+    Ok(set_cmd)
+  }
+
+  fn command_name(&self) -> &'static str {
+    match (self.setex, &self.expire) {
+      (true, Some((option, _))) if option == "PX" => "psetex",
+      (true, _) => "setex",
+      (false, _) => "set",
+    }
+  }
+
+  pub fn set_option(&mut self, cmd_iter: &mut std::slice::Iter<Bytes>) -> Result<()> {
     while let Some(option) = cmd_iter.next() {
-      let RespValue::BulkString(option_str) = option else {
-        bail!("SET options should be BulkString");
-      };
-      let option_str = option_str.to_uppercase();
+      let option_str = String::from_utf8_lossy(option).to_uppercase();
 
       match option_str.as_str() {
-        "NX" => {
-          self.nx_or_xx = Some(option_str);
-        }
-        "XX" => {
+        "NX" | "XX" => {
+          if self.nx_or_xx.as_ref().is_some_and(|opt| *opt != option_str) {
+            bail!(RedisError::Syntax);
+          }
           self.nx_or_xx = Some(option_str);
         }
         "GET" => {
           self.get = true;
         }
         "EX" | "PX" | "EXAT" | "PXAT" => {
-          let Some(expire_time_resp) = cmd_iter.next() else {
-            bail!("SET {} option should contain expire time", option_str);
-          };
-          let RespValue::BulkString(expire_time_str) = expire_time_resp else {
-            bail!(
-              "SET {} option should contain expire time as BulkString",
-              option_str
-            );
+          let (Some(expire_time_str), None) = (cmd_iter.next(), &self.expire) else {
+            bail!(RedisError::Syntax);
           };
 
-          let Ok(expire_time) = expire_time_str.parse() else {
-            bail!("SET EX value should be in valid format");
+          let Ok(expire_time) = String::from_utf8_lossy(expire_time_str).parse::<i64>() else {
+            bail!(RedisError::NotInteger);
           };
-          let expire_time = calculate_expire_time(&option_str, expire_time)
-            .context("failed to calculate expire time")?;
-
-          self.expire_time = Some(expire_time);
+          // like in Redis, seconds that overflow once in milliseconds are caught right away
+          let in_seconds = matches!(option_str.as_str(), "EX" | "EXAT");
+          if expire_time <= 0 || (in_seconds && expire_time > i64::MAX / 1000) {
+            bail!(RedisError::InvalidExpireTime("set".to_owned()));
+          }
+          self.expire = Some((option_str, expire_time as u64));
         }
-        _ => bail!("Unknown option in SET command"),
+        _ => bail!(RedisError::Syntax),
       }
     }
     Ok(())
   }
 }
 
-fn calculate_expire_time(command_type: &str, duration: u64) -> Result<ExpireTime> {
-  let duration = match command_type {
-    "EX" => Duration::from_secs(duration),
-    "PX" => Duration::from_millis(duration),
-    "EXAT" => {
-      return Ok(Duration::from_secs(duration).as_millis());
-    }
-    "PXAT" => {
-      return Ok(Duration::from_millis(duration).as_millis());
-    }
+/// Resolves an `EX`, `PX`, `EXAT` or `PXAT` amount into an absolute expire time. Like in Redis,
+/// it fails past `i64::MAX` milliseconds, so an expire time can always be replied as an integer.
+pub fn calculate_expire_time(
+  now: ExpireTime,
+  command_type: &str,
+  duration: u64,
+) -> Result<ExpireTime> {
+  let duration = duration as ExpireTime;
+  let expire_time = match command_type {
+    "EX" => duration
+      .checked_mul(1000)
+      .and_then(|duration| now.checked_add(duration)),
+    "PX" => now.checked_add(duration),
+    "EXAT" => duration.checked_mul(1000),
+    "PXAT" => Some(duration),
     _ => bail!("unknown expire time option"),
   };
-  let now = SystemTime::now();
-
-  let expire_time = now
-    .checked_add(duration)
-    .context("failed to add duration to systemtime")?;
-
-  // duration since UNIX EPOCH(1970-01-01 00:00:00 UTC)
-  let expire_time = expire_time
-    .duration_since(SystemTime::UNIX_EPOCH)
-    .context("SystemTime before unix epoch")?
-    .as_millis();
-
-  Ok(expire_time)
+  expire_time
+    .filter(|expire_time| *expire_time <= i64::MAX as ExpireTime)
+    .context("expire time overflow")
 }
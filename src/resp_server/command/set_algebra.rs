@@ -0,0 +1,109 @@
+use bytes::Bytes;
+
+use crate::database::{Data, Database, SetValue, Value};
+use crate::resp_server::{Reply, Result};
+
+use super::{set_reply, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum SetOperation {
+  Inter,
+  Union,
+  Diff,
+}
+
+/// `SINTER`, `SUNION` or `SDIFF`, or their `STORE` variants when `destination` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct SetAlgebra {
+  pub keys: Vec<Bytes>,
+  pub operation: SetOperation,
+  pub destination: Option<Bytes>,
+}
+
+impl Execute for SetAlgebra {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let SetAlgebra {
+      keys,
+      operation,
+      destination,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let result = combine_sets(&mut db, keys, *operation)?;
+    let Some(destination) = destination else {
+      return Ok(set_reply(result.members()));
+    };
+
+    let len = result.len();
+    if result.is_empty() {
+      db.remove(destination);
+    } else {
+      let data = Data {
+        value: Value::Set(result),
+        expire_time: None,
+      };
+      db.set(destination, &data);
+    }
+    Ok(Reply::Integer(len as i64))
+  }
+}
+
+impl Parse for SetAlgebra {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(SetAlgebra {
+      keys: args.to_vec(),
+      operation: SetOperation::Inter,
+      destination: None,
+    })
+  }
+}
+
+impl SetAlgebra {
+  /// `SINTERSTORE`, `SUNIONSTORE` or `SDIFFSTORE destination key [key ...]`
+  pub fn parse_store(args: &[Bytes], operation: SetOperation) -> Result<Self> {
+    Ok(SetAlgebra {
+      keys: args[1..].to_vec(),
+      operation,
+      destination: Some(args[0].clone()),
+    })
+  }
+}
+
+/// Applies `operation` to the sets at `keys`, where a missing key counts as an empty set.
+pub fn combine_sets(
+  db: &mut Database,
+  keys: &[Bytes],
+  operation: SetOperation,
+) -> Result<SetValue> {
+  let sets = db.get_values::<SetValue>(keys)?;
+  let result = match operation {
+    SetOperation::Inter => {
+      let Some(mut sets) = sets.into_iter().collect::<Option<Vec<_>>>() else {
+        return Ok(SetValue::default());
+      };
+      // only the members of the smallest set can be in all of them
+      sets.sort_by_key(|set| set.len());
+      let (smallest, others) = sets.split_first().expect("arity is checked");
+      smallest
+        .members()
+        .into_iter()
+        .filter(|member| others.iter().all(|set| set.contains(member)))
+        .collect()
+    }
+    SetOperation::Union => sets
+      .into_iter()
+      .flatten()
+      .flat_map(SetValue::members)
+      .collect(),
+    SetOperation::Diff => {
+      let (first, others) = sets.split_first().expect("arity is checked");
+      first
+        .iter()
+        .flat_map(|set| set.members())
+        .filter(|member| others.iter().flatten().all(|set| !set.contains(member)))
+        .collect()
+    }
+  };
+  Ok(result)
+}
@@ -0,0 +1,40 @@
+use bytes::Bytes;
+
+use crate::database::{Data, Value};
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+/// `SETNX`, like `SET key value NX` but replying whether the key was set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Setnx {
+  pub key: Bytes,
+  pub value: Bytes,
+}
+
+impl Execute for Setnx {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Setnx { key, value } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    if db.contains_key(key) {
+      return Ok(Reply::Integer(0));
+    }
+    let data = Data {
+      value: Value::from(value.clone()),
+      expire_time: None,
+    };
+    db.set(key, &data);
+    Ok(Reply::Integer(1))
+  }
+}
+
+impl Parse for Setnx {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Setnx {
+      key: args[0].clone(),
+      value: args[1].clone(),
+    })
+  }
+}
@@ -0,0 +1,73 @@
+use bytes::Bytes;
+
+use crate::config::DEFAULT_MAX_REQUEST_SIZE;
+use crate::database::{Data, StringValue, Value};
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_integer, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Setrange {
+  pub key: Bytes,
+  pub offset: usize,
+  pub value: Bytes,
+}
+
+impl Execute for Setrange {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Setrange { key, offset, value } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let current = db.get_value::<StringValue>(key)?.map(StringValue::to_bytes);
+    // an empty value changes nothing, and doesn't create the key either
+    if value.is_empty() {
+      return Ok(Reply::Integer(
+        current.map_or(0, |current| current.len()) as i64
+      ));
+    }
+    check_string_length(offset + value.len())?;
+
+    let mut string = current.map_or_else(Vec::new, |current| current.to_vec());
+    if string.len() < offset + value.len() {
+      string.resize(offset + value.len(), 0);
+    }
+    string[*offset..offset + value.len()].copy_from_slice(value);
+    let len = string.len();
+    match db.get_value_mut::<StringValue>(key)? {
+      // updated in place, so that the key keeps its expire time
+      Some(current) => *current = StringValue::Raw(Bytes::from(string)),
+      None => {
+        let data = Data {
+          value: Value::String(StringValue::Raw(Bytes::from(string))),
+          expire_time: None,
+        };
+        db.set(key, &data);
+      }
+    }
+    Ok(Reply::Integer(len as i64))
+  }
+}
+
+impl Parse for Setrange {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let Ok(offset) = usize::try_from(parse_integer(&args[1])?) else {
+      bail!(RedisError::Generic("offset is out of range".to_owned()));
+    };
+    Ok(Setrange {
+      key: args[0].clone(),
+      offset,
+      value: args[2].clone(),
+    })
+  }
+}
+
+/// Fails when a command would make a string longer than Redis' `proto-max-bulk-len`.
+pub fn check_string_length(len: usize) -> Result<()> {
+  if len > DEFAULT_MAX_REQUEST_SIZE {
+    bail!(RedisError::Generic(
+      "string exceeds maximum allowed size (proto-max-bulk-len)".to_owned()
+    ));
+  }
+  Ok(())
+}
@@ -0,0 +1,67 @@
+use bytes::Bytes;
+
+use crate::database::SetValue;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_integer, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Sintercard {
+  pub keys: Vec<Bytes>,
+  /// stop counting at this many members; 0 means no limit
+  pub limit: usize,
+}
+
+impl Execute for Sintercard {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Sintercard { keys, limit } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let sets = db.get_values::<SetValue>(keys)?;
+    let Some(mut sets) = sets.into_iter().collect::<Option<Vec<_>>>() else {
+      return Ok(Reply::Integer(0));
+    };
+    sets.sort_by_key(|set| set.len());
+    let (smallest, others) = sets.split_first().expect("numkeys is checked");
+    let limit = if *limit == 0 { usize::MAX } else { *limit };
+    let len = smallest
+      .members()
+      .into_iter()
+      .filter(|member| others.iter().all(|set| set.contains(member)))
+      .take(limit)
+      .count();
+    Ok(Reply::Integer(len as i64))
+  }
+}
+
+impl Parse for Sintercard {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let numkeys = match parse_integer(&args[0]) {
+      Ok(numkeys @ 1..) => numkeys as usize,
+      _ => bail!(RedisError::Generic(
+        "numkeys should be greater than 0".to_owned()
+      )),
+    };
+    let Some(keys) = args.get(1..=numkeys) else {
+      bail!(RedisError::Generic(
+        "Number of keys can't be greater than number of args".to_owned()
+      ));
+    };
+
+    let limit = match &args[numkeys + 1..] {
+      [] => 0,
+      [option, limit] if option.eq_ignore_ascii_case(b"LIMIT") => {
+        match parse_integer(limit).map(usize::try_from) {
+          Ok(Ok(limit)) => limit,
+          _ => bail!(RedisError::Generic("LIMIT can't be negative".to_owned())),
+        }
+      }
+      _ => bail!(RedisError::Syntax),
+    };
+    Ok(Sintercard {
+      keys: keys.to_vec(),
+      limit,
+    })
+  }
+}
@@ -0,0 +1,34 @@
+use bytes::Bytes;
+
+use crate::database::SetValue;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Sismember {
+  pub key: Bytes,
+  pub member: Bytes,
+}
+
+impl Execute for Sismember {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Sismember { key, member } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let is_member = db
+      .get_value::<SetValue>(key)?
+      .is_some_and(|set| set.contains(member));
+    Ok(Reply::Integer(is_member as i64))
+  }
+}
+
+impl Parse for Sismember {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Sismember {
+      key: args[0].clone(),
+      member: args[1].clone(),
+    })
+  }
+}
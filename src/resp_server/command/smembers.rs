@@ -0,0 +1,38 @@
+use bytes::Bytes;
+
+use crate::database::SetValue;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Smembers {
+  pub key: Bytes,
+}
+
+impl Execute for Smembers {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Smembers { key } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let members = db
+      .get_value::<SetValue>(key)?
+      .map(SetValue::members)
+      .unwrap_or_default();
+    Ok(set_reply(members))
+  }
+}
+
+impl Parse for Smembers {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Smembers {
+      key: args[0].clone(),
+    })
+  }
+}
+
+/// Replies with `members` as a set, which RESP2 clients receive as an array.
+pub fn set_reply(members: Vec<Bytes>) -> Reply {
+  Reply::Set(members.into_iter().map(Reply::BulkString).collect())
+}
@@ -0,0 +1,36 @@
+use bytes::Bytes;
+
+use crate::database::SetValue;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Smismember {
+  pub key: Bytes,
+  pub members: Vec<Bytes>,
+}
+
+impl Execute for Smismember {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Smismember { key, members } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let set = db.get_value::<SetValue>(key)?;
+    let replies = members
+      .iter()
+      .map(|member| Reply::Integer(set.is_some_and(|set| set.contains(member)) as i64))
+      .collect();
+    Ok(Reply::Array(replies))
+  }
+}
+
+impl Parse for Smismember {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Smismember {
+      key: args[0].clone(),
+      members: args[1..].to_vec(),
+    })
+  }
+}
@@ -0,0 +1,51 @@
+use bytes::Bytes;
+
+use crate::database::SetValue;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Smove {
+  pub source: Bytes,
+  pub destination: Bytes,
+  pub member: Bytes,
+}
+
+impl Execute for Smove {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Smove {
+      source,
+      destination,
+      member,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    // the destination must hold a set even when nothing ends up moved
+    db.get_value::<SetValue>(destination)?;
+    let Some(set) = db.get_value_mut::<SetValue>(source)? else {
+      return Ok(Reply::Integer(0));
+    };
+    if source == destination {
+      return Ok(Reply::Integer(set.contains(member) as i64));
+    }
+    if !set.remove(member) {
+      return Ok(Reply::Integer(0));
+    }
+    db.remove_if_empty(source);
+    db.get_value_or_default::<SetValue>(destination)?
+      .insert(member.clone());
+    Ok(Reply::Integer(1))
+  }
+}
+
+impl Parse for Smove {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Smove {
+      source: args[0].clone(),
+      destination: args[1].clone(),
+      member: args[2].clone(),
+    })
+  }
+}
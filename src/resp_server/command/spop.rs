@@ -0,0 +1,66 @@
+use bytes::Bytes;
+
+use crate::database::SetValue;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_integer, set_reply, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Spop {
+  pub key: Bytes,
+  pub count: Option<usize>,
+}
+
+impl Execute for Spop {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Spop { key, count } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(set) = db.get_value::<SetValue>(key)? else {
+      return Ok(match count {
+        Some(_) => set_reply(vec![]),
+        None => Reply::NullBulkString,
+      });
+    };
+
+    let mut members = set.members();
+    let popped_len = count.unwrap_or(1).min(members.len());
+    // a partial Fisher-Yates shuffle picks distinct members
+    for i in 0..popped_len {
+      let j = i + db.random_below(members.len() - i);
+      members.swap(i, j);
+    }
+    members.truncate(popped_len);
+
+    let set = db
+      .get_value_mut::<SetValue>(key)?
+      .expect("existence checked above");
+    for member in &members {
+      set.remove(member);
+    }
+    db.remove_if_empty(key);
+
+    Ok(match count {
+      Some(_) => set_reply(members),
+      None => Reply::from(members.pop()),
+    })
+  }
+}
+
+impl Parse for Spop {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let count = match &args[1..] {
+      [] => None,
+      [count] => match usize::try_from(parse_integer(count)?) {
+        Ok(count) => Some(count),
+        Err(_) => bail!(RedisError::NotPositive),
+      },
+      _ => bail!(RedisError::Syntax),
+    };
+    Ok(Spop {
+      key: args[0].clone(),
+      count,
+    })
+  }
+}
@@ -0,0 +1,61 @@
+use bytes::Bytes;
+
+use crate::database::SetValue;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_random_count, pick_with_repetition, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Srandmember {
+  pub key: Bytes,
+  /// a negative count allows the same member to be returned more than once
+  pub count: Option<i64>,
+}
+
+impl Execute for Srandmember {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Srandmember { key, count } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(set) = db.get_value::<SetValue>(key)? else {
+      return Ok(match count {
+        Some(_) => Reply::Array(vec![]),
+        None => Reply::NullBulkString,
+      });
+    };
+    let mut members = set.members();
+
+    let Some(count) = *count else {
+      let index = db.random_below(members.len());
+      return Ok(Reply::BulkString(members.swap_remove(index)));
+    };
+    let picked = if count < 0 {
+      pick_with_repetition(&mut db, &members, count.unsigned_abs())?
+    } else {
+      // a partial Fisher-Yates shuffle picks distinct members
+      let count = (count as usize).min(members.len());
+      for i in 0..count {
+        let j = i + db.random_below(members.len() - i);
+        members.swap(i, j);
+      }
+      members.truncate(count);
+      members
+    };
+    Ok(Reply::from(picked))
+  }
+}
+
+impl Parse for Srandmember {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let count = match &args[1..] {
+      [] => None,
+      [count] => Some(parse_random_count(count)?),
+      _ => bail!(RedisError::Syntax),
+    };
+    Ok(Srandmember {
+      key: args[0].clone(),
+      count,
+    })
+  }
+}
@@ -0,0 +1,36 @@
+use bytes::Bytes;
+
+use crate::database::SetValue;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Srem {
+  pub key: Bytes,
+  pub members: Vec<Bytes>,
+}
+
+impl Execute for Srem {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Srem { key, members } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(set) = db.get_value_mut::<SetValue>(key)? else {
+      return Ok(Reply::Integer(0));
+    };
+    let removed = members.iter().filter(|member| set.remove(member)).count();
+    db.remove_if_empty(key);
+    Ok(Reply::Integer(removed as i64))
+  }
+}
+
+impl Parse for Srem {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Srem {
+      key: args[0].clone(),
+      members: args[1..].to_vec(),
+    })
+  }
+}
@@ -0,0 +1,47 @@
+use crate::database::StreamId;
+use crate::resp_server::{bail, RedisError, Result};
+
+/// Parses an entry ID, `ms-seq`, or just `ms` with `missing_seq` as the sequence number.
+pub fn parse_stream_id(arg: &[u8], missing_seq: u64) -> Result<StreamId> {
+  let arg = String::from_utf8_lossy(arg);
+  let (ms, seq) = match arg.split_once('-') {
+    Some((ms, seq)) => (ms, seq.parse::<u64>()),
+    None => (arg.as_ref(), Ok(missing_seq)),
+  };
+  match (ms.parse::<u64>(), seq) {
+    (Ok(ms), Ok(seq)) => Ok(StreamId { ms, seq }),
+    _ => bail!(RedisError::InvalidStreamId),
+  }
+}
+
+/// Parses the start of an interval: an ID, where a missing sequence number means the first one,
+/// `-` for the smallest ID, or `(ID` to leave that ID out.
+pub fn parse_range_start(arg: &[u8]) -> Result<StreamId> {
+  match arg {
+    b"-" => Ok(StreamId::MIN),
+    b"+" => Ok(StreamId::MAX),
+    [b'(', id @ ..] => match parse_stream_id(id, 0)?.next() {
+      Some(id) => Ok(id),
+      None => bail!(RedisError::Generic(
+        "invalid start ID for the interval".to_owned()
+      )),
+    },
+    id => parse_stream_id(id, 0),
+  }
+}
+
+/// Parses the end of an interval: an ID, where a missing sequence number means the last one,
+/// `+` for the largest ID, or `(ID` to leave that ID out.
+pub fn parse_range_end(arg: &[u8]) -> Result<StreamId> {
+  match arg {
+    b"-" => Ok(StreamId::MIN),
+    b"+" => Ok(StreamId::MAX),
+    [b'(', id @ ..] => match parse_stream_id(id, u64::MAX)?.prev() {
+      Some(id) => Ok(id),
+      None => bail!(RedisError::Generic(
+        "invalid end ID for the interval".to_owned()
+      )),
+    },
+    id => parse_stream_id(id, u64::MAX),
+  }
+}
@@ -0,0 +1,32 @@
+use bytes::Bytes;
+
+use crate::database::StringValue;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Strlen {
+  pub key: Bytes,
+}
+
+impl Execute for Strlen {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Strlen { key } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let len = db
+      .get_value::<StringValue>(key)?
+      .map_or(0, StringValue::len);
+    Ok(Reply::Integer(len as i64))
+  }
+}
+
+impl Parse for Strlen {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Strlen {
+      key: args[0].clone(),
+    })
+  }
+}
@@ -0,0 +1,27 @@
+use bytes::Bytes;
+
+use super::{Execute, ExecutionContext, Parse};
+use crate::resp_server::{Reply, Result};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Touch {
+  pub keys: Vec<Bytes>,
+}
+
+impl Execute for Touch {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Touch { keys } = self;
+    let ExecutionContext { db, .. } = ctx;
+    let mut db = db.lock().unwrap();
+    let count = keys.iter().filter(|key| db.contains_key(key)).count();
+    Ok(Reply::Integer(count as i64))
+  }
+}
+
+impl Parse for Touch {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Touch {
+      keys: args.to_vec(),
+    })
+  }
+}
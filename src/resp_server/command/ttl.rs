@@ -0,0 +1,47 @@
+use bytes::Bytes;
+
+use crate::resp_server::{Context, Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+/// `TTL`, or `PTTL` when `in_millis` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Ttl {
+  pub key: Bytes,
+  pub in_millis: bool,
+}
+
+impl Execute for Ttl {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Ttl { key, in_millis } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let now = db.now();
+    let ttl = match db.get(key) {
+      None => -2,
+      Some(data) => match data.expire_time {
+        None => -1,
+        Some(expire_time) => {
+          let millis =
+            i64::try_from(expire_time.saturating_sub(now)).context("expire time out of range")?;
+          if *in_millis {
+            millis
+          } else {
+            millis.saturating_add(500) / 1000
+          }
+        }
+      },
+    };
+    Ok(Reply::Integer(ttl))
+  }
+}
+
+impl Parse for Ttl {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Ttl {
+      key: args[0].clone(),
+      in_millis: false,
+    })
+  }
+}
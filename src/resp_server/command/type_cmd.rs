@@ -0,0 +1,30 @@
+use bytes::Bytes;
+
+use super::{Execute, ExecutionContext, Parse};
+use crate::resp_server::{Reply, Result};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TypeCmd {
+  pub key: Bytes,
+}
+
+impl Execute for TypeCmd {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let TypeCmd { key } = self;
+    let ExecutionContext { db, .. } = ctx;
+    let type_name = db
+      .lock()
+      .unwrap()
+      .get(key)
+      .map_or("none", |data| data.type_name());
+    Ok(Reply::SimpleString(type_name.to_owned()))
+  }
+}
+
+impl Parse for TypeCmd {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(TypeCmd {
+      key: args[0].clone(),
+    })
+  }
+}
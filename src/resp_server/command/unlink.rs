@@ -0,0 +1,27 @@
+use bytes::Bytes;
+
+use super::{Execute, ExecutionContext, Parse};
+use crate::resp_server::{Reply, Result};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Unlink {
+  pub keys: Vec<Bytes>,
+}
+
+impl Execute for Unlink {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Unlink { keys } = self;
+    let ExecutionContext { db, .. } = ctx;
+    let mut db = db.lock().unwrap();
+    let count = keys.iter().filter(|key| db.unlink(key)).count();
+    Ok(Reply::Integer(count as i64))
+  }
+}
+
+impl Parse for Unlink {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Unlink {
+      keys: args.to_vec(),
+    })
+  }
+}
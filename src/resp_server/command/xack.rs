@@ -0,0 +1,43 @@
+use bytes::Bytes;
+
+use crate::database::{Stream, StreamId};
+use crate::resp_server::{Reply, Result};
+
+use super::{parse_stream_id, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Xack {
+  pub key: Bytes,
+  pub group: Bytes,
+  pub ids: Vec<StreamId>,
+}
+
+impl Execute for Xack {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Xack { key, group, ids } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let group = db
+      .get_value_mut::<Stream>(key)?
+      .and_then(|stream| stream.group_mut(group));
+    let Some(group) = group else {
+      return Ok(Reply::Integer(0));
+    };
+    let acknowledged = ids.iter().filter(|id| group.acknowledge(id)).count();
+    Ok(Reply::Integer(acknowledged as i64))
+  }
+}
+
+impl Parse for Xack {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Xack {
+      key: args[0].clone(),
+      group: args[1].clone(),
+      ids: args[2..]
+        .iter()
+        .map(|id| parse_stream_id(id, 0))
+        .collect::<Result<_>>()?,
+    })
+  }
+}
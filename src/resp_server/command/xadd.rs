@@ -0,0 +1,128 @@
+use bytes::Bytes;
+
+use crate::database::{Stream, StreamId};
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_stream_id, Execute, ExecutionContext, Parse, TrimOptions};
+
+/// The ID `XADD` is asked to give the new entry.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum XaddId {
+  /// `*`: generated from the current time
+  Auto,
+  /// `ms-*`: the next sequence number of the given time
+  AutoSeq(u64),
+  Explicit(StreamId),
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Xadd {
+  pub key: Bytes,
+  /// don't create the stream if it doesn't exist
+  pub nomkstream: bool,
+  pub trim: Option<TrimOptions>,
+  pub id: XaddId,
+  pub fields: Vec<(Bytes, Bytes)>,
+}
+
+impl Execute for Xadd {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Xadd {
+      key,
+      nomkstream,
+      trim,
+      id,
+      fields,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    if *nomkstream && db.get_value::<Stream>(key)?.is_none() {
+      return Ok(Reply::NullBulkString);
+    }
+    let now = db.now() as u64;
+    // a new stream accepts any ID but 0-0, so failing below never leaves an empty stream behind
+    let stream = db.get_value_or_default::<Stream>(key)?;
+
+    let id = match id {
+      XaddId::Auto => match stream.next_id(now) {
+        Some(id) => id,
+        None => bail!(RedisError::Generic(
+          "The stream has exhausted the last possible ID, unable to add more items".to_owned()
+        )),
+      },
+      XaddId::AutoSeq(ms) => stream.next_id_in(*ms).unwrap_or(StreamId::MIN),
+      XaddId::Explicit(id) => *id,
+    };
+    if id <= stream.last_id() {
+      bail!(RedisError::Generic(
+        "The ID specified in XADD is equal or smaller than the target stream top item".to_owned()
+      ));
+    }
+
+    stream.insert(id, fields.clone());
+    if let Some(trim) = trim {
+      stream.trim(trim.strategy, trim.approx, trim.limit);
+    }
+    db.signal_key_as_ready(key);
+    Ok(Reply::BulkString(id.to_bytes()))
+  }
+}
+
+impl Parse for Xadd {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let mut nomkstream = false;
+    let mut trim = None;
+    let mut rest = &args[1..];
+    while let Some(option) = rest.first() {
+      if option.eq_ignore_ascii_case(b"NOMKSTREAM") {
+        nomkstream = true;
+        rest = &rest[1..];
+      } else if option.eq_ignore_ascii_case(b"MAXLEN") || option.eq_ignore_ascii_case(b"MINID") {
+        let (options, used) = TrimOptions::parse(rest)?;
+        trim = Some(options);
+        rest = &rest[used..];
+      } else if option.eq_ignore_ascii_case(b"LIMIT") {
+        // a LIMIT of its own, rather than after MAXLEN or MINID, would be taken for the ID
+        bail!(RedisError::Generic(
+          "syntax error, LIMIT cannot be used without specifying a trimming strategy".to_owned()
+        ));
+      } else {
+        break;
+      }
+    }
+
+    let Some((id, fields)) = rest.split_first() else {
+      bail!(RedisError::WrongArity("xadd".to_owned()));
+    };
+    if fields.is_empty() || !fields.len().is_multiple_of(2) {
+      bail!(RedisError::WrongArity("xadd".to_owned()));
+    }
+    let id = if &id[..] == b"*" {
+      XaddId::Auto
+    } else if let Some(ms) = id.strip_suffix(b"-*") {
+      match String::from_utf8_lossy(ms).parse::<u64>() {
+        Ok(ms) => XaddId::AutoSeq(ms),
+        Err(_) => bail!(RedisError::InvalidStreamId),
+      }
+    } else {
+      match parse_stream_id(id, 0)? {
+        StreamId::MIN => bail!(RedisError::Generic(
+          "The ID specified in XADD must be greater than 0-0".to_owned()
+        )),
+        id => XaddId::Explicit(id),
+      }
+    };
+
+    Ok(Xadd {
+      key: args[0].clone(),
+      nomkstream,
+      trim,
+      id,
+      fields: fields
+        .chunks_exact(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect(),
+    })
+  }
+}
@@ -0,0 +1,140 @@
+use bytes::Bytes;
+
+use crate::database::{Stream, StreamId};
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_integer, parse_range_start, stream_entry_reply};
+use super::{Execute, ExecutionContext, Parse};
+
+/// How many pending entries `XAUTOCLAIM` looks at for each one it may claim.
+const ATTEMPTS_PER_CLAIM: usize = 10;
+
+/// `XAUTOCLAIM`, scanning the pending entries of a group for ones idle for long enough.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Xautoclaim {
+  pub key: Bytes,
+  pub group: Bytes,
+  pub consumer: Bytes,
+  /// only entries delivered at least this many milliseconds ago are claimed
+  pub min_idle_time: u128,
+  pub start: StreamId,
+  /// at most how many entries are claimed
+  pub count: usize,
+  /// reply with the IDs only, leaving the delivery counts alone
+  pub justid: bool,
+}
+
+impl Execute for Xautoclaim {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Xautoclaim {
+      key,
+      group,
+      consumer,
+      min_idle_time,
+      start,
+      count,
+      justid,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let now = db.now();
+    let Some(stream) = db.get_value_mut::<Stream>(key)? else {
+      bail!(RedisError::no_group(key, group));
+    };
+    let Some(claimer) = stream.group_mut(group) else {
+      bail!(RedisError::no_group(key, group));
+    };
+    claimer.touch_consumer(consumer, now);
+    let scanned = claimer
+      .pending_range(*start, StreamId::MAX)
+      .take(count * ATTEMPTS_PER_CLAIM + 1)
+      .map(|(id, pending)| (*id, pending.delivery_time, pending.delivery_count))
+      .collect::<Vec<_>>();
+
+    let (mut claimed, mut deleted, mut next) = (vec![], vec![], StreamId::MIN);
+    for (attempt, (id, delivery_time, delivery_count)) in scanned.into_iter().enumerate() {
+      if attempt == count * ATTEMPTS_PER_CLAIM || claimed.len() == *count {
+        next = id;
+        break;
+      }
+      let exists = stream.get(&id).is_some();
+      let group = stream.group_mut(group).expect("checked above");
+      // entries deleted from the stream can't be claimed anymore
+      if !exists {
+        group.acknowledge(&id);
+        deleted.push(id);
+        continue;
+      }
+      if now.saturating_sub(delivery_time) < *min_idle_time {
+        continue;
+      }
+      let delivery_count = if *justid {
+        delivery_count
+      } else {
+        delivery_count + 1
+      };
+      group.assign(id, consumer, now, delivery_count);
+      claimed.push(id);
+    }
+
+    if !claimed.is_empty() {
+      let group = stream.group_mut(group).expect("checked above");
+      group.touch_consumer(consumer, now).active_time = Some(now);
+    }
+    let claimed = claimed.iter().map(|id| {
+      if *justid {
+        Reply::BulkString(id.to_bytes())
+      } else {
+        stream_entry_reply(id, stream.get(id))
+      }
+    });
+    let deleted = deleted.iter().map(|id| Reply::BulkString(id.to_bytes()));
+    Ok(Reply::Array(vec![
+      Reply::BulkString(next.to_bytes()),
+      Reply::Array(claimed.collect()),
+      Reply::Array(deleted.collect()),
+    ]))
+  }
+}
+
+impl Parse for Xautoclaim {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let Ok(min_idle_time) = parse_integer(&args[3]) else {
+      bail!(RedisError::Generic(
+        "Invalid min-idle-time argument for XAUTOCLAIM".to_owned()
+      ));
+    };
+    let mut count = 100;
+    let mut justid = false;
+    let mut rest = &args[5..];
+    loop {
+      match rest {
+        [] => break,
+        [option, more @ ..] if option.eq_ignore_ascii_case(b"JUSTID") => {
+          justid = true;
+          rest = more;
+        }
+        [option, value, more @ ..] if option.eq_ignore_ascii_case(b"COUNT") => {
+          count = match usize::try_from(parse_integer(value)?) {
+            Ok(count) if count > 0 && count < usize::MAX / ATTEMPTS_PER_CLAIM => count,
+            _ => bail!(RedisError::Generic("COUNT must be > 0".to_owned())),
+          };
+          rest = more;
+        }
+        _ => bail!(RedisError::Syntax),
+      }
+    }
+
+    Ok(Xautoclaim {
+      key: args[0].clone(),
+      group: args[1].clone(),
+      consumer: args[2].clone(),
+      // a negative idle time claims any pending entry
+      min_idle_time: u128::try_from(min_idle_time).unwrap_or(0),
+      start: parse_range_start(&args[4])?,
+      count,
+      justid,
+    })
+  }
+}
@@ -0,0 +1,202 @@
+use bytes::Bytes;
+
+use crate::database::{Stream, StreamId};
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_integer, parse_stream_id, stream_entry_reply};
+use super::{Execute, ExecutionContext, Parse};
+
+/// `XCLAIM`, taking over pending entries idle for long enough.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Xclaim {
+  pub key: Bytes,
+  pub group: Bytes,
+  pub consumer: Bytes,
+  /// only entries delivered at least this many milliseconds ago are claimed
+  pub min_idle_time: u128,
+  pub ids: Vec<StreamId>,
+  /// when the claimed entries count as delivered, now by default
+  pub delivery_time: Option<DeliveryTime>,
+  /// the delivery count the claimed entries get instead of one more
+  pub retry_count: Option<u64>,
+  /// claim entries that are not pending too, as long as they are in the stream
+  pub force: bool,
+  /// reply with the IDs only, leaving the delivery counts alone
+  pub justid: bool,
+  /// moves the last delivered ID of the group forward to it
+  pub last_id: Option<StreamId>,
+}
+
+/// When `XCLAIM` records the claimed entries as delivered.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum DeliveryTime {
+  /// `IDLE`: this many milliseconds ago
+  Idle(i64),
+  /// `TIME`: at this unix time in milliseconds
+  Time(i64),
+}
+
+impl Execute for Xclaim {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Xclaim {
+      key,
+      group,
+      consumer,
+      min_idle_time,
+      ids,
+      delivery_time,
+      retry_count,
+      force,
+      justid,
+      last_id,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let now = db.now();
+    let Some(stream) = db.get_value_mut::<Stream>(key)? else {
+      bail!(RedisError::no_group(key, group));
+    };
+    let Some(claimer) = stream.group_mut(group) else {
+      bail!(RedisError::no_group(key, group));
+    };
+    if let Some(last_id) = last_id {
+      claimer.last_delivered_id = claimer.last_delivered_id.max(*last_id);
+    }
+    claimer.touch_consumer(consumer, now);
+    // times in the future, or before the epoch, are taken as now
+    let now_millis = i64::try_from(now).unwrap_or(i64::MAX);
+    let delivery_time = match delivery_time {
+      Some(DeliveryTime::Idle(idle)) => now_millis.saturating_sub(*idle),
+      Some(DeliveryTime::Time(time)) => *time,
+      None => now_millis,
+    };
+    let delivery_time = u128::try_from(delivery_time)
+      .ok()
+      .filter(|time| *time <= now)
+      .unwrap_or(now);
+
+    let mut claimed = vec![];
+    for id in ids {
+      let exists = stream.get(id).is_some();
+      let group = stream.group_mut(group).expect("checked above");
+      let pending = group.pending_entry(id).cloned();
+      let delivery_count = match pending {
+        // entries deleted from the stream can't be claimed anymore
+        Some(_) if !exists => {
+          group.acknowledge(id);
+          continue;
+        }
+        Some(pending) if now.saturating_sub(pending.delivery_time) < *min_idle_time => continue,
+        Some(pending) => pending.delivery_count,
+        None if *force && exists => 0,
+        None => continue,
+      };
+      let delivery_count = match retry_count {
+        Some(retry_count) => *retry_count,
+        None if *justid => delivery_count,
+        None => delivery_count + 1,
+      };
+      group.assign(*id, consumer, delivery_time, delivery_count);
+      claimed.push(*id);
+    }
+
+    if !claimed.is_empty() {
+      let group = stream.group_mut(group).expect("checked above");
+      group.touch_consumer(consumer, now).active_time = Some(now);
+    }
+    let claimed = claimed.iter().map(|id| {
+      if *justid {
+        Reply::BulkString(id.to_bytes())
+      } else {
+        stream_entry_reply(id, stream.get(id))
+      }
+    });
+    Ok(Reply::Array(claimed.collect()))
+  }
+}
+
+impl Parse for Xclaim {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let Ok(min_idle_time) = parse_integer(&args[3]) else {
+      bail!(RedisError::Generic(
+        "Invalid min-idle-time argument for XCLAIM".to_owned()
+      ));
+    };
+    // like in Redis, IDs go on until an argument isn't one
+    let ids = args[4..]
+      .iter()
+      .map_while(|id| parse_stream_id(id, 0).ok())
+      .collect::<Vec<_>>();
+    if ids.is_empty() {
+      bail!(RedisError::InvalidStreamId);
+    }
+
+    let mut xclaim = Xclaim {
+      key: args[0].clone(),
+      group: args[1].clone(),
+      consumer: args[2].clone(),
+      // a negative idle time claims any pending entry
+      min_idle_time: u128::try_from(min_idle_time).unwrap_or(0),
+      ids,
+      delivery_time: None,
+      retry_count: None,
+      force: false,
+      justid: false,
+      last_id: None,
+    };
+    let mut rest = &args[4 + xclaim.ids.len()..];
+    loop {
+      match rest {
+        [] => break,
+        [option, more @ ..] if option.eq_ignore_ascii_case(b"FORCE") => {
+          xclaim.force = true;
+          rest = more;
+        }
+        [option, more @ ..] if option.eq_ignore_ascii_case(b"JUSTID") => {
+          xclaim.justid = true;
+          rest = more;
+        }
+        [option, value, more @ ..] if option.eq_ignore_ascii_case(b"IDLE") => {
+          let Ok(idle) = parse_integer(value) else {
+            bail!(RedisError::Generic(
+              "Invalid IDLE option argument for XCLAIM".to_owned()
+            ));
+          };
+          xclaim.delivery_time = Some(DeliveryTime::Idle(idle));
+          rest = more;
+        }
+        [option, value, more @ ..] if option.eq_ignore_ascii_case(b"TIME") => {
+          let Ok(time) = parse_integer(value) else {
+            bail!(RedisError::Generic(
+              "Invalid TIME option argument for XCLAIM".to_owned()
+            ));
+          };
+          xclaim.delivery_time = Some(DeliveryTime::Time(time));
+          rest = more;
+        }
+        [option, value, more @ ..] if option.eq_ignore_ascii_case(b"RETRYCOUNT") => {
+          let Some(retry_count) = parse_integer(value)
+            .ok()
+            .and_then(|count| u64::try_from(count).ok())
+          else {
+            bail!(RedisError::Generic(
+              "Invalid RETRYCOUNT option argument for XCLAIM".to_owned()
+            ));
+          };
+          xclaim.retry_count = Some(retry_count);
+          rest = more;
+        }
+        [option, value, more @ ..] if option.eq_ignore_ascii_case(b"LASTID") => {
+          xclaim.last_id = Some(parse_stream_id(value, 0)?);
+          rest = more;
+        }
+        [option, ..] => bail!(RedisError::Generic(format!(
+          "Unrecognized XCLAIM option '{}'",
+          String::from_utf8_lossy(option)
+        ))),
+      }
+    }
+    Ok(xclaim)
+  }
+}
@@ -0,0 +1,38 @@
+use bytes::Bytes;
+
+use crate::database::{Stream, StreamId};
+use crate::resp_server::{Reply, Result};
+
+use super::{parse_stream_id, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Xdel {
+  pub key: Bytes,
+  pub ids: Vec<StreamId>,
+}
+
+impl Execute for Xdel {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Xdel { key, ids } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(stream) = db.get_value_mut::<Stream>(key)? else {
+      return Ok(Reply::Integer(0));
+    };
+    let deleted = ids.iter().filter(|id| stream.remove(id)).count();
+    Ok(Reply::Integer(deleted as i64))
+  }
+}
+
+impl Parse for Xdel {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Xdel {
+      key: args[0].clone(),
+      ids: args[1..]
+        .iter()
+        .map(|id| parse_stream_id(id, 0))
+        .collect::<Result<_>>()?,
+    })
+  }
+}
@@ -0,0 +1,242 @@
+use bytes::Bytes;
+
+use crate::database::{ConsumerGroup, Database, Stream, StreamId};
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_integer, parse_stream_id, Execute, ExecutionContext, Parse};
+
+/// The last delivered ID `XGROUP CREATE` and `XGROUP SETID` give a group.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum GroupStart {
+  Id(StreamId),
+  /// `$`: the last ID of the stream, so that only new entries are delivered
+  LastId,
+}
+
+/// `XGROUP`, managing the consumer groups of a stream and their consumers.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Xgroup {
+  Create {
+    key: Bytes,
+    group: Bytes,
+    start: GroupStart,
+    /// create an empty stream if the key doesn't exist
+    mkstream: bool,
+    entries_read: Option<u64>,
+  },
+  SetId {
+    key: Bytes,
+    group: Bytes,
+    start: GroupStart,
+    entries_read: Option<u64>,
+  },
+  Destroy {
+    key: Bytes,
+    group: Bytes,
+  },
+  CreateConsumer {
+    key: Bytes,
+    group: Bytes,
+    consumer: Bytes,
+  },
+  DelConsumer {
+    key: Bytes,
+    group: Bytes,
+    consumer: Bytes,
+  },
+}
+
+impl Execute for Xgroup {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    if let Xgroup::Create {
+      key,
+      mkstream: true,
+      ..
+    } = self
+    {
+      db.get_value_or_default::<Stream>(key)?;
+    }
+    let now = db.now();
+    let stream = existing_stream(&mut db, self.key())?;
+
+    match self {
+      Xgroup::Create {
+        group,
+        start,
+        entries_read,
+        ..
+      } => {
+        let id = start.resolve(stream);
+        if !stream.create_group(group.clone(), ConsumerGroup::new(id, *entries_read)) {
+          bail!(RedisError::BusyGroup);
+        }
+        Ok(Reply::ok())
+      }
+      Xgroup::SetId {
+        key,
+        group,
+        start,
+        entries_read,
+      } => {
+        let id = start.resolve(stream);
+        let Some(group) = stream.group_mut(group) else {
+          bail!(no_such_group(key, group));
+        };
+        group.last_delivered_id = id;
+        group.entries_read = *entries_read;
+        Ok(Reply::ok())
+      }
+      Xgroup::Destroy { group, .. } => Ok(Reply::Integer(stream.destroy_group(group).into())),
+      Xgroup::CreateConsumer {
+        key,
+        group,
+        consumer,
+      } => {
+        let Some(group) = stream.group_mut(group) else {
+          bail!(no_such_group(key, group));
+        };
+        Ok(Reply::Integer(group.create_consumer(consumer, now).into()))
+      }
+      Xgroup::DelConsumer {
+        key,
+        group,
+        consumer,
+      } => {
+        let Some(group) = stream.group_mut(group) else {
+          bail!(no_such_group(key, group));
+        };
+        let pending = group.remove_consumer(consumer).unwrap_or(0);
+        Ok(Reply::Integer(pending as i64))
+      }
+    }
+  }
+}
+
+impl Xgroup {
+  fn key(&self) -> &Bytes {
+    match self {
+      Xgroup::Create { key, .. }
+      | Xgroup::SetId { key, .. }
+      | Xgroup::Destroy { key, .. }
+      | Xgroup::CreateConsumer { key, .. }
+      | Xgroup::DelConsumer { key, .. } => key,
+    }
+  }
+}
+
+impl GroupStart {
+  fn resolve(self, stream: &Stream) -> StreamId {
+    match self {
+      GroupStart::Id(id) => id,
+      GroupStart::LastId => stream.last_id(),
+    }
+  }
+}
+
+fn existing_stream<'a>(db: &'a mut Database, key: &[u8]) -> Result<&'a mut Stream> {
+  match db.get_value_mut::<Stream>(key)? {
+    Some(stream) => Ok(stream),
+    None => bail!(RedisError::Generic(
+      "The XGROUP subcommand requires the key to exist. Note that for CREATE you may want to use \
+       the MKSTREAM option to create an empty stream automatically."
+        .to_owned()
+    )),
+  }
+}
+
+/// NOGROUP as `XGROUP` and `XINFO` report a missing consumer group.
+pub fn no_such_group(key: &[u8], group: &[u8]) -> RedisError {
+  RedisError::NoGroup(format!(
+    "No such consumer group '{}' for key name '{}'",
+    String::from_utf8_lossy(group),
+    String::from_utf8_lossy(key)
+  ))
+}
+
+impl Parse for Xgroup {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let subcommand = String::from_utf8_lossy(&args[0]).to_uppercase();
+    match (subcommand.as_str(), &args[1..]) {
+      ("CREATE", [key, group, start, options @ ..]) => {
+        let mut mkstream = false;
+        let mut entries_read = None;
+        let mut rest = options;
+        loop {
+          match rest {
+            [] => break,
+            [option, more @ ..] if option.eq_ignore_ascii_case(b"MKSTREAM") => {
+              mkstream = true;
+              rest = more;
+            }
+            [option, value, more @ ..] if option.eq_ignore_ascii_case(b"ENTRIESREAD") => {
+              entries_read = parse_entries_read(value)?;
+              rest = more;
+            }
+            _ => bail!(RedisError::Syntax),
+          }
+        }
+        Ok(Xgroup::Create {
+          key: key.clone(),
+          group: group.clone(),
+          start: parse_group_start(start)?,
+          mkstream,
+          entries_read,
+        })
+      }
+      ("SETID", [key, group, start, options @ ..]) => {
+        let entries_read = match options {
+          [] => None,
+          [option, value] if option.eq_ignore_ascii_case(b"ENTRIESREAD") => {
+            parse_entries_read(value)?
+          }
+          _ => bail!(RedisError::Syntax),
+        };
+        Ok(Xgroup::SetId {
+          key: key.clone(),
+          group: group.clone(),
+          start: parse_group_start(start)?,
+          entries_read,
+        })
+      }
+      ("DESTROY", [key, group]) => Ok(Xgroup::Destroy {
+        key: key.clone(),
+        group: group.clone(),
+      }),
+      ("CREATECONSUMER", [key, group, consumer]) => Ok(Xgroup::CreateConsumer {
+        key: key.clone(),
+        group: group.clone(),
+        consumer: consumer.clone(),
+      }),
+      ("DELCONSUMER", [key, group, consumer]) => Ok(Xgroup::DelConsumer {
+        key: key.clone(),
+        group: group.clone(),
+        consumer: consumer.clone(),
+      }),
+      _ => bail!(RedisError::Generic(format!(
+        "unknown subcommand or wrong number of arguments for '{}'. Try XGROUP HELP.",
+        String::from_utf8_lossy(&args[0])
+      ))),
+    }
+  }
+}
+
+fn parse_group_start(arg: &[u8]) -> Result<GroupStart> {
+  if arg == b"$" {
+    return Ok(GroupStart::LastId);
+  }
+  Ok(GroupStart::Id(parse_stream_id(arg, 0)?))
+}
+
+/// `ENTRIESREAD`, where -1 means the number of entries read is unknown.
+fn parse_entries_read(arg: &[u8]) -> Result<Option<u64>> {
+  match parse_integer(arg)? {
+    -1 => Ok(None),
+    entries_read if entries_read >= 0 => Ok(Some(entries_read as u64)),
+    _ => bail!(RedisError::Generic(
+      "value for ENTRIESREAD must be positive or -1".to_owned()
+    )),
+  }
+}
@@ -0,0 +1,228 @@
+use bytes::Bytes;
+
+use crate::database::{Stream, StreamId};
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{no_such_group, parse_integer, stream_entry_reply, Execute, ExecutionContext, Parse};
+
+/// How many entries, and pending entries of each group and consumer, `XINFO STREAM key FULL`
+/// lists without `COUNT`.
+const FULL_DEFAULT_COUNT: usize = 10;
+
+/// `XINFO`, describing a stream, its consumer groups or the consumers of one of them. `full` is
+/// how many entries `XINFO STREAM key FULL` lists, `None` for the summary of the stream.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Xinfo {
+  Stream { key: Bytes, full: Option<usize> },
+  Groups { key: Bytes },
+  Consumers { key: Bytes, group: Bytes },
+}
+
+impl Execute for Xinfo {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let now = db.now();
+    let key = match self {
+      Xinfo::Stream { key, .. } | Xinfo::Groups { key } | Xinfo::Consumers { key, .. } => key,
+    };
+    let Some(stream) = db.get_value::<Stream>(key)? else {
+      bail!(RedisError::Generic("no such key".to_owned()));
+    };
+
+    match self {
+      Xinfo::Stream {
+        full: Some(count), ..
+      } => Ok(full_stream_reply(stream, *count)),
+      Xinfo::Stream { .. } => {
+        let entry_reply = |entry: Option<(&StreamId, _)>| match entry {
+          Some((id, fields)) => stream_entry_reply(id, Some(fields)),
+          None => Reply::NullBulkString,
+        };
+        let first_id = stream.first_entry().map_or(StreamId::MIN, |(id, _)| *id);
+        Ok(Reply::Map(vec![
+          (bulk("length"), Reply::Integer(stream.len() as i64)),
+          (bulk("last-generated-id"), id_reply(stream.last_id())),
+          (
+            bulk("max-deleted-entry-id"),
+            id_reply(stream.max_deleted_id()),
+          ),
+          (
+            bulk("entries-added"),
+            Reply::Integer(stream.entries_added() as i64),
+          ),
+          (bulk("recorded-first-entry-id"), id_reply(first_id)),
+          (
+            bulk("groups"),
+            Reply::Integer(stream.groups().count() as i64),
+          ),
+          (bulk("first-entry"), entry_reply(stream.first_entry())),
+          (bulk("last-entry"), entry_reply(stream.last_entry())),
+        ]))
+      }
+      Xinfo::Groups { .. } => {
+        let groups = stream.groups().map(|(name, group)| {
+          Reply::Map(vec![
+            (bulk("name"), Reply::BulkString(name.clone())),
+            (
+              bulk("consumers"),
+              Reply::Integer(group.consumers().count() as i64),
+            ),
+            (bulk("pending"), Reply::Integer(group.pending_len() as i64)),
+            (bulk("last-delivered-id"), id_reply(group.last_delivered_id)),
+            (bulk("entries-read"), optional_integer(group.entries_read)),
+            (bulk("lag"), optional_integer(stream.group_lag(group))),
+          ])
+        });
+        Ok(Reply::Array(groups.collect()))
+      }
+      Xinfo::Consumers { group: name, .. } => {
+        let Some(group) = stream.group(name) else {
+          bail!(no_such_group(key, name));
+        };
+        let consumers = group.consumers().map(|(name, consumer)| {
+          let inactive = consumer
+            .active_time
+            .map_or(-1, |active_time| now.saturating_sub(active_time) as i64);
+          Reply::Map(vec![
+            (bulk("name"), Reply::BulkString(name.clone())),
+            (
+              bulk("pending"),
+              Reply::Integer(consumer.pending_len() as i64),
+            ),
+            (
+              bulk("idle"),
+              Reply::Integer(now.saturating_sub(consumer.seen_time) as i64),
+            ),
+            (bulk("inactive"), Reply::Integer(inactive)),
+          ])
+        });
+        Ok(Reply::Array(consumers.collect()))
+      }
+    }
+  }
+}
+
+/// Everything about a stream, listing at most `count` of its entries and of the pending entries of
+/// each group and consumer, or all of them when `count` is 0.
+fn full_stream_reply(stream: &Stream, count: usize) -> Reply {
+  let count = if count == 0 { usize::MAX } else { count };
+  let first_id = stream.first_entry().map_or(StreamId::MIN, |(id, _)| *id);
+  let entries = stream
+    .range(StreamId::MIN..=StreamId::MAX, false)
+    .take(count)
+    .map(|(id, fields)| stream_entry_reply(id, Some(fields)));
+  let groups = stream.groups().map(|(name, group)| {
+    let pending = group
+      .pending_range(StreamId::MIN, StreamId::MAX)
+      .take(count)
+      .map(|(id, pending)| {
+        Reply::Array(vec![
+          id_reply(*id),
+          Reply::BulkString(pending.consumer.clone()),
+          Reply::Integer(pending.delivery_time as i64),
+          Reply::Integer(pending.delivery_count as i64),
+        ])
+      });
+    let consumers = group.consumers().map(|(name, consumer)| {
+      let pending = consumer
+        .pending()
+        .filter_map(|id| Some((id, group.pending_entry(id)?)))
+        .take(count)
+        .map(|(id, pending)| {
+          Reply::Array(vec![
+            id_reply(*id),
+            Reply::Integer(pending.delivery_time as i64),
+            Reply::Integer(pending.delivery_count as i64),
+          ])
+        });
+      Reply::Map(vec![
+        (bulk("name"), Reply::BulkString(name.clone())),
+        (bulk("seen-time"), Reply::Integer(consumer.seen_time as i64)),
+        (
+          bulk("active-time"),
+          Reply::Integer(consumer.active_time.map_or(-1, |time| time as i64)),
+        ),
+        (
+          bulk("pel-count"),
+          Reply::Integer(consumer.pending_len() as i64),
+        ),
+        (bulk("pending"), Reply::Array(pending.collect())),
+      ])
+    });
+    Reply::Map(vec![
+      (bulk("name"), Reply::BulkString(name.clone())),
+      (bulk("last-delivered-id"), id_reply(group.last_delivered_id)),
+      (bulk("entries-read"), optional_integer(group.entries_read)),
+      (bulk("lag"), optional_integer(stream.group_lag(group))),
+      (
+        bulk("pel-count"),
+        Reply::Integer(group.pending_len() as i64),
+      ),
+      (bulk("pending"), Reply::Array(pending.collect())),
+      (bulk("consumers"), Reply::Array(consumers.collect())),
+    ])
+  });
+  Reply::Map(vec![
+    (bulk("length"), Reply::Integer(stream.len() as i64)),
+    (bulk("last-generated-id"), id_reply(stream.last_id())),
+    (
+      bulk("max-deleted-entry-id"),
+      id_reply(stream.max_deleted_id()),
+    ),
+    (
+      bulk("entries-added"),
+      Reply::Integer(stream.entries_added() as i64),
+    ),
+    (bulk("recorded-first-entry-id"), id_reply(first_id)),
+    (bulk("entries"), Reply::Array(entries.collect())),
+    (bulk("groups"), Reply::Array(groups.collect())),
+  ])
+}
+
+fn id_reply(id: StreamId) -> Reply {
+  Reply::BulkString(id.to_bytes())
+}
+
+fn optional_integer(value: Option<u64>) -> Reply {
+  value.map_or(Reply::NullBulkString, |value| Reply::Integer(value as i64))
+}
+
+fn bulk(string: &str) -> Reply {
+  Reply::BulkString(Bytes::copy_from_slice(string.as_bytes()))
+}
+
+impl Parse for Xinfo {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let subcommand = String::from_utf8_lossy(&args[0]).to_uppercase();
+    match (subcommand.as_str(), &args[1..]) {
+      ("STREAM", [key, options @ ..]) => {
+        let full = match options {
+          [] => None,
+          [full] if full.eq_ignore_ascii_case(b"FULL") => Some(FULL_DEFAULT_COUNT),
+          // like Redis, a negative count means the default
+          [full, option, count]
+            if full.eq_ignore_ascii_case(b"FULL") && option.eq_ignore_ascii_case(b"COUNT") =>
+          {
+            Some(usize::try_from(parse_integer(count)?).unwrap_or(FULL_DEFAULT_COUNT))
+          }
+          _ => bail!(RedisError::Syntax),
+        };
+        Ok(Xinfo::Stream {
+          key: key.clone(),
+          full,
+        })
+      }
+      ("GROUPS", [key]) => Ok(Xinfo::Groups { key: key.clone() }),
+      ("CONSUMERS", [key, group]) => Ok(Xinfo::Consumers {
+        key: key.clone(),
+        group: group.clone(),
+      }),
+      _ => bail!(RedisError::Generic(format!(
+        "unknown subcommand or wrong number of arguments for '{}'. Try XINFO HELP.",
+        String::from_utf8_lossy(&args[0])
+      ))),
+    }
+  }
+}
@@ -0,0 +1,30 @@
+use bytes::Bytes;
+
+use crate::database::Stream;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Xlen {
+  pub key: Bytes,
+}
+
+impl Execute for Xlen {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Xlen { key } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let len = db.get_value::<Stream>(key)?.map_or(0, Stream::len);
+    Ok(Reply::Integer(len as i64))
+  }
+}
+
+impl Parse for Xlen {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Xlen {
+      key: args[0].clone(),
+    })
+  }
+}
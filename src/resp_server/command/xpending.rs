@@ -0,0 +1,135 @@
+use bytes::Bytes;
+
+use crate::database::{Stream, StreamId};
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_integer, parse_range_end, parse_range_start, Execute, ExecutionContext, Parse};
+
+/// The extended form of `XPENDING`, listing pending entries one by one.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct PendingRange {
+  /// only entries delivered at least this many milliseconds ago
+  pub min_idle_time: Option<u128>,
+  pub start: StreamId,
+  pub end: StreamId,
+  pub count: usize,
+  pub consumer: Option<Bytes>,
+}
+
+/// `XPENDING`, summing up the pending entries of a group unless a `range` is given.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Xpending {
+  pub key: Bytes,
+  pub group: Bytes,
+  pub range: Option<PendingRange>,
+}
+
+impl Execute for Xpending {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Xpending { key, group, range } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let now = db.now();
+    let stream = db.get_value::<Stream>(key)?;
+    let Some(group) = stream.and_then(|stream| stream.group(group)) else {
+      bail!(RedisError::no_group(key, group));
+    };
+
+    let Some(PendingRange {
+      min_idle_time,
+      start,
+      end,
+      count,
+      consumer,
+    }) = range
+    else {
+      let mut pending = group.pending_range(StreamId::MIN, StreamId::MAX);
+      let Some((first_id, _)) = pending.next() else {
+        return Ok(Reply::Array(vec![
+          Reply::Integer(0),
+          Reply::NullBulkString,
+          Reply::NullBulkString,
+          Reply::NullArray,
+        ]));
+      };
+      let last_id = pending.last().map_or(first_id, |(id, _)| id);
+      let consumers = group
+        .consumers()
+        .filter(|(_, consumer)| consumer.pending_len() > 0)
+        .map(|(name, consumer)| {
+          Reply::Array(vec![
+            Reply::BulkString(name.clone()),
+            Reply::BulkString(Bytes::from(consumer.pending_len().to_string())),
+          ])
+        });
+      return Ok(Reply::Array(vec![
+        Reply::Integer(group.pending_len() as i64),
+        Reply::BulkString(first_id.to_bytes()),
+        Reply::BulkString(last_id.to_bytes()),
+        Reply::Array(consumers.collect()),
+      ]));
+    };
+
+    let entries = group
+      .pending_range(*start, *end)
+      .filter(|(_, pending)| {
+        consumer
+          .as_ref()
+          .is_none_or(|name| pending.consumer == name)
+      })
+      .map(|(id, pending)| (id, pending, now.saturating_sub(pending.delivery_time)))
+      .filter(|(_, _, idle)| min_idle_time.is_none_or(|min_idle_time| *idle >= min_idle_time))
+      .take(*count)
+      .map(|(id, pending, idle)| {
+        Reply::Array(vec![
+          Reply::BulkString(id.to_bytes()),
+          Reply::BulkString(pending.consumer.clone()),
+          Reply::Integer(idle as i64),
+          Reply::Integer(pending.delivery_count as i64),
+        ])
+      });
+    Ok(Reply::Array(entries.collect()))
+  }
+}
+
+impl Parse for Xpending {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let (key, group) = (args[0].clone(), args[1].clone());
+    let (min_idle_time, rest) = match &args[2..] {
+      [] => {
+        return Ok(Xpending {
+          key,
+          group,
+          range: None,
+        })
+      }
+      [option, idle, rest @ ..] if option.eq_ignore_ascii_case(b"IDLE") => {
+        // a negative idle time filters nothing out
+        (
+          Some(u128::try_from(parse_integer(idle)?).unwrap_or(0)),
+          rest,
+        )
+      }
+      rest => (None, rest),
+    };
+    let (start, end, count, consumer) = match rest {
+      [start, end, count] => (start, end, count, None),
+      [start, end, count, consumer] => (start, end, count, Some(consumer.clone())),
+      _ => bail!(RedisError::Syntax),
+    };
+
+    Ok(Xpending {
+      key,
+      group,
+      range: Some(PendingRange {
+        min_idle_time,
+        start: parse_range_start(start)?,
+        end: parse_range_end(end)?,
+        // a negative count asks for nothing, like in Redis
+        count: usize::try_from(parse_integer(count)?).unwrap_or(0),
+        consumer,
+      }),
+    })
+  }
+}
@@ -0,0 +1,95 @@
+use bytes::Bytes;
+
+use crate::database::{Stream, StreamFields, StreamId};
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_integer, parse_range_end, parse_range_start};
+use super::{Execute, ExecutionContext, Parse};
+
+/// `XRANGE`, or `XREVRANGE` when `rev` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Xrange {
+  pub key: Bytes,
+  pub start: StreamId,
+  pub end: StreamId,
+  pub count: Option<usize>,
+  pub rev: bool,
+}
+
+impl Execute for Xrange {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Xrange {
+      key,
+      start,
+      end,
+      count,
+      rev,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(stream) = db.get_value::<Stream>(key)? else {
+      return Ok(Reply::Array(vec![]));
+    };
+    let entries = stream
+      .range(*start..=*end, *rev)
+      .take(count.unwrap_or(usize::MAX));
+    Ok(stream_entries_reply(entries))
+  }
+}
+
+impl Parse for Xrange {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Xrange::parse_from(args, false)
+  }
+}
+
+impl Xrange {
+  /// `XRANGE key start end [COUNT count]`, or `XREVRANGE key end start [COUNT count]`
+  pub fn parse_from(args: &[Bytes], rev: bool) -> Result<Self> {
+    let (start, end) = if rev {
+      (&args[2], &args[1])
+    } else {
+      (&args[1], &args[2])
+    };
+    let count = match &args[3..] {
+      [] => None,
+      // a negative count asks for nothing, like in Redis
+      [option, count] if option.eq_ignore_ascii_case(b"COUNT") => {
+        Some(usize::try_from(parse_integer(count)?).unwrap_or(0))
+      }
+      _ => bail!(RedisError::Syntax),
+    };
+    Ok(Xrange {
+      key: args[0].clone(),
+      start: parse_range_start(start)?,
+      end: parse_range_end(end)?,
+      count,
+      rev,
+    })
+  }
+}
+
+/// Replies with stream entries, each one an array of its ID and its fields and values.
+pub fn stream_entries_reply<'a>(
+  entries: impl Iterator<Item = (&'a StreamId, &'a StreamFields)>,
+) -> Reply {
+  let entries = entries.map(|(id, fields)| stream_entry_reply(id, Some(fields)));
+  Reply::Array(entries.collect())
+}
+
+/// Replies with the ID and the fields and values of an entry, which are null when it was
+/// deleted.
+pub fn stream_entry_reply(id: &StreamId, fields: Option<&StreamFields>) -> Reply {
+  let fields = match fields {
+    Some(fields) => Reply::Array(
+      fields
+        .iter()
+        .flat_map(|(field, value)| [field.clone(), value.clone()])
+        .map(Reply::BulkString)
+        .collect(),
+    ),
+    None => Reply::NullArray,
+  };
+  Reply::Array(vec![Reply::BulkString(id.to_bytes()), fields])
+}
@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use crate::database::{Database, Stream, StreamId};
+use crate::resp_server::{bail, Protocol, RedisError, Reply, Result};
+
+use super::{parse_integer, parse_stream_id, parse_timeout_millis, serve_or_block};
+use super::{stream_entries_reply, Execute, ExecutionContext, Parse};
+
+/// Where `XREAD` starts reading a stream.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ReadFrom {
+  /// entries after this ID
+  After(StreamId),
+  /// `$`: only entries added after the command was sent
+  NewEntries,
+  /// `+`: the last entry, or the next one added when there is none
+  LastEntry,
+}
+
+impl ReadFrom {
+  /// The ID entries must come after to be read from `stream` now. `$` and `+` are pinned to the
+  /// last ID of the stream the first time, so that a blocked client later gets what came next.
+  fn resolve(&mut self, stream: Option<&Stream>) -> StreamId {
+    let last_id = stream.map_or(StreamId::MIN, Stream::last_id);
+    match *self {
+      ReadFrom::After(id) => id,
+      ReadFrom::NewEntries => {
+        *self = ReadFrom::After(last_id);
+        last_id
+      }
+      ReadFrom::LastEntry => {
+        *self = ReadFrom::After(last_id);
+        let last_entry = stream.and_then(Stream::last_entry);
+        // entry IDs are greater than 0-0, so the one before is always there
+        last_entry.map_or(last_id, |(id, _)| id.prev().expect("greater than 0-0"))
+      }
+    }
+  }
+}
+
+/// `XREAD`, which waits for new entries when `blocking` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Xread {
+  pub keys: Vec<Bytes>,
+  pub from: Vec<ReadFrom>,
+  /// at most how many entries are read from each stream
+  pub count: Option<usize>,
+  pub blocking: bool,
+  /// how long `XREAD BLOCK` waits, `None` meaning forever
+  pub timeout: Option<Duration>,
+}
+
+impl Execute for Xread {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Xread {
+      keys,
+      from,
+      count,
+      blocking,
+      timeout,
+    } = self;
+    let ExecutionContext { db, session } = ctx;
+
+    let (keys_to_read, mut from, count) = (keys.clone(), from.clone(), count.unwrap_or(usize::MAX));
+    // the reply may be built while serving another client, in the protocol of this one
+    let protocol = session.borrow().protocol;
+    let mut serve = move |db: &mut Database| {
+      let mut streams = vec![];
+      for (key, from) in keys_to_read.iter().zip(&mut from) {
+        let stream = db.get_value::<Stream>(key)?;
+        let Some(start) = from.resolve(stream).next() else {
+          continue;
+        };
+        let Some(stream) = stream else {
+          continue;
+        };
+        let mut entries = stream
+          .range(start..=StreamId::MAX, false)
+          .take(count)
+          .peekable();
+        if entries.peek().is_some() {
+          streams.push((
+            Reply::BulkString(key.clone()),
+            stream_entries_reply(entries),
+          ));
+        }
+      }
+
+      if streams.is_empty() {
+        return Ok(None);
+      }
+      Ok(Some(streams_reply(streams, protocol)))
+    };
+
+    if *blocking {
+      return serve_or_block(ctx, keys, *timeout, Reply::NullArray, serve);
+    }
+    let reply = serve(&mut db.lock().unwrap())?;
+    Ok(reply.unwrap_or(Reply::NullArray))
+  }
+}
+
+impl Parse for Xread {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let mut count = None;
+    let mut blocking = false;
+    let mut timeout = None;
+    let mut rest = args;
+    loop {
+      match rest {
+        [option, value, ..] if option.eq_ignore_ascii_case(b"COUNT") => {
+          // like in Redis, a count that isn't positive means no limit
+          count = usize::try_from(parse_integer(value)?)
+            .ok()
+            .filter(|count| *count > 0);
+          rest = &rest[2..];
+        }
+        [option, value, ..] if option.eq_ignore_ascii_case(b"BLOCK") => {
+          blocking = true;
+          timeout = parse_timeout_millis(value)?;
+          rest = &rest[2..];
+        }
+        [option, streams @ ..] if option.eq_ignore_ascii_case(b"STREAMS") => {
+          rest = streams;
+          break;
+        }
+        _ => bail!(RedisError::Syntax),
+      }
+    }
+
+    if rest.is_empty() || !rest.len().is_multiple_of(2) {
+      bail!(RedisError::Generic(
+        "Unbalanced 'xread' list of streams: for each stream key an ID or '$' must be specified."
+          .to_owned()
+      ));
+    }
+    let (keys, ids) = rest.split_at(rest.len() / 2);
+    let from = ids
+      .iter()
+      .map(|id| match &id[..] {
+        b"$" => Ok(ReadFrom::NewEntries),
+        b"+" => Ok(ReadFrom::LastEntry),
+        id => Ok(ReadFrom::After(parse_stream_id(id, 0)?)),
+      })
+      .collect::<Result<_>>()?;
+
+    Ok(Xread {
+      keys: keys.to_vec(),
+      from,
+      count,
+      blocking,
+      timeout,
+    })
+  }
+}
+
+/// Replies with the entries read from each stream: a map from stream key to entries for RESP3
+/// clients, an array of key and entries pairs for RESP2 ones.
+pub fn streams_reply(streams: Vec<(Reply, Reply)>, protocol: Protocol) -> Reply {
+  match protocol {
+    Protocol::Resp2 => Reply::Array(
+      streams
+        .into_iter()
+        .map(|(key, entries)| Reply::Array(vec![key, entries]))
+        .collect(),
+    ),
+    Protocol::Resp3 => Reply::Map(streams),
+  }
+}
@@ -0,0 +1,222 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use crate::database::{Database, Stream, StreamId};
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_integer, parse_stream_id, parse_timeout_millis, serve_or_block};
+use super::{stream_entries_reply, stream_entry_reply, streams_reply};
+use super::{Execute, ExecutionContext, Parse};
+
+/// What `XREADGROUP` reads from a stream.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ReadGroupFrom {
+  /// `>`: entries never delivered to the group
+  NewEntries,
+  /// entries after this ID still pending for the consumer
+  History(StreamId),
+}
+
+/// `XREADGROUP`, which waits for new entries when `blocking` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Xreadgroup {
+  pub group: Bytes,
+  pub consumer: Bytes,
+  pub keys: Vec<Bytes>,
+  pub from: Vec<ReadGroupFrom>,
+  /// at most how many entries are read from each stream
+  pub count: Option<usize>,
+  pub blocking: bool,
+  /// how long `XREADGROUP BLOCK` waits, `None` meaning forever
+  pub timeout: Option<Duration>,
+  /// don't add the delivered entries to the pending entries
+  pub noack: bool,
+}
+
+impl Execute for Xreadgroup {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Xreadgroup {
+      group,
+      consumer,
+      keys,
+      from,
+      count,
+      blocking,
+      timeout,
+      noack,
+    } = self;
+    let ExecutionContext { db, session } = ctx;
+
+    let (group, consumer, keys_to_read, from) =
+      (group.clone(), consumer.clone(), keys.clone(), from.clone());
+    let (count, noack) = (count.unwrap_or(usize::MAX), *noack);
+    // the reply may be built while serving another client, in the protocol of this one
+    let protocol = session.borrow().protocol;
+    let serve = move |db: &mut Database| {
+      for key in &keys_to_read {
+        let stream = db.get_value::<Stream>(key)?;
+        if stream.and_then(|stream| stream.group(&group)).is_none() {
+          bail!(RedisError::NoGroup(format!(
+            "No such key '{}' or consumer group '{}' in XREADGROUP with GROUP option",
+            String::from_utf8_lossy(key),
+            String::from_utf8_lossy(&group)
+          )));
+        }
+      }
+
+      let now = db.now();
+      let mut streams = vec![];
+      for (key, from) in keys_to_read.iter().zip(&from) {
+        let stream = db.get_value_mut::<Stream>(key)?.expect("checked above");
+        let entries = match from {
+          ReadGroupFrom::NewEntries => {
+            let entries = stream
+              .read_group(&group, &consumer, count, noack, now)
+              .expect("checked above");
+            if entries.is_empty() {
+              continue;
+            }
+            stream_entries_reply(entries.iter().map(|(id, fields)| (id, fields)))
+          }
+          // the history of a consumer is replied even when empty, so it never blocks
+          ReadGroupFrom::History(after) => {
+            read_history(stream, &group, &consumer, *after, count, now)
+          }
+        };
+        streams.push((Reply::BulkString(key.clone()), entries));
+      }
+
+      if streams.is_empty() {
+        return Ok(None);
+      }
+      Ok(Some(streams_reply(streams, protocol)))
+    };
+
+    if *blocking {
+      return serve_or_block(ctx, keys, *timeout, Reply::NullArray, serve);
+    }
+    let reply = serve(&mut db.lock().unwrap())?;
+    Ok(reply.unwrap_or(Reply::NullArray))
+  }
+}
+
+/// Delivers again up to `count` entries after `after` still pending for `consumer`. Those deleted
+/// from the stream since are replied with null fields.
+fn read_history(
+  stream: &mut Stream,
+  group: &[u8],
+  consumer: &Bytes,
+  after: StreamId,
+  count: usize,
+  now: u128,
+) -> Reply {
+  let reader = stream
+    .group_mut(group)
+    .expect("checked by the caller")
+    .touch_consumer(consumer, now);
+  let ids = reader
+    .pending()
+    .skip_while(|id| **id <= after)
+    .take(count)
+    .copied()
+    .collect::<Vec<_>>();
+  let entries = ids
+    .into_iter()
+    .map(|id| (id, stream.get(&id).cloned()))
+    .collect::<Vec<_>>();
+
+  let group = stream.group_mut(group).expect("checked by the caller");
+  for (id, fields) in &entries {
+    if fields.is_some() {
+      let delivery_count = group
+        .pending_entry(id)
+        .map_or(0, |pending| pending.delivery_count);
+      group.assign(*id, consumer, now, delivery_count + 1);
+    }
+  }
+  Reply::Array(
+    entries
+      .iter()
+      .map(|(id, fields)| stream_entry_reply(id, fields.as_ref()))
+      .collect(),
+  )
+}
+
+impl Parse for Xreadgroup {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let mut group_and_consumer = None;
+    let mut count = None;
+    let mut blocking = false;
+    let mut timeout = None;
+    let mut noack = false;
+    let mut rest = args;
+    loop {
+      match rest {
+        [option, group, consumer, ..] if option.eq_ignore_ascii_case(b"GROUP") => {
+          group_and_consumer = Some((group.clone(), consumer.clone()));
+          rest = &rest[3..];
+        }
+        [option, value, ..] if option.eq_ignore_ascii_case(b"COUNT") => {
+          // like in Redis, a count that isn't positive means no limit
+          count = usize::try_from(parse_integer(value)?)
+            .ok()
+            .filter(|count| *count > 0);
+          rest = &rest[2..];
+        }
+        [option, value, ..] if option.eq_ignore_ascii_case(b"BLOCK") => {
+          blocking = true;
+          timeout = parse_timeout_millis(value)?;
+          rest = &rest[2..];
+        }
+        [option, ..] if option.eq_ignore_ascii_case(b"NOACK") => {
+          noack = true;
+          rest = &rest[1..];
+        }
+        [option, streams @ ..] if option.eq_ignore_ascii_case(b"STREAMS") => {
+          rest = streams;
+          break;
+        }
+        _ => bail!(RedisError::Syntax),
+      }
+    }
+
+    let Some((group, consumer)) = group_and_consumer else {
+      bail!(RedisError::Generic(
+        "Missing GROUP option for XREADGROUP".to_owned()
+      ));
+    };
+    if rest.is_empty() || !rest.len().is_multiple_of(2) {
+      bail!(RedisError::Generic(
+        "Unbalanced 'xreadgroup' list of streams: for each stream key an ID or '>' must be \
+         specified."
+          .to_owned()
+      ));
+    }
+    let (keys, ids) = rest.split_at(rest.len() / 2);
+    let from = ids
+      .iter()
+      .map(|id| match &id[..] {
+        b">" => Ok(ReadGroupFrom::NewEntries),
+        b"$" => bail!(RedisError::Generic(
+          "The $ ID is meaningless in the context of XREADGROUP: you want to read the history of \
+           this consumer by specifying a proper ID, or use the > ID to get new messages. The $ ID \
+           would just return an empty result set."
+            .to_owned()
+        )),
+        id => Ok(ReadGroupFrom::History(parse_stream_id(id, 0)?)),
+      })
+      .collect::<Result<_>>()?;
+
+    Ok(Xreadgroup {
+      group,
+      consumer,
+      keys: keys.to_vec(),
+      from,
+      count,
+      blocking,
+      timeout,
+      noack,
+    })
+  }
+}
@@ -0,0 +1,111 @@
+use bytes::Bytes;
+
+use crate::database::{Stream, TrimStrategy, STREAM_NODE_MAX_ENTRIES};
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_integer, parse_stream_id, Execute, ExecutionContext, Parse};
+
+/// How `XTRIM`, or `XADD` after adding its entry, trims a stream.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct TrimOptions {
+  pub strategy: TrimStrategy,
+  /// `~`, which lets whole nodes of entries stay
+  pub approx: bool,
+  /// at most how many entries are removed, `None` meaning no limit
+  pub limit: Option<usize>,
+}
+
+impl TrimOptions {
+  /// Parses `MAXLEN | MINID [= | ~] threshold [LIMIT count]` at the start of `args`.
+  /// Returns the options and how many arguments they took.
+  pub fn parse(args: &[Bytes]) -> Result<(Self, usize)> {
+    let max_len = match args.first() {
+      Some(kind) if kind.eq_ignore_ascii_case(b"MAXLEN") => true,
+      Some(kind) if kind.eq_ignore_ascii_case(b"MINID") => false,
+      _ => bail!(RedisError::Syntax),
+    };
+    let (approx, threshold_position) = match args.get(1).map(|operator| &operator[..]) {
+      Some(b"~") => (true, 2),
+      Some(b"=") => (false, 2),
+      _ => (false, 1),
+    };
+    let Some(threshold) = args.get(threshold_position) else {
+      bail!(RedisError::Syntax);
+    };
+    let mut position = threshold_position + 1;
+    let strategy = if max_len {
+      match usize::try_from(parse_integer(threshold)?) {
+        Ok(max_len) => TrimStrategy::MaxLen(max_len),
+        Err(_) => bail!(RedisError::Generic(
+          "The MAXLEN argument must be >= 0.".to_owned()
+        )),
+      }
+    } else {
+      TrimStrategy::MinId(parse_stream_id(threshold, 0)?)
+    };
+
+    // like Redis, an approximate trim removes at most a hundred nodes unless told otherwise
+    let mut limit = approx.then_some(100 * STREAM_NODE_MAX_ENTRIES);
+    if args
+      .get(position)
+      .is_some_and(|option| option.eq_ignore_ascii_case(b"LIMIT"))
+    {
+      let Some(count) = args.get(position + 1) else {
+        bail!(RedisError::Syntax);
+      };
+      let Ok(count) = usize::try_from(parse_integer(count)?) else {
+        bail!(RedisError::Generic(
+          "The LIMIT argument must be >= 0.".to_owned()
+        ));
+      };
+      if !approx {
+        bail!(RedisError::Generic(
+          "syntax error, LIMIT cannot be used without the special ~ option".to_owned()
+        ));
+      }
+      // LIMIT 0 turns the limit off
+      limit = (count > 0).then_some(count);
+      position += 2;
+    }
+
+    let options = TrimOptions {
+      strategy,
+      approx,
+      limit,
+    };
+    Ok((options, position))
+  }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Xtrim {
+  pub key: Bytes,
+  pub trim: TrimOptions,
+}
+
+impl Execute for Xtrim {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Xtrim { key, trim } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(stream) = db.get_value_mut::<Stream>(key)? else {
+      return Ok(Reply::Integer(0));
+    };
+    let removed = stream.trim(trim.strategy, trim.approx, trim.limit);
+    Ok(Reply::Integer(removed as i64))
+  }
+}
+
+impl Parse for Xtrim {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let (trim, used) = TrimOptions::parse(&args[1..])?;
+    if used + 1 < args.len() {
+      bail!(RedisError::Syntax);
+    }
+    Ok(Xtrim {
+      key: args[0].clone(),
+      trim,
+    })
+  }
+}
@@ -0,0 +1,141 @@
+use bytes::Bytes;
+
+use crate::database::SortedSet;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_float, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Zadd {
+  pub key: Bytes,
+  pub nx: bool,
+  pub xx: bool,
+  pub gt: bool,
+  pub lt: bool,
+  /// count changed scores in the reply, not only added members
+  pub ch: bool,
+  /// add the score to the current one and reply with the result, like `ZINCRBY`
+  pub incr: bool,
+  pub entries: Vec<(f64, Bytes)>,
+}
+
+impl Execute for Zadd {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Zadd {
+      key,
+      nx,
+      xx,
+      gt,
+      lt,
+      ch,
+      incr,
+      entries,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    if *xx && db.get_value::<SortedSet>(key)?.is_none() {
+      return Ok(if *incr {
+        Reply::NullBulkString
+      } else {
+        Reply::Integer(0)
+      });
+    }
+    let zset = db.get_value_or_default::<SortedSet>(key)?;
+
+    let mut added = 0;
+    let mut changed = 0;
+    let mut incremented = None;
+    let mut outcome = Ok(());
+    for (score, member) in entries {
+      let current = zset.score(member);
+      if (*nx && current.is_some()) || (*xx && current.is_none()) {
+        continue;
+      }
+      let new = match (*incr, current) {
+        (true, Some(current)) => current + score,
+        _ => *score,
+      };
+      if new.is_nan() {
+        outcome = Err(RedisError::Generic(
+          "resulting score is not a number (NaN)".to_owned(),
+        ));
+        break;
+      }
+
+      match current {
+        Some(current) if (*gt && new <= current) || (*lt && new >= current) => continue,
+        Some(current) if new == current => {}
+        Some(_) => {
+          zset.insert(member.clone(), new);
+          changed += 1;
+        }
+        None => {
+          zset.insert(member.clone(), new);
+          added += 1;
+        }
+      }
+      incremented = Some(new);
+    }
+    // NX or XX may have skipped every member of a set that was just created
+    db.remove_if_empty(key);
+    if added > 0 {
+      db.signal_key_as_ready(key);
+    }
+    outcome?;
+
+    Ok(match (*incr, incremented) {
+      (true, Some(score)) => Reply::Double(score),
+      (true, None) => Reply::NullBulkString,
+      (false, _) if *ch => Reply::Integer(added + changed),
+      (false, _) => Reply::Integer(added),
+    })
+  }
+}
+
+impl Parse for Zadd {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let mut zadd = Zadd {
+      key: args[0].clone(),
+      ..Zadd::default()
+    };
+    let mut rest = &args[1..];
+    while let Some(option) = rest.first() {
+      match String::from_utf8_lossy(option).to_uppercase().as_str() {
+        "NX" => zadd.nx = true,
+        "XX" => zadd.xx = true,
+        "GT" => zadd.gt = true,
+        "LT" => zadd.lt = true,
+        "CH" => zadd.ch = true,
+        "INCR" => zadd.incr = true,
+        _ => break,
+      }
+      rest = &rest[1..];
+    }
+
+    if rest.is_empty() || !rest.len().is_multiple_of(2) {
+      bail!(RedisError::Syntax);
+    }
+    if zadd.nx && zadd.xx {
+      bail!(RedisError::Generic(
+        "XX and NX options at the same time are not compatible".to_owned()
+      ));
+    }
+    if (zadd.gt && zadd.lt) || (zadd.nx && (zadd.gt || zadd.lt)) {
+      bail!(RedisError::Generic(
+        "GT, LT, and/or NX options at the same time are not compatible".to_owned()
+      ));
+    }
+    if zadd.incr && rest.len() > 2 {
+      bail!(RedisError::Generic(
+        "INCR option supports a single increment-element pair".to_owned()
+      ));
+    }
+
+    zadd.entries = rest
+      .chunks_exact(2)
+      .map(|pair| Ok((parse_float(&pair[0])?, pair[1].clone())))
+      .collect::<Result<_>>()?;
+    Ok(zadd)
+  }
+}
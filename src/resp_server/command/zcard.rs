@@ -0,0 +1,30 @@
+use bytes::Bytes;
+
+use crate::database::SortedSet;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Zcard {
+  pub key: Bytes,
+}
+
+impl Execute for Zcard {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Zcard { key } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let len = db.get_value::<SortedSet>(key)?.map_or(0, SortedSet::len);
+    Ok(Reply::Integer(len as i64))
+  }
+}
+
+impl Parse for Zcard {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Zcard {
+      key: args[0].clone(),
+    })
+  }
+}
@@ -0,0 +1,36 @@
+use bytes::Bytes;
+
+use crate::database::{ScoreBound, SortedSet};
+use crate::resp_server::{Reply, Result};
+
+use super::{parse_score_bound, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Zcount {
+  pub key: Bytes,
+  pub min: ScoreBound,
+  pub max: ScoreBound,
+}
+
+impl Execute for Zcount {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Zcount { key, min, max } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let count = db
+      .get_value::<SortedSet>(key)?
+      .map_or(0, |zset| zset.range_by_score(*min, *max, false).count());
+    Ok(Reply::Integer(count as i64))
+  }
+}
+
+impl Parse for Zcount {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Zcount {
+      key: args[0].clone(),
+      min: parse_score_bound(&args[1])?,
+      max: parse_score_bound(&args[2])?,
+    })
+  }
+}
@@ -0,0 +1,47 @@
+use bytes::Bytes;
+
+use crate::database::SortedSet;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_float, Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Zincrby {
+  pub key: Bytes,
+  pub increment: f64,
+  pub member: Bytes,
+}
+
+impl Execute for Zincrby {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Zincrby {
+      key,
+      increment,
+      member,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let zset = db.get_value_or_default::<SortedSet>(key)?;
+    let score = zset.score(member).unwrap_or(0.0) + increment;
+    if score.is_nan() {
+      // only an existing member can make the sum NaN, so the set is not left empty
+      bail!(RedisError::Generic(
+        "resulting score is not a number (NaN)".to_owned()
+      ));
+    }
+    zset.insert(member.clone(), score);
+    db.signal_key_as_ready(key);
+    Ok(Reply::Double(score))
+  }
+}
+
+impl Parse for Zincrby {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Zincrby {
+      key: args[0].clone(),
+      increment: parse_float(&args[1])?,
+      member: args[2].clone(),
+    })
+  }
+}
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use crate::database::Database;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{pop_members, serve_or_block, Execute, ExecutionContext, Parse, ScoredMembers};
+
+/// `ZMPOP`, or `BZMPOP` when `blocking` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Zmpop {
+  pub keys: Vec<Bytes>,
+  pub max: bool,
+  pub count: usize,
+  pub blocking: bool,
+  /// how long `BZMPOP` waits, `None` meaning forever
+  pub timeout: Option<Duration>,
+}
+
+impl Execute for Zmpop {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Zmpop {
+      keys,
+      max,
+      count,
+      blocking,
+      timeout,
+    } = self;
+
+    let (keys_to_pop, max, count) = (keys.clone(), *max, *count);
+    let serve = move |db: &mut Database| {
+      let popped = pop_from_first_zset(db, &keys_to_pop, max, count)?;
+      Ok(popped.map(|(key, members)| {
+        // unlike ZPOPMIN, members and scores come in pairs whatever the protocol
+        let members = members
+          .into_iter()
+          .map(|(member, score)| {
+            Reply::Array(vec![Reply::BulkString(member), Reply::Double(score)])
+          })
+          .collect();
+        Reply::Array(vec![Reply::BulkString(key), Reply::Array(members)])
+      }))
+    };
+
+    if *blocking {
+      return serve_or_block(ctx, keys, *timeout, Reply::NullArray, serve);
+    }
+    let ExecutionContext { db, .. } = ctx;
+    let reply = serve(&mut db.lock().unwrap())?;
+    Ok(reply.unwrap_or(Reply::NullArray))
+  }
+}
+
+impl Parse for Zmpop {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let numkeys = String::from_utf8_lossy(&args[0]).parse::<usize>();
+    let Ok(numkeys @ 1..) = numkeys else {
+      bail!(RedisError::Generic(
+        "numkeys should be greater than 0".to_owned()
+      ));
+    };
+    let Some(keys) = args.get(1..=numkeys) else {
+      bail!(RedisError::Syntax);
+    };
+    let max = match args.get(numkeys + 1) {
+      Some(end) if end.eq_ignore_ascii_case(b"MIN") => false,
+      Some(end) if end.eq_ignore_ascii_case(b"MAX") => true,
+      _ => bail!(RedisError::Syntax),
+    };
+
+    let count = match &args[numkeys + 2..] {
+      [] => 1,
+      [option, count] if option.eq_ignore_ascii_case(b"COUNT") => {
+        match String::from_utf8_lossy(count).parse::<usize>() {
+          Ok(count @ 1..) => count,
+          _ => bail!(RedisError::Generic(
+            "count should be greater than 0".to_owned()
+          )),
+        }
+      }
+      _ => bail!(RedisError::Syntax),
+    };
+
+    Ok(Zmpop {
+      keys: keys.to_vec(),
+      max,
+      count,
+      blocking: false,
+      timeout: None,
+    })
+  }
+}
+
+/// Pops up to `count` members from the first of `keys` that holds a sorted set.
+pub fn pop_from_first_zset(
+  db: &mut Database,
+  keys: &[Bytes],
+  max: bool,
+  count: usize,
+) -> Result<Option<(Bytes, ScoredMembers)>> {
+  for key in keys {
+    if let Some(members) = pop_members(db, key, max, count)? {
+      return Ok(Some((key.clone(), members)));
+    }
+  }
+  Ok(None)
+}
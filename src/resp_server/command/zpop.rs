@@ -0,0 +1,68 @@
+use bytes::Bytes;
+
+use crate::database::{Database, SortedSet};
+use crate::resp_server::{bail, Protocol, RedisError, Reply, Result};
+
+use super::{parse_integer, scored_members_reply, Execute, ExecutionContext, Parse};
+
+/// Members with their scores, in the order they were popped.
+pub type ScoredMembers = Vec<(Bytes, f64)>;
+
+/// `ZPOPMIN`, or `ZPOPMAX` when `max` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Zpop {
+  pub key: Bytes,
+  pub max: bool,
+  pub count: Option<usize>,
+}
+
+impl Execute for Zpop {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Zpop { key, max, count } = self;
+    let ExecutionContext { db, session } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let popped = pop_members(&mut db, key, *max, count.unwrap_or(1))?.unwrap_or_default();
+    // without a count, the member and its score come as a flat array even in RESP3
+    let protocol = match count {
+      Some(_) => session.borrow().protocol,
+      None => Protocol::Resp2,
+    };
+    Ok(scored_members_reply(popped, true, protocol))
+  }
+}
+
+impl Parse for Zpop {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let count = match &args[1..] {
+      [] => None,
+      [count] => match usize::try_from(parse_integer(count)?) {
+        Ok(count) => Some(count),
+        Err(_) => bail!(RedisError::NotPositive),
+      },
+      _ => bail!(RedisError::Syntax),
+    };
+    Ok(Zpop {
+      key: args[0].clone(),
+      max: false,
+      count,
+    })
+  }
+}
+
+/// Pops up to `count` members with the lowest scores from the sorted set at `key`, or with the
+/// highest when `max`, deleting the set once it is empty. Returns `None` when the set doesn't
+/// exist.
+pub fn pop_members(
+  db: &mut Database,
+  key: &[u8],
+  max: bool,
+  count: usize,
+) -> Result<Option<ScoredMembers>> {
+  let Some(zset) = db.get_value_mut::<SortedSet>(key)? else {
+    return Ok(None);
+  };
+  let popped = (0..count).map_while(|_| zset.pop(max)).collect();
+  db.remove_if_empty(key);
+  Ok(Some(popped))
+}
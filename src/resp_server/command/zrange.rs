@@ -0,0 +1,223 @@
+use bytes::Bytes;
+
+use crate::database::{Data, LexBound, ScoreBound, SortedSet, Value};
+use crate::resp_server::{bail, Protocol, RedisError, Reply, Result};
+
+use super::{normalize_range, parse_float, parse_integer, Execute, ExecutionContext, Parse};
+
+/// Which members a `ZRANGE` selects.
+#[derive(PartialEq, Debug, Clone)]
+pub enum RangeBy {
+  /// inclusive positions, which count from the end when negative
+  Index(i64, i64),
+  Score(ScoreBound, ScoreBound),
+  Lex(LexBound, LexBound),
+}
+
+/// `ZRANGE` with the unified syntax of Redis 6.2, which the older `ZRANGEBYSCORE`, `ZRANGEBYLEX`
+/// and `ZREV*` commands are special cases of. `ZRANGESTORE` when `destination` is set.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Zrange {
+  pub key: Bytes,
+  pub range: RangeBy,
+  pub rev: bool,
+  /// offset and count, a negative count meaning all the members after the offset
+  pub limit: Option<(i64, i64)>,
+  pub with_scores: bool,
+  pub destination: Option<Bytes>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum RangeKind {
+  Index,
+  Score,
+  Lex,
+}
+
+impl Execute for Zrange {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Zrange {
+      key,
+      range,
+      rev,
+      limit,
+      with_scores,
+      destination,
+    } = self;
+    let ExecutionContext { db, session } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let entries = match db.get_value::<SortedSet>(key)? {
+      Some(zset) => select_range(zset, range, *rev, *limit),
+      None => vec![],
+    };
+
+    let Some(destination) = destination else {
+      let protocol = session.borrow().protocol;
+      return Ok(scored_members_reply(entries, *with_scores, protocol));
+    };
+    let len = entries.len();
+    if entries.is_empty() {
+      db.remove(destination);
+    } else {
+      let mut zset = SortedSet::default();
+      for (member, score) in entries {
+        zset.insert(member, score);
+      }
+      let data = Data {
+        value: Value::ZSet(zset),
+        expire_time: None,
+      };
+      db.set(destination, &data);
+    }
+    Ok(Reply::Integer(len as i64))
+  }
+}
+
+impl Parse for Zrange {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Zrange::parse_from(args, None, false)
+  }
+}
+
+impl Zrange {
+  /// Parses `key start stop` and the options after them. The unified syntax of `ZRANGE` is used
+  /// when `kind` is `None`; the older commands fix the kind of range and its direction instead.
+  pub fn parse_from(args: &[Bytes], kind: Option<RangeKind>, rev: bool) -> Result<Self> {
+    let unified = kind.is_none();
+    let mut kind = kind.unwrap_or(RangeKind::Index);
+    let mut rev = rev;
+    let mut limit = None;
+    let mut with_scores = false;
+
+    let mut options = args[3..].iter();
+    while let Some(option) = options.next() {
+      match String::from_utf8_lossy(option).to_uppercase().as_str() {
+        "BYSCORE" if unified => kind = RangeKind::Score,
+        "BYLEX" if unified => kind = RangeKind::Lex,
+        "REV" if unified => rev = true,
+        "WITHSCORES" => with_scores = true,
+        "LIMIT" => {
+          let (Some(offset), Some(count)) = (options.next(), options.next()) else {
+            bail!(RedisError::Syntax);
+          };
+          limit = Some((parse_integer(offset)?, parse_integer(count)?));
+        }
+        _ => bail!(RedisError::Syntax),
+      }
+    }
+
+    if limit.is_some() && kind == RangeKind::Index {
+      bail!(RedisError::Generic(
+        "syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX"
+          .to_owned()
+      ));
+    }
+    if with_scores && kind == RangeKind::Lex {
+      bail!(RedisError::Generic(
+        "syntax error, WITHSCORES not supported in combination with BYLEX".to_owned()
+      ));
+    }
+
+    // reversed score and lex ranges start from the maximum
+    let (start, stop) = (&args[1], &args[2]);
+    let (min, max) = if rev { (stop, start) } else { (start, stop) };
+    let range = match kind {
+      RangeKind::Index => RangeBy::Index(parse_integer(start)?, parse_integer(stop)?),
+      RangeKind::Score => RangeBy::Score(parse_score_bound(min)?, parse_score_bound(max)?),
+      RangeKind::Lex => RangeBy::Lex(parse_lex_bound(min)?, parse_lex_bound(max)?),
+    };
+    Ok(Zrange {
+      key: args[0].clone(),
+      range,
+      rev,
+      limit,
+      with_scores,
+      destination: None,
+    })
+  }
+
+  /// `ZRANGESTORE destination source min max [BYSCORE | BYLEX] [REV] [LIMIT offset count]`
+  pub fn parse_store(args: &[Bytes]) -> Result<Self> {
+    let zrange = Zrange::parse_from(&args[1..], None, false)?;
+    if zrange.with_scores {
+      bail!(RedisError::Syntax);
+    }
+    Ok(Zrange {
+      destination: Some(args[0].clone()),
+      ..zrange
+    })
+  }
+}
+
+fn select_range(
+  zset: &SortedSet,
+  range: &RangeBy,
+  rev: bool,
+  limit: Option<(i64, i64)>,
+) -> Vec<(Bytes, f64)> {
+  let entries: Box<dyn Iterator<Item = (&Bytes, f64)>> = match range {
+    RangeBy::Index(start, stop) => {
+      let Some((start, stop)) = normalize_range(*start, *stop, zset.len()) else {
+        return vec![];
+      };
+      zset.range_by_rank(start, stop, rev)
+    }
+    RangeBy::Score(min, max) => zset.range_by_score(*min, *max, rev),
+    RangeBy::Lex(min, max) => zset.range_by_lex(min, max, rev),
+  };
+  let entries = match limit {
+    None => entries,
+    Some((offset, _)) if offset < 0 => return vec![],
+    Some((offset, count)) => {
+      let count = usize::try_from(count).unwrap_or(usize::MAX);
+      Box::new(entries.skip(offset as usize).take(count))
+    }
+  };
+  entries
+    .map(|(member, score)| (member.clone(), score))
+    .collect()
+}
+
+/// Parses a score bound like `1.5`, `(1.5` or `-inf`.
+pub fn parse_score_bound(arg: &[u8]) -> Result<ScoreBound> {
+  let (score, exclusive) = match arg.strip_prefix(b"(") {
+    Some(score) => (score, true),
+    None => (arg, false),
+  };
+  let Ok(score) = parse_float(score) else {
+    bail!(RedisError::Generic("min or max is not a float".to_owned()));
+  };
+  Ok(ScoreBound { score, exclusive })
+}
+
+/// Parses a lex bound: `-`, `+`, `[member` or `(member`.
+pub fn parse_lex_bound(arg: &Bytes) -> Result<LexBound> {
+  Ok(match arg.first() {
+    Some(b'-') if arg.len() == 1 => LexBound::Min,
+    Some(b'+') if arg.len() == 1 => LexBound::Max,
+    Some(b'[') => LexBound::Inclusive(arg.slice(1..)),
+    Some(b'(') => LexBound::Exclusive(arg.slice(1..)),
+    _ => bail!(RedisError::Generic(
+      "min or max not valid string range item".to_owned()
+    )),
+  })
+}
+
+/// Replies with members, each followed by its score when `with_scores` is set: RESP3 clients get
+/// the member and score pairs as nested arrays, RESP2 clients get a flat array.
+pub fn scored_members_reply(
+  entries: Vec<(Bytes, f64)>,
+  with_scores: bool,
+  protocol: Protocol,
+) -> Reply {
+  let replies = entries.into_iter().map(|(member, score)| {
+    let member = Reply::BulkString(member);
+    match (with_scores, protocol) {
+      (false, _) => vec![member],
+      (true, Protocol::Resp2) => vec![member, Reply::Double(score)],
+      (true, Protocol::Resp3) => vec![Reply::Array(vec![member, Reply::Double(score)])],
+    }
+  });
+  Reply::Array(replies.flatten().collect())
+}
@@ -0,0 +1,58 @@
+use bytes::Bytes;
+
+use crate::database::SortedSet;
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+/// `ZRANK`, or `ZREVRANK` when `rev` is set.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Zrank {
+  pub key: Bytes,
+  pub member: Bytes,
+  pub rev: bool,
+  pub with_score: bool,
+}
+
+impl Execute for Zrank {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Zrank {
+      key,
+      member,
+      rev,
+      with_score,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let ranked = db.get_value::<SortedSet>(key)?.and_then(|zset| {
+      let rank = zset.rank(member, *rev)?;
+      Some((rank, zset.score(member)?))
+    });
+
+    Ok(match (ranked, with_score) {
+      (None, false) => Reply::NullBulkString,
+      (None, true) => Reply::NullArray,
+      (Some((rank, _)), false) => Reply::Integer(rank as i64),
+      (Some((rank, score)), true) => {
+        Reply::Array(vec![Reply::Integer(rank as i64), Reply::Double(score)])
+      }
+    })
+  }
+}
+
+impl Parse for Zrank {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    let with_score = match &args[2..] {
+      [] => false,
+      [option] if option.eq_ignore_ascii_case(b"WITHSCORE") => true,
+      _ => bail!(RedisError::Syntax),
+    };
+    Ok(Zrank {
+      key: args[0].clone(),
+      member: args[1].clone(),
+      rev: false,
+      with_score,
+    })
+  }
+}
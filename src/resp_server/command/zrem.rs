@@ -0,0 +1,39 @@
+use bytes::Bytes;
+
+use crate::database::SortedSet;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Zrem {
+  pub key: Bytes,
+  pub members: Vec<Bytes>,
+}
+
+impl Execute for Zrem {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Zrem { key, members } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let Some(zset) = db.get_value_mut::<SortedSet>(key)? else {
+      return Ok(Reply::Integer(0));
+    };
+    let removed = members
+      .iter()
+      .filter(|member| zset.remove(member).is_some())
+      .count();
+    db.remove_if_empty(key);
+    Ok(Reply::Integer(removed as i64))
+  }
+}
+
+impl Parse for Zrem {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Zrem {
+      key: args[0].clone(),
+      members: args[1..].to_vec(),
+    })
+  }
+}
@@ -0,0 +1,34 @@
+use bytes::Bytes;
+
+use crate::database::SortedSet;
+use crate::resp_server::{Reply, Result};
+
+use super::{Execute, ExecutionContext, Parse};
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Zscore {
+  pub key: Bytes,
+  pub member: Bytes,
+}
+
+impl Execute for Zscore {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let Zscore { key, member } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let score = db
+      .get_value::<SortedSet>(key)?
+      .and_then(|zset| zset.score(member));
+    Ok(score.map_or(Reply::NullBulkString, Reply::Double))
+  }
+}
+
+impl Parse for Zscore {
+  fn parse(args: &[Bytes]) -> Result<Self> {
+    Ok(Zscore {
+      key: args[0].clone(),
+      member: args[1].clone(),
+    })
+  }
+}
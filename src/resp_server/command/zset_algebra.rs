@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+
+use crate::database::{Data, Database, SortedSet, Value};
+use crate::resp_server::{bail, RedisError, Reply, Result};
+
+use super::{parse_float, parse_integer, Execute, ExecutionContext, SetOperation};
+
+/// How the weighted scores of a member found in several sets combine.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Aggregate {
+  Sum,
+  Min,
+  Max,
+}
+
+impl Aggregate {
+  fn apply(self, a: f64, b: f64) -> f64 {
+    match self {
+      // inf + -inf is NaN, which Redis turns into 0
+      Aggregate::Sum => nan_to_zero(a + b),
+      Aggregate::Min => a.min(b),
+      Aggregate::Max => a.max(b),
+    }
+  }
+}
+
+/// `ZUNIONSTORE` or `ZINTERSTORE`.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ZsetAlgebra {
+  pub destination: Bytes,
+  pub keys: Vec<Bytes>,
+  /// one per key, all 1 unless `WEIGHTS` is given
+  pub weights: Vec<f64>,
+  pub aggregate: Aggregate,
+  pub operation: SetOperation,
+}
+
+impl Execute for ZsetAlgebra {
+  fn execute(&self, ctx: &ExecutionContext) -> Result<Reply> {
+    let ZsetAlgebra {
+      destination,
+      keys,
+      weights,
+      aggregate,
+      operation,
+    } = self;
+    let ExecutionContext { db, .. } = ctx;
+
+    let mut db = db.lock().unwrap();
+    let mut inputs = Vec::with_capacity(keys.len());
+    for (key, weight) in keys.iter().zip(weights) {
+      let weighted = scored_members(&mut db, key)?.map(|entries| {
+        entries
+          .into_iter()
+          .map(|(member, score)| (member, nan_to_zero(score * weight)))
+          .collect::<Vec<_>>()
+      });
+      inputs.push(weighted);
+    }
+
+    let mut scores = HashMap::new();
+    match operation {
+      SetOperation::Union => {
+        for (member, score) in inputs.into_iter().flatten().flatten() {
+          scores
+            .entry(member)
+            .and_modify(|total| *total = aggregate.apply(*total, score))
+            .or_insert(score);
+        }
+      }
+      _ => {
+        if let Some(inputs) = inputs.into_iter().collect::<Option<Vec<_>>>() {
+          let (first, others) = inputs.split_first().expect("numkeys is checked");
+          scores = first.iter().cloned().collect();
+          for input in others {
+            scores = input
+              .iter()
+              .filter_map(|(member, score)| {
+                let total = scores.get(member)?;
+                Some((member.clone(), aggregate.apply(*total, *score)))
+              })
+              .collect();
+          }
+        }
+      }
+    }
+
+    let len = scores.len();
+    if scores.is_empty() {
+      db.remove(destination);
+    } else {
+      let mut zset = SortedSet::default();
+      for (member, score) in scores {
+        zset.insert(member, score);
+      }
+      let data = Data {
+        value: Value::ZSet(zset),
+        expire_time: None,
+      };
+      db.set(destination, &data);
+    }
+    Ok(Reply::Integer(len as i64))
+  }
+}
+
+impl ZsetAlgebra {
+  /// `ZUNIONSTORE` or `ZINTERSTORE destination numkeys key [key ...] [WEIGHTS weight [weight ...]]
+  /// [AGGREGATE SUM | MIN | MAX]`
+  pub fn parse_from(args: &[Bytes], operation: SetOperation) -> Result<Self> {
+    let numkeys = parse_integer(&args[1])?;
+    if numkeys < 1 {
+      let name = match operation {
+        SetOperation::Union => "zunionstore",
+        _ => "zinterstore",
+      };
+      bail!(RedisError::Generic(format!(
+        "at least 1 input key is needed for '{}' command",
+        name
+      )));
+    }
+    let Some(keys) = args.get(2..2 + numkeys as usize) else {
+      bail!(RedisError::Syntax);
+    };
+
+    let mut weights = vec![1.0; keys.len()];
+    let mut aggregate = Aggregate::Sum;
+    let mut options = args[2 + keys.len()..].iter();
+    while let Some(option) = options.next() {
+      match String::from_utf8_lossy(option).to_uppercase().as_str() {
+        "WEIGHTS" => {
+          for weight in &mut weights {
+            let Some(arg) = options.next() else {
+              bail!(RedisError::Syntax);
+            };
+            let Ok(parsed) = parse_float(arg) else {
+              bail!(RedisError::Generic(
+                "weight value is not a float".to_owned()
+              ));
+            };
+            *weight = parsed;
+          }
+        }
+        "AGGREGATE" => {
+          let Some(arg) = options.next() else {
+            bail!(RedisError::Syntax);
+          };
+          aggregate = match String::from_utf8_lossy(arg).to_uppercase().as_str() {
+            "SUM" => Aggregate::Sum,
+            "MIN" => Aggregate::Min,
+            "MAX" => Aggregate::Max,
+            _ => bail!(RedisError::Syntax),
+          };
+        }
+        _ => bail!(RedisError::Syntax),
+      }
+    }
+
+    Ok(ZsetAlgebra {
+      destination: args[0].clone(),
+      keys: keys.to_vec(),
+      weights,
+      aggregate,
+      operation,
+    })
+  }
+}
+
+/// The members of the sorted set at `key` with their scores, where the members of a plain set
+/// all score 1. Returns `None` when the key doesn't exist.
+fn scored_members(db: &mut Database, key: &[u8]) -> Result<Option<Vec<(Bytes, f64)>>> {
+  let Some(data) = db.get(key) else {
+    return Ok(None);
+  };
+  let entries = match &data.value {
+    Value::ZSet(zset) => zset
+      .iter()
+      .map(|(member, score)| (member.clone(), score))
+      .collect(),
+    Value::Set(set) => set
+      .members()
+      .into_iter()
+      .map(|member| (member, 1.0))
+      .collect(),
+    _ => bail!(RedisError::WrongType),
+  };
+  Ok(Some(entries))
+}
+
+fn nan_to_zero(score: f64) -> f64 {
+  if score.is_nan() {
+    0.0
+  } else {
+    score
+  }
+}
@@ -0,0 +1,2233 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use bytes::Bytes;
+
+use super::command::*;
+use super::{bail, RedisError, Reply, Result};
+
+/// Finds the positions of the keys in a full argument vector, command name included.
+type GetKeys = fn(&[Bytes]) -> Vec<usize>;
+
+/// Static description of a command, mirroring what Redis exposes through `COMMAND INFO`.
+#[derive(Debug)]
+pub struct CommandSpec {
+  /// lowercase command name
+  pub name: &'static str,
+  /// number of arguments including the command name; a negative value means "at least"
+  pub arity: i64,
+  pub flags: &'static [&'static str],
+  /// position of the first key, or 0 if the command takes no key
+  pub first_key: i64,
+  /// position of the last key; a negative value counts from the end of the arguments
+  pub last_key: i64,
+  pub step: i64,
+  pub acl_categories: &'static [&'static str],
+  pub group: &'static str,
+  pub since: &'static str,
+  pub summary: &'static str,
+  /// finds the positions of the keys of a command flagged `movablekeys`, whose keys can't be
+  /// told by `first_key`, `last_key` and `step`, like Redis' `getkeys_proc`
+  pub get_keys: Option<GetKeys>,
+  pub parse: fn(&[Bytes]) -> Result<Command>,
+}
+
+pub static COMMAND_TABLE: &[CommandSpec] = &[
+  CommandSpec {
+    name: "append",
+    arity: 3,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@string", "@fast"],
+    group: "string",
+    since: "2.0.0",
+    summary: "Appends a string to the value of a key. Creates the key if it doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Append(Append::parse(args)?)),
+  },
+  CommandSpec {
+    name: "blmove",
+    arity: 6,
+    flags: &["write", "denyoom", "blocking"],
+    first_key: 1,
+    last_key: 2,
+    step: 1,
+    acl_categories: &["@write", "@list", "@slow", "@blocking"],
+    group: "list",
+    since: "6.2.0",
+    summary: "Pops an element from a list, pushes it to another list and returns it. Blocks until an element is available otherwise. Deletes the list if the last element was moved.",
+    get_keys: None,
+    parse: |args| Ok(Command::Blmove(Blmove::parse(args)?)),
+  },
+  CommandSpec {
+    name: "blmpop",
+    arity: -5,
+    flags: &["write", "blocking", "movablekeys"],
+    first_key: 0,
+    last_key: 0,
+    step: 0,
+    acl_categories: &["@write", "@list", "@slow", "@blocking"],
+    group: "list",
+    since: "7.0.0",
+    summary: "Pops the first element from one of multiple lists. Blocks until an element is available otherwise. Deletes the list if the last element was popped.",
+    get_keys: Some(|argv| numkeys_keys(argv, 2)),
+    parse: |args| {
+      let timeout = parse_timeout(&args[0])?;
+      let lmpop = Lmpop::parse(&args[1..])?;
+      Ok(Command::Lmpop(Lmpop { blocking: true, timeout, ..lmpop }))
+    },
+  },
+  CommandSpec {
+    name: "blpop",
+    arity: -3,
+    flags: &["write", "blocking"],
+    first_key: 1,
+    last_key: -2,
+    step: 1,
+    acl_categories: &["@write", "@list", "@slow", "@blocking"],
+    group: "list",
+    since: "2.0.0",
+    summary: "Removes and returns the first element in a list. Blocks until an element is available otherwise. Deletes the list if the last element was popped.",
+    get_keys: None,
+    parse: |args| Ok(Command::Bpop(Bpop::parse(args)?)),
+  },
+  CommandSpec {
+    name: "brpop",
+    arity: -3,
+    flags: &["write", "blocking"],
+    first_key: 1,
+    last_key: -2,
+    step: 1,
+    acl_categories: &["@write", "@list", "@slow", "@blocking"],
+    group: "list",
+    since: "2.0.0",
+    summary: "Removes and returns the last element in a list. Blocks until an element is available otherwise. Deletes the list if the last element was popped.",
+    get_keys: None,
+    parse: |args| Ok(Command::Bpop(Bpop { end: ListEnd::Right, ..Bpop::parse(args)? })),
+  },
+  CommandSpec {
+    name: "brpoplpush",
+    arity: 4,
+    flags: &["write", "denyoom", "blocking"],
+    first_key: 1,
+    last_key: 2,
+    step: 1,
+    acl_categories: &["@write", "@list", "@slow", "@blocking"],
+    group: "list",
+    since: "2.2.0",
+    summary: "Pops an element from a list, pushes it to another list and returns it. Block until an element is available otherwise. Deletes the list if the last element was popped.",
+    get_keys: None,
+    parse: |args| Ok(Command::Blmove(Blmove::parse_brpoplpush(args)?)),
+  },
+  CommandSpec {
+    name: "bzmpop",
+    arity: -5,
+    flags: &["write", "blocking", "movablekeys"],
+    first_key: 0,
+    last_key: 0,
+    step: 0,
+    acl_categories: &["@write", "@sortedset", "@slow", "@blocking"],
+    group: "sorted-set",
+    since: "7.0.0",
+    summary: "Removes and returns a member by score from one or more sorted sets. Blocks until a member is available otherwise. Deletes the sorted set if the last element was popped.",
+    get_keys: Some(|argv| numkeys_keys(argv, 2)),
+    parse: |args| {
+      let timeout = parse_timeout(&args[0])?;
+      let zmpop = Zmpop::parse(&args[1..])?;
+      Ok(Command::Zmpop(Zmpop { blocking: true, timeout, ..zmpop }))
+    },
+  },
+  CommandSpec {
+    name: "bzpopmax",
+    arity: -3,
+    flags: &["write", "fast", "blocking"],
+    first_key: 1,
+    last_key: -2,
+    step: 1,
+    acl_categories: &["@write", "@sortedset", "@fast", "@blocking"],
+    group: "sorted-set",
+    since: "5.0.0",
+    summary: "Removes and returns the member with the highest score from one or more sorted sets. Blocks until a member is available otherwise. Deletes the sorted set if the last element was popped.",
+    get_keys: None,
+    parse: |args| Ok(Command::Bzpop(Bzpop { max: true, ..Bzpop::parse(args)? })),
+  },
+  CommandSpec {
+    name: "bzpopmin",
+    arity: -3,
+    flags: &["write", "fast", "blocking"],
+    first_key: 1,
+    last_key: -2,
+    step: 1,
+    acl_categories: &["@write", "@sortedset", "@fast", "@blocking"],
+    group: "sorted-set",
+    since: "5.0.0",
+    summary: "Removes and returns the member with the lowest score from one or more sorted sets. Blocks until a member is available otherwise. Deletes the sorted set if the last element was popped.",
+    get_keys: None,
+    parse: |args| Ok(Command::Bzpop(Bzpop::parse(args)?)),
+  },
+  CommandSpec {
+    name: "command",
+    arity: -1,
+    flags: &["loading", "stale"],
+    first_key: 0,
+    last_key: 0,
+    step: 0,
+    acl_categories: &["@slow", "@connection"],
+    group: "server",
+    since: "2.8.13",
+    summary: "Returns detailed information about all commands.",
+    get_keys: None,
+    parse: |args| Ok(Command::Command(CommandCmd::parse(args)?)),
+  },
+  CommandSpec {
+    name: "copy",
+    arity: -3,
+    flags: &["write", "denyoom"],
+    first_key: 1,
+    last_key: 2,
+    step: 1,
+    acl_categories: &["@keyspace", "@write", "@slow"],
+    group: "generic",
+    since: "6.2.0",
+    summary: "Copies the value of a key to a new key.",
+    get_keys: None,
+    parse: |args| Ok(Command::Copy(Copy::parse(args)?)),
+  },
+  CommandSpec {
+    name: "debug",
+    arity: -2,
+    flags: &["admin", "noscript", "loading", "stale", "protected"],
+    first_key: 0,
+    last_key: 0,
+    step: 0,
+    acl_categories: &["@admin", "@slow", "@dangerous"],
+    group: "server",
+    since: "1.0.0",
+    summary: "A container for debugging commands.",
+    get_keys: None,
+    parse: |args| Ok(Command::Debug(DebugCmd::parse(args)?)),
+  },
+  CommandSpec {
+    name: "decr",
+    arity: 2,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@string", "@fast"],
+    group: "string",
+    since: "1.0.0",
+    summary: "Decrements the integer value of a key by one. Uses 0 as initial value if the key doesn't exist.",
+    get_keys: None,
+    parse: |args| {
+      Ok(Command::Incrby(Incrby {
+        key: args[0].clone(),
+        increment: -1,
+      }))
+    },
+  },
+  CommandSpec {
+    name: "decrby",
+    arity: 3,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@string", "@fast"],
+    group: "string",
+    since: "1.0.0",
+    summary: "Decrements a number from the integer value of a key. Uses 0 as initial value if the key doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Incrby(Incrby::parse_from(args, true)?)),
+  },
+  CommandSpec {
+    name: "del",
+    arity: -2,
+    flags: &["write"],
+    first_key: 1,
+    last_key: -1,
+    step: 1,
+    acl_categories: &["@keyspace", "@write", "@slow"],
+    group: "generic",
+    since: "1.0.0",
+    summary: "Deletes one or more keys.",
+    get_keys: None,
+    parse: |args| Ok(Command::Del(Del::parse(args)?)),
+  },
+  CommandSpec {
+    name: "echo",
+    arity: 2,
+    flags: &["fast"],
+    first_key: 0,
+    last_key: 0,
+    step: 0,
+    acl_categories: &["@fast", "@connection"],
+    group: "connection",
+    since: "1.0.0",
+    summary: "Returns the given string.",
+    get_keys: None,
+    parse: |args| Ok(Command::Echo(Echo::parse(args)?)),
+  },
+  CommandSpec {
+    name: "exists",
+    arity: -2,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: -1,
+    step: 1,
+    acl_categories: &["@keyspace", "@read", "@fast"],
+    group: "generic",
+    since: "1.0.0",
+    summary: "Determines whether one or more keys exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Exists(Exists::parse(args)?)),
+  },
+  CommandSpec {
+    name: "expire",
+    arity: -3,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@keyspace", "@write", "@fast"],
+    group: "generic",
+    since: "1.0.0",
+    summary: "Sets the expiration time of a key in seconds.",
+    get_keys: None,
+    parse: |args| Ok(Command::Expire(Expire::parse(args)?)),
+  },
+  CommandSpec {
+    name: "expireat",
+    arity: -3,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@keyspace", "@write", "@fast"],
+    group: "generic",
+    since: "1.2.0",
+    summary: "Sets the expiration time of a key to a Unix timestamp.",
+    get_keys: None,
+    parse: |args| Ok(Command::Expire(Expire { unit: "EXAT".to_owned(), ..Expire::parse(args)? })),
+  },
+  CommandSpec {
+    name: "expiretime",
+    arity: 2,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@keyspace", "@read", "@fast"],
+    group: "generic",
+    since: "7.0.0",
+    summary: "Returns the expiration time of a key as a Unix timestamp.",
+    get_keys: None,
+    parse: |args| Ok(Command::ExpireTime(ExpireTimeCmd::parse(args)?)),
+  },
+  CommandSpec {
+    name: "get",
+    arity: 2,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@string", "@fast"],
+    group: "string",
+    since: "1.0.0",
+    summary: "Returns the string value of a key.",
+    get_keys: None,
+    parse: |args| Ok(Command::Get(Get::parse(args)?)),
+  },
+  CommandSpec {
+    name: "getdel",
+    arity: 2,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@string", "@fast"],
+    group: "string",
+    since: "6.2.0",
+    summary: "Returns the string value of a key after deleting the key.",
+    get_keys: None,
+    parse: |args| Ok(Command::Getdel(Getdel::parse(args)?)),
+  },
+  CommandSpec {
+    name: "getex",
+    arity: -2,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@string", "@fast"],
+    group: "string",
+    since: "6.2.0",
+    summary: "Returns the string value of a key after setting its expiration time.",
+    get_keys: None,
+    parse: |args| Ok(Command::Getex(Getex::parse(args)?)),
+  },
+  CommandSpec {
+    name: "getrange",
+    arity: 4,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@string", "@slow"],
+    group: "string",
+    since: "2.4.0",
+    summary: "Returns a substring of the string stored at a key.",
+    get_keys: None,
+    parse: |args| Ok(Command::Getrange(Getrange::parse(args)?)),
+  },
+  CommandSpec {
+    name: "getset",
+    arity: 3,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@string", "@fast"],
+    group: "string",
+    since: "1.0.0",
+    summary: "Returns the previous string value of a key after setting it to a new value.",
+    get_keys: None,
+    parse: |args| {
+      Ok(Command::Set(Set {
+        get: true,
+        ..Set::parse(args)?
+      }))
+    },
+  },
+  CommandSpec {
+    name: "hdel",
+    arity: -3,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@hash", "@fast"],
+    group: "hash",
+    since: "2.0.0",
+    summary: "Deletes one or more fields and their values from a hash. Deletes the hash if no fields remain.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hdel(Hdel::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hello",
+    arity: -1,
+    flags: &[
+      "noscript",
+      "loading",
+      "stale",
+      "fast",
+      "no_auth",
+      "allow_busy",
+    ],
+    first_key: 0,
+    last_key: 0,
+    step: 0,
+    acl_categories: &["@fast", "@connection"],
+    group: "connection",
+    since: "6.0.0",
+    summary: "Handshakes with the Redis server.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hello(Hello::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hexists",
+    arity: 3,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@hash", "@fast"],
+    group: "hash",
+    since: "2.0.0",
+    summary: "Determines whether a field exists in a hash.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hexists(Hexists::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hexpire",
+    arity: -6,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@hash", "@fast"],
+    group: "hash",
+    since: "7.4.0",
+    summary: "Set expiry for hash field using relative time to expire (seconds)",
+    get_keys: None,
+    parse: |args| Ok(Command::Hexpire(Hexpire::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hexpireat",
+    arity: -6,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@hash", "@fast"],
+    group: "hash",
+    since: "7.4.0",
+    summary: "Set expiry for hash field using an absolute Unix timestamp (seconds)",
+    get_keys: None,
+    parse: |args| Ok(Command::Hexpire(Hexpire { unit: "EXAT".to_owned(), ..Hexpire::parse(args)? })),
+  },
+  CommandSpec {
+    name: "hexpiretime",
+    arity: -5,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@hash", "@fast"],
+    group: "hash",
+    since: "7.4.0",
+    summary: "Returns the expiration time of a hash field as a Unix timestamp, in seconds.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hexpiretime(Hexpiretime::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hget",
+    arity: 3,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@hash", "@fast"],
+    group: "hash",
+    since: "2.0.0",
+    summary: "Returns the value of a field in a hash.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hget(Hget::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hgetall",
+    arity: 2,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@hash", "@slow"],
+    group: "hash",
+    since: "2.0.0",
+    summary: "Returns all fields and values in a hash.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hgetall(Hgetall::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hincrby",
+    arity: 4,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@hash", "@fast"],
+    group: "hash",
+    since: "2.0.0",
+    summary: "Increments the integer value of a field in a hash by a number. Uses 0 as initial value if the field doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hincrby(Hincrby::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hincrbyfloat",
+    arity: 4,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@hash", "@fast"],
+    group: "hash",
+    since: "2.6.0",
+    summary: "Increments the floating point value of a field by a number. Uses 0 as initial value if the field doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hincrbyfloat(Hincrbyfloat::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hkeys",
+    arity: 2,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@hash", "@slow"],
+    group: "hash",
+    since: "2.0.0",
+    summary: "Returns all fields in a hash.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hkeys(Hkeys::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hlen",
+    arity: 2,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@hash", "@fast"],
+    group: "hash",
+    since: "2.0.0",
+    summary: "Returns the number of fields in a hash.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hlen(Hlen::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hmget",
+    arity: -3,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@hash", "@fast"],
+    group: "hash",
+    since: "2.0.0",
+    summary: "Returns the values of all fields in a hash.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hmget(Hmget::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hpersist",
+    arity: -5,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@hash", "@fast"],
+    group: "hash",
+    since: "7.4.0",
+    summary: "Removes the expiration time for each specified field",
+    get_keys: None,
+    parse: |args| Ok(Command::Hpersist(Hpersist::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hpexpire",
+    arity: -6,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@hash", "@fast"],
+    group: "hash",
+    since: "7.4.0",
+    summary: "Set expiry for hash field using relative time to expire (milliseconds)",
+    get_keys: None,
+    parse: |args| Ok(Command::Hexpire(Hexpire { unit: "PX".to_owned(), ..Hexpire::parse(args)? })),
+  },
+  CommandSpec {
+    name: "hpexpireat",
+    arity: -6,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@hash", "@fast"],
+    group: "hash",
+    since: "7.4.0",
+    summary: "Set expiry for hash field using an absolute Unix timestamp (milliseconds)",
+    get_keys: None,
+    parse: |args| Ok(Command::Hexpire(Hexpire { unit: "PXAT".to_owned(), ..Hexpire::parse(args)? })),
+  },
+  CommandSpec {
+    name: "hpexpiretime",
+    arity: -5,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@hash", "@fast"],
+    group: "hash",
+    since: "7.4.0",
+    summary: "Returns the expiration time of a hash field as a Unix timestamp, in msec.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hexpiretime(Hexpiretime { in_millis: true, ..Hexpiretime::parse(args)? })),
+  },
+  CommandSpec {
+    name: "hpttl",
+    arity: -5,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@hash", "@fast"],
+    group: "hash",
+    since: "7.4.0",
+    summary: "Returns the TTL in milliseconds of a hash field.",
+    get_keys: None,
+    parse: |args| Ok(Command::Httl(Httl { in_millis: true, ..Httl::parse(args)? })),
+  },
+  CommandSpec {
+    name: "hrandfield",
+    arity: -2,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@hash", "@slow"],
+    group: "hash",
+    since: "6.2.0",
+    summary: "Returns one or more random fields from a hash.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hrandfield(Hrandfield::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hset",
+    arity: -4,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@hash", "@fast"],
+    group: "hash",
+    since: "2.0.0",
+    summary: "Creates or modifies the value of a field in a hash.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hset(Hset::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hsetnx",
+    arity: 4,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@hash", "@fast"],
+    group: "hash",
+    since: "2.0.0",
+    summary: "Sets the value of a field in a hash only when the field doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hsetnx(Hsetnx::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hstrlen",
+    arity: 3,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@hash", "@fast"],
+    group: "hash",
+    since: "3.2.0",
+    summary: "Returns the length of the value of a field.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hstrlen(Hstrlen::parse(args)?)),
+  },
+  CommandSpec {
+    name: "httl",
+    arity: -5,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@hash", "@fast"],
+    group: "hash",
+    since: "7.4.0",
+    summary: "Returns the TTL in seconds of a hash field.",
+    get_keys: None,
+    parse: |args| Ok(Command::Httl(Httl::parse(args)?)),
+  },
+  CommandSpec {
+    name: "hvals",
+    arity: 2,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@hash", "@slow"],
+    group: "hash",
+    since: "2.0.0",
+    summary: "Returns all values in a hash.",
+    get_keys: None,
+    parse: |args| Ok(Command::Hkeys(Hkeys { values: true, ..Hkeys::parse(args)? })),
+  },
+  CommandSpec {
+    name: "incr",
+    arity: 2,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@string", "@fast"],
+    group: "string",
+    since: "1.0.0",
+    summary: "Increments the integer value of a key by one. Uses 0 as initial value if the key doesn't exist.",
+    get_keys: None,
+    parse: |args| {
+      Ok(Command::Incrby(Incrby {
+        key: args[0].clone(),
+        increment: 1,
+      }))
+    },
+  },
+  CommandSpec {
+    name: "incrby",
+    arity: 3,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@string", "@fast"],
+    group: "string",
+    since: "1.0.0",
+    summary: "Increments the integer value of a key by a number. Uses 0 as initial value if the key doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Incrby(Incrby::parse(args)?)),
+  },
+  CommandSpec {
+    name: "incrbyfloat",
+    arity: 3,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@string", "@fast"],
+    group: "string",
+    since: "2.6.0",
+    summary: "Increment the floating point value of a key by a number. Uses 0 as initial value if the key doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Incrbyfloat(Incrbyfloat::parse(args)?)),
+  },
+  CommandSpec {
+    name: "info",
+    arity: -1,
+    flags: &["loading", "stale"],
+    first_key: 0,
+    last_key: 0,
+    step: 0,
+    acl_categories: &["@slow", "@dangerous"],
+    group: "server",
+    since: "1.0.0",
+    summary: "Returns information and statistics about the server.",
+    get_keys: None,
+    parse: |args| Ok(Command::Info(Info::parse(args)?)),
+  },
+  CommandSpec {
+    name: "lcs",
+    arity: -3,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 2,
+    step: 1,
+    acl_categories: &["@read", "@string", "@slow"],
+    group: "string",
+    since: "7.0.0",
+    summary: "Finds the longest common substring.",
+    get_keys: None,
+    parse: |args| Ok(Command::Lcs(Lcs::parse(args)?)),
+  },
+  CommandSpec {
+    name: "lindex",
+    arity: 3,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@list", "@slow"],
+    group: "list",
+    since: "1.0.0",
+    summary: "Returns an element from a list by its index.",
+    get_keys: None,
+    parse: |args| Ok(Command::Lindex(Lindex::parse(args)?)),
+  },
+  CommandSpec {
+    name: "linsert",
+    arity: 5,
+    flags: &["write", "denyoom"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@list", "@slow"],
+    group: "list",
+    since: "2.2.0",
+    summary: "Inserts an element before or after another element in a list.",
+    get_keys: None,
+    parse: |args| Ok(Command::Linsert(Linsert::parse(args)?)),
+  },
+  CommandSpec {
+    name: "llen",
+    arity: 2,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@list", "@fast"],
+    group: "list",
+    since: "1.0.0",
+    summary: "Returns the length of a list.",
+    get_keys: None,
+    parse: |args| Ok(Command::Llen(Llen::parse(args)?)),
+  },
+  CommandSpec {
+    name: "lmove",
+    arity: 5,
+    flags: &["write", "denyoom"],
+    first_key: 1,
+    last_key: 2,
+    step: 1,
+    acl_categories: &["@write", "@list", "@slow"],
+    group: "list",
+    since: "6.2.0",
+    summary: "Returns an element after popping it from one list and pushing it to another. Deletes the list if the last element was moved.",
+    get_keys: None,
+    parse: |args| Ok(Command::Lmove(Lmove::parse(args)?)),
+  },
+  CommandSpec {
+    name: "lmpop",
+    arity: -4,
+    flags: &["write", "movablekeys"],
+    first_key: 0,
+    last_key: 0,
+    step: 0,
+    acl_categories: &["@write", "@list", "@slow"],
+    group: "list",
+    since: "7.0.0",
+    summary: "Returns multiple elements from a list after removing them. Deletes the list if the last element was popped.",
+    get_keys: Some(|argv| numkeys_keys(argv, 1)),
+    parse: |args| Ok(Command::Lmpop(Lmpop::parse(args)?)),
+  },
+  CommandSpec {
+    name: "lpop",
+    arity: -2,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@list", "@fast"],
+    group: "list",
+    since: "1.0.0",
+    summary: "Returns the first elements in a list after removing it. Deletes the list if the last element was popped.",
+    get_keys: None,
+    parse: |args| Ok(Command::Pop(Pop::parse(args)?)),
+  },
+  CommandSpec {
+    name: "lpos",
+    arity: -3,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@list", "@slow"],
+    group: "list",
+    since: "6.0.6",
+    summary: "Returns the index of matching elements in a list.",
+    get_keys: None,
+    parse: |args| Ok(Command::Lpos(Lpos::parse(args)?)),
+  },
+  CommandSpec {
+    name: "lpush",
+    arity: -3,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@list", "@fast"],
+    group: "list",
+    since: "1.0.0",
+    summary: "Prepends one or more elements to a list. Creates the key if it doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Push(Push::parse(args)?)),
+  },
+  CommandSpec {
+    name: "lpushx",
+    arity: -3,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@list", "@fast"],
+    group: "list",
+    since: "2.2.0",
+    summary: "Prepends one or more elements to a list only when the list exists.",
+    get_keys: None,
+    parse: |args| Ok(Command::Push(Push { only_if_exists: true, ..Push::parse(args)? })),
+  },
+  CommandSpec {
+    name: "lrange",
+    arity: 4,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@list", "@slow"],
+    group: "list",
+    since: "1.0.0",
+    summary: "Returns a range of elements from a list.",
+    get_keys: None,
+    parse: |args| Ok(Command::Lrange(Lrange::parse(args)?)),
+  },
+  CommandSpec {
+    name: "lrem",
+    arity: 4,
+    flags: &["write"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@list", "@slow"],
+    group: "list",
+    since: "1.0.0",
+    summary: "Removes elements from a list. Deletes the list if the last element was removed.",
+    get_keys: None,
+    parse: |args| Ok(Command::Lrem(Lrem::parse(args)?)),
+  },
+  CommandSpec {
+    name: "lset",
+    arity: 4,
+    flags: &["write", "denyoom"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@list", "@slow"],
+    group: "list",
+    since: "1.0.0",
+    summary: "Sets the value of an element in a list by its index.",
+    get_keys: None,
+    parse: |args| Ok(Command::Lset(Lset::parse(args)?)),
+  },
+  CommandSpec {
+    name: "ltrim",
+    arity: 4,
+    flags: &["write"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@list", "@slow"],
+    group: "list",
+    since: "1.0.0",
+    summary: "Removes elements from both ends a list. Deletes the list if all elements were trimmed.",
+    get_keys: None,
+    parse: |args| Ok(Command::Ltrim(Ltrim::parse(args)?)),
+  },
+  CommandSpec {
+    name: "mget",
+    arity: -2,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: -1,
+    step: 1,
+    acl_categories: &["@read", "@string", "@fast"],
+    group: "string",
+    since: "1.0.0",
+    summary: "Atomically returns the string values of one or more keys.",
+    get_keys: None,
+    parse: |args| Ok(Command::Mget(Mget::parse(args)?)),
+  },
+  CommandSpec {
+    name: "mset",
+    arity: -3,
+    flags: &["write", "denyoom"],
+    first_key: 1,
+    last_key: -1,
+    step: 2,
+    acl_categories: &["@write", "@string", "@slow"],
+    group: "string",
+    since: "1.0.1",
+    summary: "Atomically creates or modifies the string values of one or more keys.",
+    get_keys: None,
+    parse: |args| Ok(Command::Mset(Mset::parse(args)?)),
+  },
+  CommandSpec {
+    name: "msetnx",
+    arity: -3,
+    flags: &["write", "denyoom"],
+    first_key: 1,
+    last_key: -1,
+    step: 2,
+    acl_categories: &["@write", "@string", "@slow"],
+    group: "string",
+    since: "1.0.1",
+    summary: "Atomically modifies the string values of one or more keys only when all keys don't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Mset(Mset::parse_from(args, true)?)),
+  },
+  CommandSpec {
+    name: "persist",
+    arity: 2,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@keyspace", "@write", "@fast"],
+    group: "generic",
+    since: "2.2.0",
+    summary: "Removes the expiration time of a key.",
+    get_keys: None,
+    parse: |args| Ok(Command::Persist(Persist::parse(args)?)),
+  },
+  CommandSpec {
+    name: "pexpire",
+    arity: -3,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@keyspace", "@write", "@fast"],
+    group: "generic",
+    since: "2.6.0",
+    summary: "Sets the expiration time of a key in milliseconds.",
+    get_keys: None,
+    parse: |args| Ok(Command::Expire(Expire { unit: "PX".to_owned(), ..Expire::parse(args)? })),
+  },
+  CommandSpec {
+    name: "pexpireat",
+    arity: -3,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@keyspace", "@write", "@fast"],
+    group: "generic",
+    since: "2.6.0",
+    summary: "Sets the expiration time of a key to a Unix milliseconds timestamp.",
+    get_keys: None,
+    parse: |args| Ok(Command::Expire(Expire { unit: "PXAT".to_owned(), ..Expire::parse(args)? })),
+  },
+  CommandSpec {
+    name: "pexpiretime",
+    arity: 2,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@keyspace", "@read", "@fast"],
+    group: "generic",
+    since: "7.0.0",
+    summary: "Returns the expiration time of a key as a Unix milliseconds timestamp.",
+    get_keys: None,
+    parse: |args| Ok(Command::ExpireTime(ExpireTimeCmd { in_millis: true, ..ExpireTimeCmd::parse(args)? })),
+  },
+  CommandSpec {
+    name: "ping",
+    arity: -1,
+    flags: &["fast"],
+    first_key: 0,
+    last_key: 0,
+    step: 0,
+    acl_categories: &["@fast", "@connection"],
+    group: "connection",
+    since: "1.0.0",
+    summary: "Returns the server's liveliness response.",
+    get_keys: None,
+    parse: |args| Ok(Command::Ping(Ping::parse(args)?)),
+  },
+  CommandSpec {
+    name: "psetex",
+    arity: 4,
+    flags: &["write", "denyoom"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@string", "@slow"],
+    group: "string",
+    since: "2.6.0",
+    summary: "Sets both string value and expiration time in milliseconds of a key. The key is created if it doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Set(Set::parse_setex(args, "PX")?)),
+  },
+  CommandSpec {
+    name: "pttl",
+    arity: 2,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@keyspace", "@read", "@fast"],
+    group: "generic",
+    since: "2.6.0",
+    summary: "Returns the expiration time in milliseconds of a key.",
+    get_keys: None,
+    parse: |args| Ok(Command::Ttl(Ttl { in_millis: true, ..Ttl::parse(args)? })),
+  },
+  CommandSpec {
+    name: "rename",
+    arity: 3,
+    flags: &["write"],
+    first_key: 1,
+    last_key: 2,
+    step: 1,
+    acl_categories: &["@keyspace", "@write", "@slow"],
+    group: "generic",
+    since: "1.0.0",
+    summary: "Renames a key and overwrites the destination.",
+    get_keys: None,
+    parse: |args| Ok(Command::Rename(Rename::parse(args)?)),
+  },
+  CommandSpec {
+    name: "renamenx",
+    arity: 3,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 2,
+    step: 1,
+    acl_categories: &["@keyspace", "@write", "@fast"],
+    group: "generic",
+    since: "1.0.0",
+    summary: "Renames a key only when the target key name doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Rename(Rename { nx: true, ..Rename::parse(args)? })),
+  },
+  CommandSpec {
+    name: "rpop",
+    arity: -2,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@list", "@fast"],
+    group: "list",
+    since: "1.0.0",
+    summary: "Returns and removes the last elements of a list. Deletes the list if the last element was popped.",
+    get_keys: None,
+    parse: |args| Ok(Command::Pop(Pop::parse_from(args, ListEnd::Right)?)),
+  },
+  CommandSpec {
+    name: "rpoplpush",
+    arity: 3,
+    flags: &["write", "denyoom"],
+    first_key: 1,
+    last_key: 2,
+    step: 1,
+    acl_categories: &["@write", "@list", "@slow"],
+    group: "list",
+    since: "1.2.0",
+    summary: "Returns the last element of a list after removing and pushing it to another list. Deletes the list if the last element was popped.",
+    get_keys: None,
+    parse: |args| Ok(Command::Lmove(Lmove { source: args[0].clone(), destination: args[1].clone(), from: ListEnd::Right, to: ListEnd::Left })),
+  },
+  CommandSpec {
+    name: "rpush",
+    arity: -3,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@list", "@fast"],
+    group: "list",
+    since: "1.0.0",
+    summary: "Appends one or more elements to a list. Creates the key if it doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Push(Push { end: ListEnd::Right, ..Push::parse(args)? })),
+  },
+  CommandSpec {
+    name: "rpushx",
+    arity: -3,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@list", "@fast"],
+    group: "list",
+    since: "2.2.0",
+    summary: "Appends an element to a list only when the list exists.",
+    get_keys: None,
+    parse: |args| Ok(Command::Push(Push { end: ListEnd::Right, only_if_exists: true, ..Push::parse(args)? })),
+  },
+  CommandSpec {
+    name: "sadd",
+    arity: -3,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@set", "@fast"],
+    group: "set",
+    since: "1.0.0",
+    summary: "Adds one or more members to a set. Creates the key if it doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Sadd(Sadd::parse(args)?)),
+  },
+  CommandSpec {
+    name: "scard",
+    arity: 2,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@set", "@fast"],
+    group: "set",
+    since: "1.0.0",
+    summary: "Returns the number of members in a set.",
+    get_keys: None,
+    parse: |args| Ok(Command::Scard(Scard::parse(args)?)),
+  },
+  CommandSpec {
+    name: "sdiff",
+    arity: -2,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: -1,
+    step: 1,
+    acl_categories: &["@read", "@set", "@slow"],
+    group: "set",
+    since: "1.0.0",
+    summary: "Returns the difference of multiple sets.",
+    get_keys: None,
+    parse: |args| Ok(Command::SetAlgebra(SetAlgebra { operation: SetOperation::Diff, ..SetAlgebra::parse(args)? })),
+  },
+  CommandSpec {
+    name: "sdiffstore",
+    arity: -3,
+    flags: &["write", "denyoom"],
+    first_key: 1,
+    last_key: -1,
+    step: 1,
+    acl_categories: &["@write", "@set", "@slow"],
+    group: "set",
+    since: "1.0.0",
+    summary: "Stores the difference of multiple sets in a key.",
+    get_keys: None,
+    parse: |args| Ok(Command::SetAlgebra(SetAlgebra::parse_store(args, SetOperation::Diff)?)),
+  },
+  CommandSpec {
+    name: "set",
+    arity: -3,
+    flags: &["write", "denyoom"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@string", "@slow"],
+    group: "string",
+    since: "1.0.0",
+    summary:
+      "Sets the string value of a key, ignoring its type. The key is created if it doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Set(Set::parse(args)?)),
+  },
+  CommandSpec {
+    name: "setex",
+    arity: 4,
+    flags: &["write", "denyoom"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@string", "@slow"],
+    group: "string",
+    since: "2.0.0",
+    summary: "Sets the string value and expiration time of a key. Creates the key if it doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Set(Set::parse_setex(args, "EX")?)),
+  },
+  CommandSpec {
+    name: "setnx",
+    arity: 3,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@string", "@fast"],
+    group: "string",
+    since: "1.0.0",
+    summary: "Set the string value of a key only when the key doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Setnx(Setnx::parse(args)?)),
+  },
+  CommandSpec {
+    name: "setrange",
+    arity: 4,
+    flags: &["write", "denyoom"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@string", "@slow"],
+    group: "string",
+    since: "2.2.0",
+    summary: "Overwrites a part of a string value with another by an offset. Creates the key if it doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Setrange(Setrange::parse(args)?)),
+  },
+  CommandSpec {
+    name: "sinter",
+    arity: -2,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: -1,
+    step: 1,
+    acl_categories: &["@read", "@set", "@slow"],
+    group: "set",
+    since: "1.0.0",
+    summary: "Returns the intersect of multiple sets.",
+    get_keys: None,
+    parse: |args| Ok(Command::SetAlgebra(SetAlgebra::parse(args)?)),
+  },
+  CommandSpec {
+    name: "sintercard",
+    arity: -3,
+    flags: &["readonly", "movablekeys"],
+    first_key: 0,
+    last_key: 0,
+    step: 0,
+    acl_categories: &["@read", "@set", "@slow"],
+    group: "set",
+    since: "7.0.0",
+    summary: "Returns the number of members of the intersect of multiple sets.",
+    get_keys: Some(|argv| numkeys_keys(argv, 1)),
+    parse: |args| Ok(Command::Sintercard(Sintercard::parse(args)?)),
+  },
+  CommandSpec {
+    name: "sinterstore",
+    arity: -3,
+    flags: &["write", "denyoom"],
+    first_key: 1,
+    last_key: -1,
+    step: 1,
+    acl_categories: &["@write", "@set", "@slow"],
+    group: "set",
+    since: "1.0.0",
+    summary: "Stores the intersect of multiple sets in a key.",
+    get_keys: None,
+    parse: |args| Ok(Command::SetAlgebra(SetAlgebra::parse_store(args, SetOperation::Inter)?)),
+  },
+  CommandSpec {
+    name: "sismember",
+    arity: 3,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@set", "@fast"],
+    group: "set",
+    since: "1.0.0",
+    summary: "Determines whether a member belongs to a set.",
+    get_keys: None,
+    parse: |args| Ok(Command::Sismember(Sismember::parse(args)?)),
+  },
+  CommandSpec {
+    name: "smembers",
+    arity: 2,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@set", "@slow"],
+    group: "set",
+    since: "1.0.0",
+    summary: "Returns all members of a set.",
+    get_keys: None,
+    parse: |args| Ok(Command::Smembers(Smembers::parse(args)?)),
+  },
+  CommandSpec {
+    name: "smismember",
+    arity: -3,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@set", "@fast"],
+    group: "set",
+    since: "6.2.0",
+    summary: "Determines whether multiple members belong to a set.",
+    get_keys: None,
+    parse: |args| Ok(Command::Smismember(Smismember::parse(args)?)),
+  },
+  CommandSpec {
+    name: "smove",
+    arity: 4,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 2,
+    step: 1,
+    acl_categories: &["@write", "@set", "@fast"],
+    group: "set",
+    since: "1.0.0",
+    summary: "Moves a member from one set to another.",
+    get_keys: None,
+    parse: |args| Ok(Command::Smove(Smove::parse(args)?)),
+  },
+  CommandSpec {
+    name: "spop",
+    arity: -2,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@set", "@fast"],
+    group: "set",
+    since: "1.0.0",
+    summary: "Returns one or more random members from a set after removing them. Deletes the set if the last member was popped.",
+    get_keys: None,
+    parse: |args| Ok(Command::Spop(Spop::parse(args)?)),
+  },
+  CommandSpec {
+    name: "srandmember",
+    arity: -2,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@set", "@slow"],
+    group: "set",
+    since: "1.0.0",
+    summary: "Get one or multiple random members from a set",
+    get_keys: None,
+    parse: |args| Ok(Command::Srandmember(Srandmember::parse(args)?)),
+  },
+  CommandSpec {
+    name: "srem",
+    arity: -3,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@set", "@fast"],
+    group: "set",
+    since: "1.0.0",
+    summary: "Removes one or more members from a set. Deletes the set if the last member was removed.",
+    get_keys: None,
+    parse: |args| Ok(Command::Srem(Srem::parse(args)?)),
+  },
+  CommandSpec {
+    name: "strlen",
+    arity: 2,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@string", "@fast"],
+    group: "string",
+    since: "2.2.0",
+    summary: "Returns the length of a string value.",
+    get_keys: None,
+    parse: |args| Ok(Command::Strlen(Strlen::parse(args)?)),
+  },
+  CommandSpec {
+    name: "sunion",
+    arity: -2,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: -1,
+    step: 1,
+    acl_categories: &["@read", "@set", "@slow"],
+    group: "set",
+    since: "1.0.0",
+    summary: "Returns the union of multiple sets.",
+    get_keys: None,
+    parse: |args| Ok(Command::SetAlgebra(SetAlgebra { operation: SetOperation::Union, ..SetAlgebra::parse(args)? })),
+  },
+  CommandSpec {
+    name: "sunionstore",
+    arity: -3,
+    flags: &["write", "denyoom"],
+    first_key: 1,
+    last_key: -1,
+    step: 1,
+    acl_categories: &["@write", "@set", "@slow"],
+    group: "set",
+    since: "1.0.0",
+    summary: "Stores the union of multiple sets in a key.",
+    get_keys: None,
+    parse: |args| Ok(Command::SetAlgebra(SetAlgebra::parse_store(args, SetOperation::Union)?)),
+  },
+  CommandSpec {
+    name: "touch",
+    arity: -2,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: -1,
+    step: 1,
+    acl_categories: &["@keyspace", "@read", "@fast"],
+    group: "generic",
+    since: "3.2.1",
+    summary: "Returns the number of existing keys out of those specified after updating the time they were last accessed.",
+    get_keys: None,
+    parse: |args| Ok(Command::Touch(Touch::parse(args)?)),
+  },
+  CommandSpec {
+    name: "ttl",
+    arity: 2,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@keyspace", "@read", "@fast"],
+    group: "generic",
+    since: "1.0.0",
+    summary: "Returns the expiration time in seconds of a key.",
+    get_keys: None,
+    parse: |args| Ok(Command::Ttl(Ttl::parse(args)?)),
+  },
+  CommandSpec {
+    name: "type",
+    arity: 2,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@keyspace", "@read", "@fast"],
+    group: "generic",
+    since: "1.0.0",
+    summary: "Determines the type of value stored at a key.",
+    get_keys: None,
+    parse: |args| Ok(Command::Type(TypeCmd::parse(args)?)),
+  },
+  CommandSpec {
+    name: "unlink",
+    arity: -2,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: -1,
+    step: 1,
+    acl_categories: &["@keyspace", "@write", "@fast"],
+    group: "generic",
+    since: "4.0.0",
+    summary: "Asynchronously deletes one or more keys.",
+    get_keys: None,
+    parse: |args| Ok(Command::Unlink(Unlink::parse(args)?)),
+  },
+  CommandSpec {
+    name: "xack",
+    arity: -4,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@stream", "@fast"],
+    group: "stream",
+    since: "5.0.0",
+    summary: "Returns the number of messages that were successfully acknowledged by the consumer group member of a stream.",
+    get_keys: None,
+    parse: |args| Ok(Command::Xack(Xack::parse(args)?)),
+  },
+  CommandSpec {
+    name: "xadd",
+    arity: -5,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@stream", "@fast"],
+    group: "stream",
+    since: "5.0.0",
+    summary: "Appends a new message to a stream. Creates the key if it doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Xadd(Xadd::parse(args)?)),
+  },
+  CommandSpec {
+    name: "xautoclaim",
+    arity: -6,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@stream", "@fast"],
+    group: "stream",
+    since: "6.2.0",
+    summary: "Changes, or acquires, ownership of messages in a consumer group, as if the messages were delivered to as consumer group member.",
+    get_keys: None,
+    parse: |args| Ok(Command::Xautoclaim(Xautoclaim::parse(args)?)),
+  },
+  CommandSpec {
+    name: "xclaim",
+    arity: -6,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@stream", "@fast"],
+    group: "stream",
+    since: "5.0.0",
+    summary: "Changes, or acquires, ownership of a message in a consumer group, as if the message was delivered a consumer group member.",
+    get_keys: None,
+    parse: |args| Ok(Command::Xclaim(Xclaim::parse(args)?)),
+  },
+  CommandSpec {
+    name: "xdel",
+    arity: -3,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@stream", "@fast"],
+    group: "stream",
+    since: "5.0.0",
+    summary: "Returns the number of messages after removing them from a stream.",
+    get_keys: None,
+    parse: |args| Ok(Command::Xdel(Xdel::parse(args)?)),
+  },
+  CommandSpec {
+    name: "xgroup",
+    arity: -2,
+    flags: &[],
+    first_key: 0,
+    last_key: 0,
+    step: 0,
+    acl_categories: &["@slow"],
+    group: "stream",
+    since: "5.0.0",
+    summary: "A container for consumer groups commands.",
+    get_keys: None,
+    parse: |args| Ok(Command::Xgroup(Xgroup::parse(args)?)),
+  },
+  CommandSpec {
+    name: "xinfo",
+    arity: -2,
+    flags: &[],
+    first_key: 0,
+    last_key: 0,
+    step: 0,
+    acl_categories: &["@slow"],
+    group: "stream",
+    since: "5.0.0",
+    summary: "A container for stream introspection commands.",
+    get_keys: None,
+    parse: |args| Ok(Command::Xinfo(Xinfo::parse(args)?)),
+  },
+  CommandSpec {
+    name: "xlen",
+    arity: 2,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@stream", "@fast"],
+    group: "stream",
+    since: "5.0.0",
+    summary: "Return the number of messages in a stream.",
+    get_keys: None,
+    parse: |args| Ok(Command::Xlen(Xlen::parse(args)?)),
+  },
+  CommandSpec {
+    name: "xpending",
+    arity: -3,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@stream", "@slow"],
+    group: "stream",
+    since: "5.0.0",
+    summary: "Returns the information and entries from a stream consumer group's pending entries list.",
+    get_keys: None,
+    parse: |args| Ok(Command::Xpending(Xpending::parse(args)?)),
+  },
+  CommandSpec {
+    name: "xrange",
+    arity: -4,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@stream", "@slow"],
+    group: "stream",
+    since: "5.0.0",
+    summary: "Returns the messages from a stream within a range of IDs.",
+    get_keys: None,
+    parse: |args| Ok(Command::Xrange(Xrange::parse(args)?)),
+  },
+  CommandSpec {
+    name: "xread",
+    arity: -4,
+    flags: &["readonly", "blocking", "movablekeys"],
+    first_key: 0,
+    last_key: 0,
+    step: 0,
+    acl_categories: &["@read", "@stream", "@slow", "@blocking"],
+    group: "stream",
+    since: "5.0.0",
+    summary: "Returns messages from multiple streams with IDs greater than the ones requested. Blocks until a message is available otherwise.",
+    get_keys: Some(streams_keys),
+    parse: |args| Ok(Command::Xread(Xread::parse(args)?)),
+  },
+  CommandSpec {
+    name: "xreadgroup",
+    arity: -7,
+    flags: &["write", "blocking", "movablekeys"],
+    first_key: 0,
+    last_key: 0,
+    step: 0,
+    acl_categories: &["@write", "@stream", "@slow", "@blocking"],
+    group: "stream",
+    since: "5.0.0",
+    summary: "Returns new or historical messages from a stream for a consumer in a group. Blocks until a message is available otherwise.",
+    get_keys: Some(streams_keys),
+    parse: |args| Ok(Command::Xreadgroup(Xreadgroup::parse(args)?)),
+  },
+  CommandSpec {
+    name: "xrevrange",
+    arity: -4,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@stream", "@slow"],
+    group: "stream",
+    since: "5.0.0",
+    summary: "Returns the messages from a stream within a range of IDs in reverse order.",
+    get_keys: None,
+    parse: |args| Ok(Command::Xrange(Xrange::parse_from(args, true)?)),
+  },
+  CommandSpec {
+    name: "xtrim",
+    arity: -4,
+    flags: &["write"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@stream", "@slow"],
+    group: "stream",
+    since: "5.0.0",
+    summary: "Deletes messages from the beginning of a stream.",
+    get_keys: None,
+    parse: |args| Ok(Command::Xtrim(Xtrim::parse(args)?)),
+  },
+  CommandSpec {
+    name: "zadd",
+    arity: -4,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@sortedset", "@fast"],
+    group: "sorted-set",
+    since: "1.2.0",
+    summary: "Adds one or more members to a sorted set, or updates their scores. Creates the key if it doesn't exist.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zadd(Zadd::parse(args)?)),
+  },
+  CommandSpec {
+    name: "zcard",
+    arity: 2,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@sortedset", "@fast"],
+    group: "sorted-set",
+    since: "1.2.0",
+    summary: "Returns the number of members in a sorted set.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zcard(Zcard::parse(args)?)),
+  },
+  CommandSpec {
+    name: "zcount",
+    arity: 4,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@sortedset", "@fast"],
+    group: "sorted-set",
+    since: "2.0.0",
+    summary: "Returns the count of members in a sorted set that have scores within a range.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zcount(Zcount::parse(args)?)),
+  },
+  CommandSpec {
+    name: "zincrby",
+    arity: 4,
+    flags: &["write", "denyoom", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@sortedset", "@fast"],
+    group: "sorted-set",
+    since: "1.2.0",
+    summary: "Increments the score of a member in a sorted set.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zincrby(Zincrby::parse(args)?)),
+  },
+  CommandSpec {
+    name: "zinterstore",
+    arity: -4,
+    flags: &["write", "denyoom", "movablekeys"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@sortedset", "@slow"],
+    group: "sorted-set",
+    since: "2.0.0",
+    summary: "Stores the intersect of multiple sorted sets in a key.",
+    get_keys: Some(destination_numkeys_keys),
+    parse: |args| Ok(Command::ZsetAlgebra(ZsetAlgebra::parse_from(args, SetOperation::Inter)?)),
+  },
+  CommandSpec {
+    name: "zmpop",
+    arity: -4,
+    flags: &["write", "movablekeys"],
+    first_key: 0,
+    last_key: 0,
+    step: 0,
+    acl_categories: &["@write", "@sortedset", "@slow"],
+    group: "sorted-set",
+    since: "7.0.0",
+    summary: "Returns the highest- or lowest-scoring members from one or more sorted sets after removing them. Deletes the sorted set if the last member was popped.",
+    get_keys: Some(|argv| numkeys_keys(argv, 1)),
+    parse: |args| Ok(Command::Zmpop(Zmpop::parse(args)?)),
+  },
+  CommandSpec {
+    name: "zpopmax",
+    arity: -2,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@sortedset", "@fast"],
+    group: "sorted-set",
+    since: "5.0.0",
+    summary: "Returns the highest-scoring members from a sorted set after removing them. Deletes the sorted set if the last member was popped.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zpop(Zpop { max: true, ..Zpop::parse(args)? })),
+  },
+  CommandSpec {
+    name: "zpopmin",
+    arity: -2,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@sortedset", "@fast"],
+    group: "sorted-set",
+    since: "5.0.0",
+    summary: "Returns the lowest-scoring members from a sorted set after removing them. Deletes the sorted set if the last member was popped.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zpop(Zpop::parse(args)?)),
+  },
+  CommandSpec {
+    name: "zrange",
+    arity: -4,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@sortedset", "@slow"],
+    group: "sorted-set",
+    since: "1.2.0",
+    summary: "Returns members in a sorted set within a range of indexes.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zrange(Zrange::parse(args)?)),
+  },
+  CommandSpec {
+    name: "zrangebylex",
+    arity: -4,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@sortedset", "@slow"],
+    group: "sorted-set",
+    since: "2.8.9",
+    summary: "Returns members in a sorted set within a lexicographical range.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zrange(Zrange::parse_from(args, Some(RangeKind::Lex), false)?)),
+  },
+  CommandSpec {
+    name: "zrangebyscore",
+    arity: -4,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@sortedset", "@slow"],
+    group: "sorted-set",
+    since: "1.0.5",
+    summary: "Returns members in a sorted set within a range of scores.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zrange(Zrange::parse_from(args, Some(RangeKind::Score), false)?)),
+  },
+  CommandSpec {
+    name: "zrangestore",
+    arity: -5,
+    flags: &["write", "denyoom"],
+    first_key: 1,
+    last_key: 2,
+    step: 1,
+    acl_categories: &["@write", "@sortedset", "@slow"],
+    group: "sorted-set",
+    since: "6.2.0",
+    summary: "Stores a range of members from sorted set in a key.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zrange(Zrange::parse_store(args)?)),
+  },
+  CommandSpec {
+    name: "zrank",
+    arity: -3,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@sortedset", "@fast"],
+    group: "sorted-set",
+    since: "2.0.0",
+    summary: "Returns the index of a member in a sorted set ordered by ascending scores.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zrank(Zrank::parse(args)?)),
+  },
+  CommandSpec {
+    name: "zrem",
+    arity: -3,
+    flags: &["write", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@sortedset", "@fast"],
+    group: "sorted-set",
+    since: "1.2.0",
+    summary: "Removes one or more members from a sorted set. Deletes the sorted set if all members were removed.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zrem(Zrem::parse(args)?)),
+  },
+  CommandSpec {
+    name: "zrevrange",
+    arity: -4,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@sortedset", "@slow"],
+    group: "sorted-set",
+    since: "1.2.0",
+    summary: "Returns members in a sorted set within a range of indexes in reverse order.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zrange(Zrange::parse_from(args, Some(RangeKind::Index), true)?)),
+  },
+  CommandSpec {
+    name: "zrevrangebylex",
+    arity: -4,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@sortedset", "@slow"],
+    group: "sorted-set",
+    since: "2.8.9",
+    summary: "Returns members in a sorted set within a lexicographical range in reverse order.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zrange(Zrange::parse_from(args, Some(RangeKind::Lex), true)?)),
+  },
+  CommandSpec {
+    name: "zrevrangebyscore",
+    arity: -4,
+    flags: &["readonly"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@sortedset", "@slow"],
+    group: "sorted-set",
+    since: "2.2.0",
+    summary: "Returns members in a sorted set within a range of scores in reverse order.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zrange(Zrange::parse_from(args, Some(RangeKind::Score), true)?)),
+  },
+  CommandSpec {
+    name: "zrevrank",
+    arity: -3,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@sortedset", "@fast"],
+    group: "sorted-set",
+    since: "2.0.0",
+    summary: "Returns the index of a member in a sorted set ordered by descending scores.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zrank(Zrank { rev: true, ..Zrank::parse(args)? })),
+  },
+  CommandSpec {
+    name: "zscore",
+    arity: 3,
+    flags: &["readonly", "fast"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@read", "@sortedset", "@fast"],
+    group: "sorted-set",
+    since: "1.2.0",
+    summary: "Returns the score of a member in a sorted set.",
+    get_keys: None,
+    parse: |args| Ok(Command::Zscore(Zscore::parse(args)?)),
+  },
+  CommandSpec {
+    name: "zunionstore",
+    arity: -4,
+    flags: &["write", "denyoom", "movablekeys"],
+    first_key: 1,
+    last_key: 1,
+    step: 1,
+    acl_categories: &["@write", "@sortedset", "@slow"],
+    group: "sorted-set",
+    since: "2.0.0",
+    summary: "Stores the union of multiple sorted sets in a key.",
+    get_keys: Some(destination_numkeys_keys),
+    parse: |args| Ok(Command::ZsetAlgebra(ZsetAlgebra::parse_from(args, SetOperation::Union)?)),
+  },
+];
+
+/// Finds the spec of a command by its case-insensitive name.
+pub fn lookup(name: &[u8]) -> Option<&'static CommandSpec> {
+  static SPECS_BY_NAME: OnceLock<HashMap<&'static [u8], &'static CommandSpec>> = OnceLock::new();
+
+  SPECS_BY_NAME
+    .get_or_init(|| {
+      COMMAND_TABLE
+        .iter()
+        .map(|spec| (spec.name.as_bytes(), spec))
+        .collect()
+    })
+    .get(name.to_ascii_lowercase().as_slice())
+    .copied()
+}
+
+impl CommandSpec {
+  /// `argc` counts the command name, like `arity` does.
+  pub fn accepts_arity(&self, argc: usize) -> bool {
+    let argc = argc as i64;
+    if self.arity >= 0 {
+      argc == self.arity
+    } else {
+      argc >= -self.arity
+    }
+  }
+
+  /// Parses the arguments following the command name, after checking them against `arity`.
+  pub fn parse_args(&self, args: &[Bytes]) -> Result<Command> {
+    if !self.accepts_arity(args.len() + 1) {
+      bail!(RedisError::WrongArity(self.name.to_owned()));
+    }
+    (self.parse)(args)
+  }
+
+  /// Extracts the keys from a full argument vector (command name included) using the key positions.
+  pub fn keys<'a>(&self, argv: &'a [Bytes]) -> Vec<&'a Bytes> {
+    if let Some(get_keys) = self.get_keys {
+      return get_keys(argv)
+        .into_iter()
+        .filter_map(|pos| argv.get(pos))
+        .collect();
+    }
+    if self.first_key <= 0 {
+      return vec![];
+    }
+    let last_key = if self.last_key < 0 {
+      argv.len() as i64 + self.last_key
+    } else {
+      self.last_key
+    };
+    (self.first_key..=last_key)
+      .step_by(self.step.max(1) as usize)
+      .filter_map(|pos| argv.get(pos as usize))
+      .collect()
+  }
+
+  /// The reply of `COMMAND INFO` for this command.
+  pub fn info(&self) -> Reply {
+    Reply::Array(vec![
+      Reply::BulkString(Bytes::from_static(self.name.as_bytes())),
+      Reply::Integer(self.arity),
+      Reply::Set(status_strings(self.flags)),
+      Reply::Integer(self.first_key),
+      Reply::Integer(self.last_key),
+      Reply::Integer(self.step),
+      Reply::Set(status_strings(self.acl_categories)),
+      // tips, key specifications and subcommands
+      Reply::Array(vec![]),
+      Reply::Array(vec![]),
+      Reply::Array(vec![]),
+    ])
+  }
+
+  /// The reply of `COMMAND DOCS` for this command.
+  pub fn docs(&self) -> Reply {
+    Reply::Map(vec![
+      (bulk("summary"), bulk(self.summary)),
+      (bulk("since"), bulk(self.since)),
+      (bulk("group"), bulk(self.group)),
+    ])
+  }
+}
+
+/// The keys following a `numkeys` argument at `numkeys_pos`, like those of `LMPOP`. Finds none
+/// when `numkeys` isn't a positive integer or counts more arguments than there are.
+fn numkeys_keys(argv: &[Bytes], numkeys_pos: usize) -> Vec<usize> {
+  let numkeys = argv
+    .get(numkeys_pos)
+    .and_then(|numkeys| String::from_utf8_lossy(numkeys).parse::<usize>().ok())
+    .unwrap_or(0);
+  let first_key = numkeys_pos + 1;
+  if numkeys > argv.len().saturating_sub(first_key) {
+    return vec![];
+  }
+  (first_key..first_key + numkeys).collect()
+}
+
+/// The destination and source keys of `ZUNIONSTORE` and `ZINTERSTORE`, or none when the sources
+/// can't be found.
+fn destination_numkeys_keys(argv: &[Bytes]) -> Vec<usize> {
+  let sources = numkeys_keys(argv, 2);
+  if sources.is_empty() {
+    return vec![];
+  }
+  [1].into_iter().chain(sources).collect()
+}
+
+/// The keys of `XREAD` and `XREADGROUP`, the first half of the arguments after `STREAMS`, or none when the options
+/// before it or the number of arguments after it are off.
+fn streams_keys(argv: &[Bytes]) -> Vec<usize> {
+  let mut position = 1;
+  while let Some(arg) = argv.get(position) {
+    if arg.eq_ignore_ascii_case(b"COUNT") || arg.eq_ignore_ascii_case(b"BLOCK") {
+      position += 2;
+    } else if arg.eq_ignore_ascii_case(b"GROUP") {
+      position += 3;
+    } else if arg.eq_ignore_ascii_case(b"NOACK") {
+      position += 1;
+    } else if arg.eq_ignore_ascii_case(b"STREAMS") {
+      let streams = argv.len() - position - 1;
+      if streams == 0 || !streams.is_multiple_of(2) {
+        return vec![];
+      }
+      return (position + 1..position + 1 + streams / 2).collect();
+    } else {
+      break;
+    }
+  }
+  vec![]
+}
+
+fn status_strings(strings: &[&str]) -> Vec<Reply> {
+  strings
+    .iter()
+    .map(|string| Reply::SimpleString(string.to_string()))
+    .collect()
+}
+
+fn bulk(string: &'static str) -> Reply {
+  Reply::BulkString(Bytes::from_static(string.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests_command_table {
+  use super::*;
+
+  #[test]
+  fn should_keep_table_sorted_and_unique() {
+    let names: Vec<_> = COMMAND_TABLE.iter().map(|spec| spec.name).collect();
+    let mut sorted_names = names.clone();
+    sorted_names.sort();
+    sorted_names.dedup();
+    assert_eq!(names, sorted_names);
+  }
+
+  #[test]
+  fn should_check_arity() {
+    let get = lookup(b"GeT").unwrap();
+    assert!(get.accepts_arity(2));
+    assert!(!get.accepts_arity(3));
+
+    let set = lookup(b"set").unwrap();
+    assert!(!set.accepts_arity(2));
+    assert!(set.accepts_arity(3));
+    assert!(set.accepts_arity(5));
+  }
+
+  #[test]
+  fn should_extract_keys_from_positions() {
+    let argv: Vec<Bytes> = ["SET", "k", "v", "EX", "10"]
+      .iter()
+      .map(|arg| Bytes::from(*arg))
+      .collect();
+    assert_eq!(lookup(b"set").unwrap().keys(&argv), vec![&argv[1]]);
+    assert!(lookup(b"ping").unwrap().keys(&argv).is_empty());
+  }
+}
@@ -0,0 +1,85 @@
+use thiserror::Error;
+
+/// Errors reported to clients. Each one renders as a single line starting with a Redis error code,
+/// so it can be sent as is in a `-` error reply.
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum RedisError {
+  #[error("ERR {0}")]
+  Generic(String),
+  #[error("ERR syntax error")]
+  Syntax,
+  #[error("ERR wrong number of arguments for '{0}' command")]
+  WrongArity(String),
+  #[error("ERR unknown command '{name}', with args beginning with: {args}")]
+  UnknownCommand { name: String, args: String },
+  #[error("ERR value is not an integer or out of range")]
+  NotInteger,
+  #[error("ERR value is out of range, must be positive")]
+  NotPositive,
+  #[error("ERR value is not a valid float")]
+  NotFloat,
+  #[error("ERR invalid expire time in '{0}' command")]
+  InvalidExpireTime(String),
+  #[error("ERR Invalid stream ID specified as stream command argument")]
+  InvalidStreamId,
+  #[error("ERR Protocol error: {0}")]
+  Protocol(String),
+  #[error("WRONGTYPE Operation against a key holding the wrong kind of value")]
+  WrongType,
+  #[error("BUSYGROUP Consumer Group name already exists")]
+  BusyGroup,
+  #[error("NOGROUP {0}")]
+  NoGroup(String),
+  #[error("NOSCRIPT No matching script. Please use EVAL.")]
+  NoScript,
+  #[error(
+    "BUSY Redis is busy running a script. You can only call SCRIPT KILL or SHUTDOWN NOSAVE."
+  )]
+  Busy,
+  #[error("NOAUTH Authentication required.")]
+  NoAuth,
+  #[error("WRONGPASS invalid username-password pair or user is disabled.")]
+  WrongPass,
+  #[error("NOPROTO sorry, this protocol version is not supported.")]
+  NoProto,
+  #[error("EXECABORT Transaction discarded because of previous errors.")]
+  ExecAbort,
+}
+
+impl RedisError {
+  pub fn unknown_command(name: &[u8], args: &[&[u8]]) -> Self {
+    RedisError::UnknownCommand {
+      name: String::from_utf8_lossy(name).into_owned(),
+      args: args
+        .iter()
+        .map(|arg| format!("'{}' ", String::from_utf8_lossy(arg)))
+        .collect(),
+    }
+  }
+
+  /// NOGROUP as most stream commands report a missing stream or consumer group.
+  pub fn no_group(key: &[u8], group: &[u8]) -> Self {
+    RedisError::NoGroup(format!(
+      "No such key '{}' or consumer group '{}'",
+      String::from_utf8_lossy(key),
+      String::from_utf8_lossy(group)
+    ))
+  }
+}
+
+#[cfg(test)]
+mod tests_redis_error {
+  use super::*;
+
+  #[test]
+  fn should_render_redis_compatible_messages() {
+    assert_eq!(
+      RedisError::WrongArity("get".to_owned()).to_string(),
+      "ERR wrong number of arguments for 'get' command"
+    );
+    assert_eq!(
+      RedisError::unknown_command(b"foo", &[b"a", b"b"]).to_string(),
+      "ERR unknown command 'foo', with args beginning with: 'a' 'b' "
+    );
+  }
+}
@@ -1,44 +1,57 @@
 use super::{bail, Context, Result};
 use super::{RespValue, Token};
 
-pub fn parse(tokens: &[Token]) -> Result<RespValue> {
+/// Parses every top-level value in `tokens`, so pipelined requests yield one value per frame.
+pub fn parse(tokens: &[Token]) -> Result<Vec<RespValue>> {
   let mut iter = tokens.iter().peekable();
 
-  fn _parse(iter: &mut std::iter::Peekable<std::slice::Iter<Token>>) -> Result<RespValue> {
+  type TokenIter<'a> = std::iter::Peekable<std::slice::Iter<'a, Token>>;
+
+  fn _parse(iter: &mut TokenIter) -> Result<RespValue> {
     match iter.next() {
-      Some(Token::Array(length)) => {
-        let mut cmd = vec![];
-        for _ in 0..*length {
-          let element = _parse(iter).context("failed to parse array element")?;
-          cmd.push(element);
-        }
-        if let Some(_) = iter.peek() {
-          bail!(
-            "Array length({}) exceeds the actual number of elements",
-            *length
-          )
-        } else {
-          Ok(RespValue::Array(cmd))
-        }
+      Some(Token::Array(length)) => Ok(RespValue::Array(
+        _parse_elements(iter, *length).context("failed to parse array element")?,
+      )),
+      Some(Token::Set(length)) => Ok(RespValue::Set(
+        _parse_elements(iter, *length).context("failed to parse set element")?,
+      )),
+      Some(Token::Push(length)) => Ok(RespValue::Push(
+        _parse_elements(iter, *length).context("failed to parse push element")?,
+      )),
+      Some(Token::Map(length)) => Ok(RespValue::Map(
+        _parse_pairs(iter, *length).context("failed to parse map entry")?,
+      )),
+      Some(Token::Attribute(length)) => {
+        let attributes = _parse_pairs(iter, *length).context("failed to parse attribute")?;
+        let value = _parse(iter).context("attribute should be followed by a value")?;
+        Ok(RespValue::Attribute {
+          attributes,
+          value: Box::new(value),
+        })
       }
       Some(Token::BulkString(length)) => {
-        let string_value_token = iter
-          .next()
-          .context("BulkString indicator exists, but actual string value is not given")?;
-        if let Token::StringValue(string_value) = string_value_token {
-          if *length != string_value.len() {
-            bail!("BulkString length does not match with actual string value")
-          } else {
-            Ok(RespValue::BulkString(string_value.clone()))
-          }
-        } else {
-          bail!(
-            "expected BulkString string value, but {:?} is given",
-            string_value_token
-          )
+        let string_value = _parse_string_value(iter, *length)?;
+        Ok(RespValue::BulkString(string_value))
+      }
+      Some(Token::VerbatimString(length)) => {
+        let string_value = _parse_string_value(iter, *length)?;
+        if string_value.get(3) != Some(&b':') {
+          bail!("VerbatimString should start with a three bytes format followed by ':'")
         }
+        Ok(RespValue::VerbatimString {
+          format: string_value.slice(..3),
+          text: string_value.slice(4..),
+        })
       }
+      Some(Token::NullArray) => Ok(RespValue::NullArray),
       Some(Token::NullBulkString) => Ok(RespValue::NullBulkString),
+      Some(Token::SimpleString(string)) => Ok(RespValue::SimpleString(string.clone())),
+      Some(Token::Error(message)) => Ok(RespValue::Error(message.clone())),
+      Some(Token::Integer(integer)) => Ok(RespValue::Integer(*integer)),
+      Some(Token::Null) => Ok(RespValue::Null),
+      Some(Token::Double(double)) => Ok(RespValue::Double(*double)),
+      Some(Token::Boolean(boolean)) => Ok(RespValue::Boolean(*boolean)),
+      Some(Token::BigNumber(big_number)) => Ok(RespValue::BigNumber(big_number.clone())),
       Some(Token::StringValue(_)) => {
         bail!("string value should always be consumed in BulkString match clause")
       }
@@ -48,5 +61,71 @@ pub fn parse(tokens: &[Token]) -> Result<RespValue> {
     }
   }
 
-  _parse(&mut iter).context("failed to parse")
+  fn _parse_elements(iter: &mut TokenIter, length: usize) -> Result<Vec<RespValue>> {
+    (0..length).map(|_| _parse(iter)).collect()
+  }
+
+  fn _parse_pairs(iter: &mut TokenIter, length: usize) -> Result<Vec<(RespValue, RespValue)>> {
+    (0..length)
+      .map(|_| Ok((_parse(iter)?, _parse(iter)?)))
+      .collect()
+  }
+
+  fn _parse_string_value(iter: &mut TokenIter, length: usize) -> Result<bytes::Bytes> {
+    let string_value_token = iter
+      .next()
+      .context("BulkString indicator exists, but actual string value is not given")?;
+    if let Token::StringValue(string_value) = string_value_token {
+      if length != string_value.len() {
+        bail!("BulkString length does not match with actual string value")
+      } else {
+        Ok(string_value.clone())
+      }
+    } else {
+      bail!(
+        "expected BulkString string value, but {:?} is given",
+        string_value_token
+      )
+    }
+  }
+
+  let mut values = vec![];
+  while iter.peek().is_some() {
+    values.push(_parse(&mut iter).context("failed to parse")?);
+  }
+  Ok(values)
+}
+
+#[cfg(test)]
+mod tests_parsing {
+  use super::*;
+  use crate::resp_server::tokenizer::tokenize;
+  use bytes::Bytes;
+
+  #[test]
+  fn should_parse_resp3_aggregates() {
+    let tokens =
+      tokenize(b"%1\r\n+k\r\n~2\r\n:1\r\n_\r\n|1\r\n+a\r\n#f\r\n=7\r\ntxt:abc\r\n").unwrap();
+    let values = parse(&tokens).unwrap();
+
+    assert_eq!(
+      values,
+      vec![
+        RespValue::Map(vec![(
+          RespValue::SimpleString(Bytes::from("k")),
+          RespValue::Set(vec![RespValue::Integer(1), RespValue::Null]),
+        )]),
+        RespValue::Attribute {
+          attributes: vec![(
+            RespValue::SimpleString(Bytes::from("a")),
+            RespValue::Boolean(false),
+          )],
+          value: Box::new(RespValue::VerbatimString {
+            format: Bytes::from("txt"),
+            text: Bytes::from("abc"),
+          }),
+        },
+      ]
+    );
+  }
 }
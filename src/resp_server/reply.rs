@@ -0,0 +1,258 @@
+use bytes::{BufMut, Bytes, BytesMut};
+
+use super::{Protocol, NULL_BULK_STRING};
+
+const NULL_ARRAY: &str = "*-1";
+const NULL: &str = "_";
+
+/// A reply to a client command.
+/// RESP3-only types are downgraded to their closest RESP2 form when encoded for a RESP2 client.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Reply {
+  SimpleString(String),
+  Error(String),
+  Integer(i64),
+  BulkString(Bytes),
+  NullBulkString,
+  Array(Vec<Reply>),
+  NullArray,
+  Null,
+  Map(Vec<(Reply, Reply)>),
+  Set(Vec<Reply>),
+  Double(f64),
+  Boolean(bool),
+  BigNumber(String),
+  VerbatimString {
+    format: String,
+    text: Bytes,
+  },
+  Attribute {
+    attributes: Vec<(Reply, Reply)>,
+    reply: Box<Reply>,
+  },
+  Push(Vec<Reply>),
+}
+
+impl Reply {
+  pub fn ok() -> Self {
+    Reply::SimpleString("OK".to_owned())
+  }
+
+  pub fn encode(&self, protocol: Protocol) -> Vec<u8> {
+    let mut buf = BytesMut::new();
+    self.encode_into(&mut buf, protocol);
+    buf.to_vec()
+  }
+
+  pub fn encode_into(&self, buf: &mut BytesMut, protocol: Protocol) {
+    match (self, protocol) {
+      // simple strings and errors can't contain CR or LF, so they are replaced to keep the line valid
+      (Reply::SimpleString(string), _) => put_line(buf, b'+', &single_line(string)),
+      (Reply::Error(message), _) => put_line(buf, b'-', &single_line(message)),
+      (Reply::Integer(integer), _) => put_line(buf, b':', &integer.to_string()),
+      (Reply::BulkString(bytes), _) => put_blob(buf, b'$', bytes),
+      (Reply::Array(elements), _) => put_aggregate(buf, b'*', elements, protocol),
+      (Reply::NullBulkString | Reply::Null, Protocol::Resp2) => {
+        buf.put_slice(NULL_BULK_STRING.as_bytes());
+        buf.put_slice(b"\r\n");
+      }
+      (Reply::NullArray, Protocol::Resp2) => {
+        buf.put_slice(NULL_ARRAY.as_bytes());
+        buf.put_slice(b"\r\n");
+      }
+      (Reply::NullBulkString | Reply::NullArray | Reply::Null, Protocol::Resp3) => {
+        buf.put_slice(NULL.as_bytes());
+        buf.put_slice(b"\r\n");
+      }
+      (Reply::Map(entries), Protocol::Resp2) => {
+        put_line(buf, b'*', &(entries.len() * 2).to_string());
+        for (key, value) in entries {
+          key.encode_into(buf, protocol);
+          value.encode_into(buf, protocol);
+        }
+      }
+      (Reply::Map(entries), Protocol::Resp3) => put_map(buf, b'%', entries, protocol),
+      (Reply::Set(elements) | Reply::Push(elements), Protocol::Resp2) => {
+        put_aggregate(buf, b'*', elements, protocol)
+      }
+      (Reply::Set(elements), Protocol::Resp3) => put_aggregate(buf, b'~', elements, protocol),
+      (Reply::Push(elements), Protocol::Resp3) => put_aggregate(buf, b'>', elements, protocol),
+      (Reply::Double(double), Protocol::Resp2) => {
+        put_blob(buf, b'$', format_double(*double).as_bytes())
+      }
+      (Reply::Double(double), Protocol::Resp3) => put_line(buf, b',', &format_double(*double)),
+      (Reply::Boolean(boolean), Protocol::Resp2) => {
+        put_line(buf, b':', if *boolean { "1" } else { "0" })
+      }
+      (Reply::Boolean(boolean), Protocol::Resp3) => {
+        put_line(buf, b'#', if *boolean { "t" } else { "f" })
+      }
+      (Reply::BigNumber(big_number), Protocol::Resp2) => put_blob(buf, b'$', big_number.as_bytes()),
+      (Reply::BigNumber(big_number), Protocol::Resp3) => put_line(buf, b'(', big_number),
+      (Reply::VerbatimString { text, .. }, Protocol::Resp2) => put_blob(buf, b'$', text),
+      (Reply::VerbatimString { format, text }, Protocol::Resp3) => {
+        put_blob(buf, b'=', &[format.as_bytes(), b":", text].concat())
+      }
+      // RESP2 has no out-of-band data, so attributes are simply dropped
+      (Reply::Attribute { reply, .. }, Protocol::Resp2) => reply.encode_into(buf, protocol),
+      (Reply::Attribute { attributes, reply }, Protocol::Resp3) => {
+        put_map(buf, b'|', attributes, protocol);
+        reply.encode_into(buf, protocol);
+      }
+    }
+  }
+}
+
+impl From<Option<Bytes>> for Reply {
+  fn from(value: Option<Bytes>) -> Self {
+    match value {
+      Some(bytes) => Reply::BulkString(bytes),
+      None => Reply::NullBulkString,
+    }
+  }
+}
+
+impl From<Vec<Bytes>> for Reply {
+  fn from(elements: Vec<Bytes>) -> Self {
+    Reply::Array(elements.into_iter().map(Reply::BulkString).collect())
+  }
+}
+
+fn put_line(buf: &mut BytesMut, type_byte: u8, line: &str) {
+  buf.put_u8(type_byte);
+  buf.put_slice(line.as_bytes());
+  buf.put_slice(b"\r\n");
+}
+
+fn put_blob(buf: &mut BytesMut, type_byte: u8, bytes: &[u8]) {
+  put_line(buf, type_byte, &bytes.len().to_string());
+  buf.put_slice(bytes);
+  buf.put_slice(b"\r\n");
+}
+
+fn put_aggregate(buf: &mut BytesMut, type_byte: u8, elements: &[Reply], protocol: Protocol) {
+  put_line(buf, type_byte, &elements.len().to_string());
+  for element in elements {
+    element.encode_into(buf, protocol);
+  }
+}
+
+fn put_map(buf: &mut BytesMut, type_byte: u8, entries: &[(Reply, Reply)], protocol: Protocol) {
+  put_line(buf, type_byte, &entries.len().to_string());
+  for (key, value) in entries {
+    key.encode_into(buf, protocol);
+    value.encode_into(buf, protocol);
+  }
+}
+
+fn single_line(string: &str) -> String {
+  string.replace(['\r', '\n'], " ")
+}
+
+pub fn format_double(double: f64) -> String {
+  if double.is_nan() {
+    "nan".to_owned()
+  } else if double.is_infinite() {
+    if double > 0.0 { "inf" } else { "-inf" }.to_owned()
+  } else {
+    double.to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests_reply_encoding {
+  use super::*;
+
+  #[test]
+  fn should_encode_scalar_replies() {
+    assert_eq!(Reply::ok().encode(Protocol::Resp2), b"+OK\r\n");
+    assert_eq!(
+      Reply::Error("ERR unknown".to_owned()).encode(Protocol::Resp2),
+      b"-ERR unknown\r\n"
+    );
+    assert_eq!(Reply::Integer(-42).encode(Protocol::Resp2), b":-42\r\n");
+    assert_eq!(Reply::NullBulkString.encode(Protocol::Resp2), b"$-1\r\n");
+    assert_eq!(Reply::NullArray.encode(Protocol::Resp2), b"*-1\r\n");
+  }
+
+  #[test]
+  fn should_encode_bulk_string_containing_crlf() {
+    let reply = Reply::BulkString(Bytes::from_static(b"a\r\nb"));
+    assert_eq!(reply.encode(Protocol::Resp2), b"$4\r\na\r\nb\r\n");
+  }
+
+  #[test]
+  fn should_keep_simple_string_on_a_single_line() {
+    let reply = Reply::SimpleString("multi\r\nline".to_owned());
+    assert_eq!(reply.encode(Protocol::Resp2), b"+multi  line\r\n");
+  }
+
+  #[test]
+  fn should_encode_nested_arrays() {
+    let reply = Reply::Array(vec![
+      Reply::Integer(1),
+      Reply::Array(vec![
+        Reply::BulkString(Bytes::from("x")),
+        Reply::NullBulkString,
+      ]),
+      Reply::Array(vec![]),
+    ]);
+    assert_eq!(
+      reply.encode(Protocol::Resp2),
+      b"*3\r\n:1\r\n*2\r\n$1\r\nx\r\n$-1\r\n*0\r\n"
+    );
+  }
+
+  #[test]
+  fn should_encode_resp3_types_natively() {
+    let protocol = Protocol::Resp3;
+    let map = Reply::Map(vec![(
+      Reply::BulkString(Bytes::from("k")),
+      Reply::Set(vec![Reply::Integer(1)]),
+    )]);
+    assert_eq!(map.encode(protocol), b"%1\r\n$1\r\nk\r\n~1\r\n:1\r\n");
+    assert_eq!(Reply::NullBulkString.encode(protocol), b"_\r\n");
+    assert_eq!(Reply::NullArray.encode(protocol), b"_\r\n");
+    assert_eq!(Reply::Double(1.5).encode(protocol), b",1.5\r\n");
+    assert_eq!(
+      Reply::Double(f64::NEG_INFINITY).encode(protocol),
+      b",-inf\r\n"
+    );
+    assert_eq!(Reply::Boolean(true).encode(protocol), b"#t\r\n");
+    assert_eq!(
+      Reply::BigNumber("-12".to_owned()).encode(protocol),
+      b"(-12\r\n"
+    );
+    let verbatim = Reply::VerbatimString {
+      format: "txt".to_owned(),
+      text: Bytes::from("hi"),
+    };
+    assert_eq!(verbatim.encode(protocol), b"=6\r\ntxt:hi\r\n");
+    let attribute = Reply::Attribute {
+      attributes: vec![(Reply::SimpleString("a".to_owned()), Reply::Integer(1))],
+      reply: Box::new(Reply::Push(vec![Reply::ok()])),
+    };
+    assert_eq!(
+      attribute.encode(protocol),
+      b"|1\r\n+a\r\n:1\r\n>1\r\n+OK\r\n"
+    );
+  }
+
+  #[test]
+  fn should_downgrade_resp3_types_for_resp2() {
+    let protocol = Protocol::Resp2;
+    let map = Reply::Map(vec![(
+      Reply::BulkString(Bytes::from("k")),
+      Reply::Set(vec![Reply::Integer(1)]),
+    )]);
+    assert_eq!(map.encode(protocol), b"*2\r\n$1\r\nk\r\n*1\r\n:1\r\n");
+    assert_eq!(Reply::Null.encode(protocol), b"$-1\r\n");
+    assert_eq!(Reply::Double(1.5).encode(protocol), b"$3\r\n1.5\r\n");
+    assert_eq!(Reply::Boolean(false).encode(protocol), b":0\r\n");
+    let attribute = Reply::Attribute {
+      attributes: vec![(Reply::SimpleString("a".to_owned()), Reply::Integer(1))],
+      reply: Box::new(Reply::ok()),
+    };
+    assert_eq!(attribute.encode(protocol), b"+OK\r\n");
+  }
+}
@@ -1,26 +1,68 @@
+use std::cell::RefCell;
 use std::sync::{Arc, Mutex};
 
 use crate::database::Database;
 use crate::resp_server::command::Execute;
 
-use super::{interpreter, parser, tokenizer, ExecutionContext};
+use super::{interpreter, parser, tokenizer};
 use super::{Context, Result};
+use super::{ExecutionContext, RedisError, Reply, RespValue, Session};
 
-pub fn generate_response(request: &[u8], db: &Arc<Mutex<Database>>) -> Result<Vec<u8>> {
-  let str = std::str::from_utf8(request)
-    .context("failed to convert raw binary request to utf-8 string slice")?;
+/// Executes every command in `request` in order and returns one reply per command.
+/// A command that fails still yields its error reply, so the replies stay aligned with the
+/// pipelined requests; only a request that cannot be parsed at all fails as a whole.
+pub fn generate_response(
+  request: &[u8],
+  db: &Arc<Mutex<Database>>,
+  session: &RefCell<Session>,
+) -> Result<Vec<Vec<u8>>> {
+  let tokens = tokenizer::tokenize(request).context("tokenization failed")?;
+  let intermediate_representations = parser::parse(&tokens).context("parsing failed")?;
 
-  let tokens = tokenizer::tokenize(str).context("tokenization failed")?;
-  let intermediate_representation = parser::parse(&tokens).context("parsing failed")?;
-  let command =
-    interpreter::interpret(&intermediate_representation).context("interpretation failed")?;
+  Ok(execute_all(intermediate_representations, db, session))
+}
+
+/// Executes commands in order and returns their replies. Execution stops after a command that
+/// blocks the client: the commands after it wait in `Session::blocked` until it got its reply.
+pub fn execute_all(
+  intermediate_representations: Vec<RespValue>,
+  db: &Arc<Mutex<Database>>,
+  session: &RefCell<Session>,
+) -> Vec<Vec<u8>> {
+  let context = ExecutionContext { db, session };
+  let mut responses = vec![];
+  let mut irs = intermediate_representations.into_iter();
+  while let Some(ir) = irs.next() {
+    let reply = execute(&ir, &context).unwrap_or_else(|err| error_reply(&err));
+    // the command may have pushed to keys other clients are blocked on
+    db.lock().unwrap().serve_blocked_clients();
+
+    if let Some(blocked) = session.borrow_mut().blocked.as_mut() {
+      blocked.pending = irs.collect();
+      break;
+    }
+    // the protocol is read after execution, since HELLO replies in the protocol it switches to
+    responses.push(reply.encode(session.borrow().protocol));
+  }
+  responses
+}
+
+fn execute(ir: &RespValue, context: &ExecutionContext) -> Result<Reply> {
+  let command = interpreter::interpret(ir).context("interpretation failed")?;
+  command
+    .execute(context)
+    .context("failed to execute command")
+}
 
-  let context = ExecutionContext { db };
-  let response = command
-    .execute(&context)
-    .context("failed to execute command")?;
+/// Turns a failure into a single-line error reply. The code and message come from the
+/// `RedisError` in the chain when there is one; the full chain only goes to the server log.
+pub fn error_reply(err: &anyhow::Error) -> Reply {
+  eprintln!("{:?}", err);
 
-  Ok(response)
+  match err.downcast_ref::<RedisError>() {
+    Some(redis_error) => Reply::Error(redis_error.to_string()),
+    None => Reply::Error(format!("ERR {}", err.root_cause())),
+  }
 }
 
 #[cfg(test)]
@@ -28,23 +70,52 @@ mod tests_response_generation {
   use super::*;
   use crate::database::*;
   use crate::resp_server::*;
+  use bytes::Bytes;
 
   fn mock_db() -> Arc<Mutex<Database>> {
     Arc::new(Mutex::new(Database::new()))
   }
 
+  fn mock_db_with_clock(clock: &Arc<ManualClock>) -> Arc<Mutex<Database>> {
+    Arc::new(Mutex::new(Database::with_clock(clock.clone())))
+  }
+
+  fn mock_session() -> RefCell<Session> {
+    RefCell::new(Session::new())
+  }
+
+  fn run(
+    client_request: &str,
+    db: &Arc<Mutex<Database>>,
+    session: &RefCell<Session>,
+  ) -> Vec<Vec<u8>> {
+    generate_response(client_request.as_bytes(), db, session).unwrap()
+  }
+
+  fn run_str(
+    client_request: &str,
+    db: &Arc<Mutex<Database>>,
+    session: &RefCell<Session>,
+  ) -> Vec<String> {
+    run(client_request, db, session)
+      .into_iter()
+      .map(|response| String::from_utf8(response).unwrap())
+      .collect()
+  }
+
   #[test]
   fn should_work_with_case_insensitivity() {
     let db = mock_db();
+    let session = mock_session();
 
     let client_query = "*2\r\n$4\r\nECHO\r\n$3\r\nhey\r\n".to_owned();
-    let response = generate_response(client_query.as_bytes(), &Arc::clone(&db)).unwrap();
-    let expected_response = b"+hey\r\n".to_vec();
+    let response = generate_response(client_query.as_bytes(), &Arc::clone(&db), &session).unwrap();
+    let expected_response = vec![b"$3\r\nhey\r\n".to_vec()];
     assert_eq!(response, expected_response);
 
     let client_query = "*2\r\n$4\r\necho\r\n$3\r\nhey\r\n".to_owned();
-    let response = generate_response(client_query.as_bytes(), &Arc::clone(&db)).unwrap();
-    let expected_response = b"+hey\r\n".to_vec();
+    let response = generate_response(client_query.as_bytes(), &Arc::clone(&db), &session).unwrap();
+    let expected_response = vec![b"$3\r\nhey\r\n".to_vec()];
     assert_eq!(response, expected_response);
   }
 
@@ -54,16 +125,23 @@ mod tests_response_generation {
     #[test]
     fn should_insert_key_value_into_db() {
       let db = mock_db();
+      let session = mock_session();
 
       let key = "mykey";
       let value = "myvalue";
       let client_request = format!("*3\r\n$3\r\nSET\r\n$5\r\n{}\r\n$7\r\n{}\r\n", key, value);
-      let respone = generate_response(client_request.as_bytes(), &db).unwrap();
-      let expected_response = b"+OK\r\n".to_vec();
+      let respone = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      let expected_response = vec![b"+OK\r\n".to_vec()];
       assert_eq!(respone, expected_response);
 
-      assert_eq!(value, db.lock().unwrap().get(key).unwrap().value);
-      assert_eq!(None, db.lock().unwrap().get(key).unwrap().expire_time);
+      assert_eq!(
+        Value::from(Bytes::from(value)),
+        db.lock().unwrap().get(key.as_bytes()).unwrap().value
+      );
+      assert_eq!(
+        None,
+        db.lock().unwrap().get(key.as_bytes()).unwrap().expire_time
+      );
     }
 
     #[test]
@@ -72,12 +150,13 @@ mod tests_response_generation {
       let key = "mykey";
       let value = "myvalue";
       let data = Data {
-        value: value.to_owned(),
+        value: Value::from(Bytes::from(value)),
         expire_time: None,
       };
 
       let db = mock_db();
-      db.lock().unwrap().set(key, &data);
+      let session = mock_session();
+      db.lock().unwrap().set(key.as_bytes(), &data);
 
       let new_value = "newvalue";
 
@@ -90,12 +169,40 @@ mod tests_response_generation {
         new_value.len(),
         new_value
       );
-      let respone = generate_response(client_request.as_bytes(), &db).unwrap();
-      let expected_response = b"+OK\r\n".to_vec();
+      let respone = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      let expected_response = vec![b"+OK\r\n".to_vec()];
       assert_eq!(respone, expected_response);
 
-      assert_eq!(new_value, db.lock().unwrap().get(key).unwrap().value);
-      assert_eq!(None, db.lock().unwrap().get(key).unwrap().expire_time);
+      assert_eq!(
+        Value::from(Bytes::from(new_value)),
+        db.lock().unwrap().get(key.as_bytes()).unwrap().value
+      );
+      assert_eq!(
+        None,
+        db.lock().unwrap().get(key.as_bytes()).unwrap().expire_time
+      );
+    }
+
+    #[test]
+    fn should_reply_with_old_value_or_nil_for_get_and_nx_options() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let client_request = "*4\r\n$3\r\nSET\r\n$1\r\nk\r\n$2\r\nv1\r\n$3\r\nGET\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      assert_eq!(response, vec![b"$-1\r\n".to_vec()]);
+
+      let client_request = "*4\r\n$3\r\nSET\r\n$1\r\nk\r\n$2\r\nv2\r\n$3\r\nGET\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      assert_eq!(response, vec![b"$2\r\nv1\r\n".to_vec()]);
+
+      let client_request = "*4\r\n$3\r\nSET\r\n$1\r\nk\r\n$2\r\nv3\r\n$2\r\nNX\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      assert_eq!(response, vec![b"$-1\r\n".to_vec()]);
+      assert_eq!(
+        db.lock().unwrap().get(b"k").unwrap().value,
+        Value::from(Bytes::from("v2"))
+      );
     }
   }
 
@@ -108,12 +215,13 @@ mod tests_response_generation {
       let key = "mykey";
       let value = "myvalue";
       let data = Data {
-        value: value.to_owned(),
+        value: Value::from(Bytes::from(value)),
         expire_time: None,
       };
 
       let db = mock_db();
-      db.lock().unwrap().set(key, &data);
+      let session = mock_session();
+      db.lock().unwrap().set(key.as_bytes(), &data);
 
       let client_request = format!(
         "*2\r\n${}\r\n{}\r\n${}\r\n{}\r\n",
@@ -122,13 +230,8 @@ mod tests_response_generation {
         key.len(),
         key
       );
-      let respone = generate_response(client_request.as_bytes(), &db).unwrap();
-      let expected_response = format!("+{}\r\n", data.value).as_bytes().to_vec();
-      println!(
-        "response: {:?} / expected_response: {:?}",
-        String::from_utf8(respone.clone()).unwrap(),
-        String::from_utf8(expected_response.clone()).unwrap()
-      );
+      let respone = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      let expected_response = vec![Reply::BulkString(Bytes::from(value)).encode(Protocol::Resp2)];
       assert_eq!(respone, expected_response);
     }
 
@@ -138,6 +241,7 @@ mod tests_response_generation {
       let key = "randomkey";
 
       let db = mock_db();
+      let session = mock_session();
 
       let client_request = format!(
         "*2\r\n${}\r\n{}\r\n${}\r\n{}\r\n",
@@ -146,9 +250,2403 @@ mod tests_response_generation {
         key.len(),
         key
       );
-      let respone = generate_response(client_request.as_bytes(), &db).unwrap();
-      let expected_response = format!("{}\r\n", NULL_BULK_STRING).as_bytes().to_vec();
+      let respone = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      let expected_response = vec![format!("{}\r\n", NULL_BULK_STRING).as_bytes().to_vec()];
       assert_eq!(respone, expected_response);
     }
   }
+
+  mod test_string_arithmetic {
+    use super::*;
+    use crate::database::StringValue;
+
+    #[test]
+    fn should_increment_and_decrement_integers() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "SET n 10 EX 100\r\nINCR n\r\nDECRBY n 20\r\nINCRBY n -1\r\nDECR missing\r\nTTL n\r\nGET n\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "+OK\r\n",
+        ":11\r\n",
+        ":-9\r\n",
+        ":-10\r\n",
+        ":-1\r\n",
+        ":100\r\n",
+        "$3\r\n-10\r\n",
+      ];
+      assert_eq!(response, expected_response);
+      assert_eq!(
+        db.lock().unwrap().get(b"n").unwrap().value,
+        Value::String(StringValue::Int(-10))
+      );
+
+      let response = run_str(
+        "SET s 042\r\nINCR s\r\nSET big 9223372036854775807\r\nINCR big\r\nDECRBY n -9223372036854775808\r\nINCRBY n x\r\nLPUSH l a\r\nINCR l\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "+OK\r\n",
+        "-ERR value is not an integer or out of range\r\n",
+        "+OK\r\n",
+        "-ERR increment or decrement would overflow\r\n",
+        "-ERR decrement would overflow\r\n",
+        "-ERR value is not an integer or out of range\r\n",
+        ":1\r\n",
+        "-WRONGTYPE Operation against a key holding the wrong kind of value\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_increment_floats() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "INCRBYFLOAT f 10.5\r\nINCRBYFLOAT f 0.1\r\nSET n 5\r\nINCRBYFLOAT n -2\r\nINCRBYFLOAT f inf\r\nINCRBYFLOAT f x\r\nSET s abc\r\nINCRBYFLOAT s 1\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "$4\r\n10.5\r\n",
+        "$4\r\n10.6\r\n",
+        "+OK\r\n",
+        "$1\r\n3\r\n",
+        "-ERR increment would produce NaN or Infinity\r\n",
+        "-ERR value is not a valid float\r\n",
+        "+OK\r\n",
+        "-ERR value is not a valid float\r\n",
+      ];
+      assert_eq!(response, expected_response);
+      assert_eq!(
+        db.lock().unwrap().get(b"n").unwrap().value,
+        Value::String(StringValue::Int(3))
+      );
+    }
+  }
+
+  mod test_string_manipulation {
+    use super::*;
+
+    #[test]
+    fn should_append_and_overwrite_ranges() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "APPEND s Hello\r\nAPPEND s \" World\"\r\nSTRLEN s\r\nSTRLEN missing\r\nSET n 12\r\nAPPEND n 3\r\nINCR n\r\nSETRANGE s 6 Redis\r\nGET s\r\nSETRANGE pad 3 x\r\nGET pad\r\nSETRANGE missing 5 \"\"\r\nEXISTS missing\r\nSETRANGE s -1 x\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        ":5\r\n",
+        ":11\r\n",
+        ":11\r\n",
+        ":0\r\n",
+        "+OK\r\n",
+        ":3\r\n",
+        ":124\r\n",
+        ":11\r\n",
+        "$11\r\nHello Redis\r\n",
+        ":4\r\n",
+        "$4\r\n\0\0\0x\r\n",
+        ":0\r\n",
+        ":0\r\n",
+        "-ERR offset is out of range\r\n",
+      ];
+      assert_eq!(response, expected_response);
+
+      let response = run_str(
+        "GETRANGE s 0 4\r\nGETRANGE s -5 -1\r\nGETRANGE s 6 100\r\nGETRANGE s 5 2\r\nGETRANGE s -100 -50\r\nGETRANGE n 1 -1\r\nGETRANGE missing 0 -1\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "$5\r\nHello\r\n",
+        "$5\r\nRedis\r\n",
+        "$5\r\nRedis\r\n",
+        "$0\r\n\r\n",
+        "$0\r\n\r\n",
+        "$2\r\n24\r\n",
+        "$0\r\n\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_find_longest_common_subsequence() {
+      let db = mock_db();
+      let session = mock_session();
+
+      run_str(
+        "SET a ohmytext\r\nSET b mynewtext\r\nLPUSH l x\r\n",
+        &db,
+        &session,
+      );
+      let response = run_str(
+        "LCS a b\r\nLCS a b LEN\r\nLCS a b IDX\r\nLCS a b IDX MINMATCHLEN 4 WITHMATCHLEN\r\nLCS a missing\r\nLCS a b LEN IDX\r\nLCS a l\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "$6\r\nmytext\r\n",
+        ":6\r\n",
+        "*4\r\n$7\r\nmatches\r\n*2\r\n*2\r\n*2\r\n:4\r\n:7\r\n*2\r\n:5\r\n:8\r\n*2\r\n*2\r\n:2\r\n:3\r\n*2\r\n:0\r\n:1\r\n$3\r\nlen\r\n:6\r\n",
+        "*4\r\n$7\r\nmatches\r\n*1\r\n*3\r\n*2\r\n:4\r\n:7\r\n*2\r\n:5\r\n:8\r\n:4\r\n$3\r\nlen\r\n:6\r\n",
+        "$0\r\n\r\n",
+        "-ERR If you want both the length and indexes, please just use IDX.\r\n",
+        "-ERR The specified keys must contain string values\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+  }
+
+  mod test_string_variants {
+    use super::*;
+
+    #[test]
+    fn should_set_and_get_several_keys() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "MSET a 1 b 2\r\nLPUSH l x\r\nMGET a b missing l\r\nMSETNX b 3 c 4\r\nEXISTS c\r\nMSETNX c 3 d 4\r\nMGET c d\r\nMSET a 1 b\r\nMSETNX a 1 b\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "+OK\r\n",
+        ":1\r\n",
+        "*4\r\n$1\r\n1\r\n$1\r\n2\r\n$-1\r\n$-1\r\n",
+        ":0\r\n",
+        ":0\r\n",
+        ":1\r\n",
+        "*2\r\n$1\r\n3\r\n$1\r\n4\r\n",
+        "-ERR wrong number of arguments for 'mset' command\r\n",
+        "-ERR wrong number of arguments for 'msetnx' command\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_set_with_expire_time_only_when_missing() {
+      let clock = Arc::new(ManualClock::new(1_000_000));
+      let db = mock_db_with_clock(&clock);
+      let session = mock_session();
+
+      let response = run_str(
+        "SETEX s 10 v\r\nTTL s\r\nPSETEX p 1500 v\r\nPTTL p\r\nSETEX s 0 v\r\nPSETEX p -1 v\r\nSETEX s x v\r\nSETNX s x\r\nSETNX n x\r\nGETSET s new\r\nTTL s\r\nGETSET missing v\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "+OK\r\n",
+        ":10\r\n",
+        "+OK\r\n",
+        ":1500\r\n",
+        "-ERR invalid expire time in 'setex' command\r\n",
+        "-ERR invalid expire time in 'psetex' command\r\n",
+        "-ERR value is not an integer or out of range\r\n",
+        ":0\r\n",
+        ":1\r\n",
+        "$1\r\nv\r\n",
+        ":-1\r\n",
+        "$-1\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_get_strings_and_change_their_expire_time() {
+      let clock = Arc::new(ManualClock::new(1_000_000));
+      let db = mock_db_with_clock(&clock);
+      let session = mock_session();
+
+      run_str("PSETEX p 1500 v\r\nSET n x\r\nLPUSH l a\r\n", &db, &session);
+      let response = run_str(
+        "GETEX p PERSIST\r\nPTTL p\r\nGETEX p EX 100\r\nTTL p\r\nGETEX p\r\nTTL p\r\nGETEX p PXAT 1\r\nEXISTS p\r\nGETEX missing EX 1\r\nGETEX n EX 0\r\nGETEX n PERSIST EX 1\r\nGETEX n NX\r\nGETDEL n\r\nGETDEL n\r\nGETDEL l\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "$1\r\nv\r\n",
+        ":-1\r\n",
+        "$1\r\nv\r\n",
+        ":100\r\n",
+        "$1\r\nv\r\n",
+        ":100\r\n",
+        "$1\r\nv\r\n",
+        ":0\r\n",
+        "$-1\r\n",
+        "-ERR invalid expire time in 'getex' command\r\n",
+        "-ERR syntax error\r\n",
+        "-ERR syntax error\r\n",
+        "$1\r\nx\r\n",
+        "$-1\r\n",
+        "-WRONGTYPE Operation against a key holding the wrong kind of value\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_reject_set_expire_times_past_the_limit() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "SET k v EX 9223372036854775807\r\nSET k v EXAT 9223372036854776\r\nSET k v PXAT 9223372036854775807\r\nEXISTS k\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "-ERR invalid expire time in 'set' command\r\n",
+        "-ERR invalid expire time in 'set' command\r\n",
+        "+OK\r\n",
+        ":1\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_reject_setex_expire_times_past_the_limit() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "SETEX k 9223372036854775807 v\r\nPSETEX k 9223372036854775807 v\r\nEXISTS k\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "-ERR invalid expire time in 'setex' command\r\n",
+        "-ERR invalid expire time in 'psetex' command\r\n",
+        ":0\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_reject_getex_expire_times_past_the_limit() {
+      let db = mock_db();
+      let session = mock_session();
+
+      run_str("SET k v\r\n", &db, &session);
+      let response = run_str(
+        "GETEX k EX 9223372036854775807\r\nGETEX k EXAT 9223372036854776\r\nTTL k\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "-ERR invalid expire time in 'getex' command\r\n",
+        "-ERR invalid expire time in 'getex' command\r\n",
+        ":-1\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+  }
+
+  mod test_pipelining {
+    use super::*;
+
+    #[test]
+    fn should_reply_to_every_command_in_order() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let client_request =
+        "*3\r\n$3\r\nSET\r\n$1\r\na\r\n$1\r\n1\r\n*2\r\n$3\r\nGET\r\n$1\r\na\r\n*1\r\n$4\r\nPING\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      let expected_response = vec![
+        b"+OK\r\n".to_vec(),
+        b"$1\r\n1\r\n".to_vec(),
+        b"+PONG\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_keep_replying_after_a_failed_command() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let client_request = "*1\r\n$7\r\nUNKNOWN\r\n*1\r\n$4\r\nPING\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      assert_eq!(response.len(), 2);
+      assert!(response[0].starts_with(b"-ERR"));
+      assert_eq!(response[1], b"+PONG\r\n".to_vec());
+    }
+  }
+
+  mod test_binary_safety {
+    use super::*;
+
+    #[test]
+    fn should_store_values_containing_crlf_and_non_utf8_bytes() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let value = b"\xff\x00\r\n\xfe";
+      let mut client_request = b"*3\r\n$3\r\nSET\r\n$3\r\nbin\r\n$5\r\n".to_vec();
+      client_request.extend_from_slice(value);
+      client_request.extend_from_slice(b"\r\n");
+
+      let response = generate_response(&client_request, &db, &session).unwrap();
+      assert_eq!(response, vec![b"+OK\r\n".to_vec()]);
+      assert_eq!(
+        db.lock().unwrap().get(b"bin").unwrap().value,
+        Value::from(Bytes::copy_from_slice(value))
+      );
+    }
+  }
+
+  mod test_error_reply {
+    use super::*;
+
+    #[test]
+    fn should_reply_with_single_line_redis_errors() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let client_request = "GET\r\nFOO a b\r\nSET k v PX abc\r\nSET k v EX 0\r\nSET k v NX XX\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      let expected_response = vec![
+        b"-ERR wrong number of arguments for 'get' command\r\n".to_vec(),
+        b"-ERR unknown command 'FOO', with args beginning with: 'a' 'b' \r\n".to_vec(),
+        b"-ERR value is not an integer or out of range\r\n".to_vec(),
+        b"-ERR invalid expire time in 'set' command\r\n".to_vec(),
+        b"-ERR syntax error\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_use_root_cause_for_errors_without_redis_code() {
+      let err = anyhow::anyhow!("root cause").context("outer context");
+      assert_eq!(error_reply(&err), Reply::Error("ERR root cause".to_owned()));
+
+      let err = anyhow::Error::new(RedisError::WrongType).context("outer context");
+      assert_eq!(
+        error_reply(&err).encode(Protocol::Resp2),
+        b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n".to_vec()
+      );
+    }
+  }
+
+  mod test_inline_command {
+    use super::*;
+
+    #[test]
+    fn should_interpret_inline_commands_like_multibulk_ones() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let client_request = "PING\r\nSET greeting \"hello world\"\nGET greeting\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      let expected_response = vec![
+        b"+PONG\r\n".to_vec(),
+        b"+OK\r\n".to_vec(),
+        b"$11\r\nhello world\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+  }
+
+  mod test_hello {
+    use super::*;
+
+    #[test]
+    fn should_switch_connection_to_resp3() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let client_request = "*2\r\n$5\r\nHELLO\r\n$1\r\n3\r\n*2\r\n$3\r\nGET\r\n$1\r\nk\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      assert!(response[0].starts_with(b"%7\r\n$6\r\nserver\r\n$5\r\nredis\r\n"));
+      assert_eq!(response[1], b"_\r\n".to_vec());
+      assert_eq!(session.borrow().protocol, Protocol::Resp3);
+    }
+
+    #[test]
+    fn should_reply_in_resp2_without_protocol_version() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let client_request = "*1\r\n$5\r\nHELLO\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      assert!(response[0].starts_with(b"*14\r\n"));
+      assert_eq!(session.borrow().protocol, Protocol::Resp2);
+    }
+
+    #[test]
+    fn should_set_client_name_and_reject_unsupported_protocol() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let client_request = "*4\r\n$5\r\nHELLO\r\n$1\r\n2\r\n$7\r\nSETNAME\r\n$6\r\nworker\r\n";
+      generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      assert_eq!(session.borrow().name, Some(Bytes::from("worker")));
+
+      let client_request = "*2\r\n$5\r\nHELLO\r\n$1\r\n4\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      assert!(response[0].starts_with(b"-NOPROTO"));
+    }
+  }
+
+  mod test_command_introspection {
+    use super::*;
+
+    #[test]
+    fn should_serve_command_family_from_command_table() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let client_request = "COMMAND INFO get nosuchcommand\r\nCOMMAND GETKEYS SET k v EX 10\r\nCOMMAND GETKEYS PING\r\nCOMMAND DOCS echo\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      let expected_response = vec![
+        b"*2\r\n*10\r\n$3\r\nget\r\n:2\r\n*2\r\n+readonly\r\n+fast\r\n:1\r\n:1\r\n:1\r\n*3\r\n+@read\r\n+@string\r\n+@fast\r\n*0\r\n*0\r\n*0\r\n*-1\r\n".to_vec(),
+        b"*1\r\n$1\r\nk\r\n".to_vec(),
+        b"-ERR The command has no key arguments\r\n".to_vec(),
+        b"*2\r\n$4\r\necho\r\n*6\r\n$7\r\nsummary\r\n$25\r\nReturns the given string.\r\n$5\r\nsince\r\n$5\r\n1.0.0\r\n$5\r\ngroup\r\n$10\r\nconnection\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_count_every_command_in_table() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let client_request = "COMMAND COUNT\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      let count = crate::resp_server::command_table::COMMAND_TABLE.len();
+      assert_eq!(response, vec![format!(":{}\r\n", count).into_bytes()]);
+    }
+  }
+
+  mod test_keyspace {
+    use super::*;
+
+    #[test]
+    fn should_count_deleted_and_existing_keys() {
+      let db = mock_db();
+
+      let response = run("SET a 1\r\nSET b 2\r\nEXISTS a a b c\r\nTOUCH a c\r\nDEL a c\r\nUNLINK b\r\nEXISTS a b\r\n", &db, &mock_session());
+      let expected_response: Vec<Vec<u8>> = vec![
+        b"+OK\r\n".to_vec(),
+        b"+OK\r\n".to_vec(),
+        b":3\r\n".to_vec(),
+        b":1\r\n".to_vec(),
+        b":1\r\n".to_vec(),
+        b":1\r\n".to_vec(),
+        b":0\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_unlink_large_values() {
+      let db = mock_db();
+      let data = Data {
+        value: Value::from(Bytes::from(vec![b'x'; 1024 * 1024])),
+        expire_time: None,
+      };
+      db.lock().unwrap().set(b"big", &data);
+
+      assert_eq!(
+        run("UNLINK big\r\n", &db, &mock_session()),
+        vec![b":1\r\n".to_vec()]
+      );
+      assert!(!db.lock().unwrap().contains_key(b"big"));
+    }
+
+    #[test]
+    fn should_report_type_of_key() {
+      let db = mock_db();
+
+      let response = run(
+        "SET a 1\r\nTYPE a\r\nTYPE missing\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response: Vec<Vec<u8>> = vec![
+        b"+OK\r\n".to_vec(),
+        b"+string\r\n".to_vec(),
+        b"+none\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_rename_keys_with_their_expire_time() {
+      let db = mock_db();
+
+      let response = run(
+        "SET a 1 PX 100000\r\nRENAME a b\r\nRENAME a b\r\nSET c 3\r\nRENAMENX b c\r\nRENAMENX b d\r\nRENAME d d\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response: Vec<Vec<u8>> = vec![
+        b"+OK\r\n".to_vec(),
+        b"+OK\r\n".to_vec(),
+        b"-ERR no such key\r\n".to_vec(),
+        b"+OK\r\n".to_vec(),
+        b":0\r\n".to_vec(),
+        b":1\r\n".to_vec(),
+        b"+OK\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+
+      let mut db = db.lock().unwrap();
+      assert!(!db.contains_key(b"b"));
+      assert_eq!(db.get(b"d").unwrap().value, Value::from(Bytes::from("1")));
+      assert!(db.get(b"d").unwrap().expire_time.is_some());
+    }
+
+    #[test]
+    fn should_copy_only_with_replace_when_destination_exists() {
+      let db = mock_db();
+
+      let response = run(
+        "SET a 1\r\nSET b 2\r\nCOPY a b\r\nCOPY a b DB 0 REPLACE\r\nCOPY missing c\r\nCOPY a a\r\nCOPY a c DB 1\r\nCOPY a c FOO\r\nGET b\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response: Vec<Vec<u8>> = vec![
+        b"+OK\r\n".to_vec(),
+        b"+OK\r\n".to_vec(),
+        b":0\r\n".to_vec(),
+        b":1\r\n".to_vec(),
+        b":0\r\n".to_vec(),
+        b"-ERR source and destination objects are the same\r\n".to_vec(),
+        b"-ERR DB index is out of range\r\n".to_vec(),
+        b"-ERR syntax error\r\n".to_vec(),
+        b"$1\r\n1\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+  }
+
+  mod test_expire {
+    use super::*;
+
+    #[test]
+    fn should_reply_sentinels_for_missing_key_and_key_without_expire() {
+      let db = mock_db();
+
+      let response = run(
+        "SET a 1\r\nTTL a\r\nPTTL a\r\nEXPIRETIME a\r\nTTL missing\r\nPEXPIRETIME missing\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response: Vec<Vec<u8>> = vec![
+        b"+OK\r\n".to_vec(),
+        b":-1\r\n".to_vec(),
+        b":-1\r\n".to_vec(),
+        b":-1\r\n".to_vec(),
+        b":-2\r\n".to_vec(),
+        b":-2\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_set_read_and_remove_expire_time() {
+      let db = mock_db();
+
+      let response = run(
+        "SET a 1\r\nEXPIRE a 100\r\nTTL a\r\nPEXPIREAT a 33177117420000\r\nEXPIRETIME a\r\nPEXPIRETIME a\r\nPERSIST a\r\nPERSIST a\r\nTTL a\r\nEXPIRE missing 100\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response: Vec<Vec<u8>> = vec![
+        b"+OK\r\n".to_vec(),
+        b":1\r\n".to_vec(),
+        b":100\r\n".to_vec(),
+        b":1\r\n".to_vec(),
+        b":33177117420\r\n".to_vec(),
+        b":33177117420000\r\n".to_vec(),
+        b":1\r\n".to_vec(),
+        b":0\r\n".to_vec(),
+        b":-1\r\n".to_vec(),
+        b":0\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_count_down_with_the_clock() {
+      let clock = Arc::new(ManualClock::new(1_000_000));
+      let db = mock_db_with_clock(&clock);
+
+      let response = run(
+        "SET a 1 PX 100000\r\nPTTL a\r\nEXPIRE a 10\r\n",
+        &db,
+        &mock_session(),
+      );
+      assert_eq!(response[1], b":100000\r\n".to_vec());
+
+      clock.advance(std::time::Duration::from_millis(2_400));
+      let response = run("TTL a\r\nPTTL a\r\nPEXPIRETIME a\r\n", &db, &mock_session());
+      let expected_response: Vec<Vec<u8>> = vec![
+        b":8\r\n".to_vec(),
+        b":7600\r\n".to_vec(),
+        b":1010000\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+
+      clock.advance(std::time::Duration::from_millis(7_601));
+      assert_eq!(
+        run("GET a\r\nTTL a\r\n", &db, &mock_session()),
+        vec![b"$-1\r\n".to_vec(), b":-2\r\n".to_vec()]
+      );
+    }
+
+    #[test]
+    fn should_delete_key_when_expire_time_is_in_the_past() {
+      let db = mock_db();
+
+      let response = run(
+        "SET a 1\r\nSET b 2\r\nEXPIRE a -1\r\nEXPIREAT b 1\r\nEXISTS a b\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response: Vec<Vec<u8>> = vec![
+        b"+OK\r\n".to_vec(),
+        b"+OK\r\n".to_vec(),
+        b":1\r\n".to_vec(),
+        b":1\r\n".to_vec(),
+        b":0\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_apply_conditions() {
+      let db = mock_db();
+
+      let response = run(
+        "SET a 1\r\nEXPIRE a 100 XX\r\nEXPIRE a 100 GT\r\nEXPIRE a 100 LT\r\nEXPIRE a 200 NX\r\nEXPIRE a 50 GT\r\nEXPIRE a 200 XX GT\r\nEXPIRE a 300 LT\r\nTTL a\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response: Vec<Vec<u8>> = vec![
+        b"+OK\r\n".to_vec(),
+        b":0\r\n".to_vec(),
+        b":0\r\n".to_vec(),
+        b":1\r\n".to_vec(),
+        b":0\r\n".to_vec(),
+        b":0\r\n".to_vec(),
+        b":1\r\n".to_vec(),
+        b":0\r\n".to_vec(),
+        b":200\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_reject_invalid_arguments() {
+      let db = mock_db();
+
+      let response = run(
+        "EXPIRE a 10 NX XX\r\nEXPIRE a 10 GT LT\r\nEXPIRE a 10 FOO\r\nEXPIRE a ten\r\nSET a 1\r\nEXPIRE a 9223372036854775807\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response: Vec<Vec<u8>> = vec![
+        b"-ERR NX and XX, GT or LT options at the same time are not compatible\r\n".to_vec(),
+        b"-ERR GT and LT options at the same time are not compatible\r\n".to_vec(),
+        b"-ERR Unsupported option FOO\r\n".to_vec(),
+        b"-ERR value is not an integer or out of range\r\n".to_vec(),
+        b"+OK\r\n".to_vec(),
+        b"-ERR invalid expire time in 'expire' command\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+  }
+
+  mod test_expiration {
+    use super::*;
+
+    fn expired_data(value: &str) -> Data {
+      Data {
+        value: Value::from(Bytes::from(value.to_owned())),
+        expire_time: Some(1),
+      }
+    }
+
+    #[test]
+    fn should_delete_expired_key_on_access() {
+      let db = mock_db();
+      let session = mock_session();
+      db.lock().unwrap().set(b"a", &expired_data("1"));
+      db.lock().unwrap().set(b"b", &expired_data("2"));
+
+      let client_request = "GET a\r\nSET b new GET\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      let expected_response = vec![b"$-1\r\n".to_vec(), b"$-1\r\n".to_vec()];
+      assert_eq!(response, expected_response);
+
+      let db = db.lock().unwrap();
+      assert_eq!(db.len(), 1);
+      assert_eq!(db.expires_len(), 0);
+      assert_eq!(db.stats().expired_keys, 2);
+    }
+
+    #[test]
+    fn should_leave_expired_keys_to_lazy_expiry_when_active_expire_is_disabled() {
+      let clock = Arc::new(ManualClock::new(1_000));
+      let db = mock_db_with_clock(&clock);
+      let session = mock_session();
+
+      let client_request = "DEBUG SET-ACTIVE-EXPIRE 0\r\nSET a 1 PX 10\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      assert_eq!(response, vec![b"+OK\r\n".to_vec(), b"+OK\r\n".to_vec()]);
+
+      clock.advance(std::time::Duration::from_millis(100));
+      active_expire_cycle(&db);
+      assert_eq!(db.lock().unwrap().len(), 1);
+
+      let client_request = "EXISTS a\r\nDEBUG SET-ACTIVE-EXPIRE x\r\nDEBUG NOPE\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      let expected_response = vec![
+        b":0\r\n".to_vec(),
+        b"-ERR value is not an integer or out of range\r\n".to_vec(),
+        b"-ERR unknown subcommand or wrong number of arguments for 'NOPE'. Try DEBUG HELP.\r\n"
+          .to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+      assert_eq!(db.lock().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn should_report_expire_stats_and_keyspace_in_info() {
+      let db = mock_db();
+      let session = mock_session();
+      db.lock().unwrap().set(b"a", &expired_data("1"));
+
+      let client_request = "SET b 1 EX 100\r\nSET c 1\r\nEXISTS a\r\nINFO keyspace STATS\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      let info = String::from_utf8(response[3].clone()).unwrap();
+      assert!(info.starts_with('$'));
+      assert!(info.contains("# Stats\r\nexpired_keys:1\r\n"));
+      assert!(info.contains("# Keyspace\r\ndb0:keys=2,expires=1,avg_ttl="));
+      assert!(!info.contains("# Server"));
+    }
+
+    #[test]
+    fn should_reply_info_as_verbatim_string_in_resp3() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let client_request = "HELLO 3\r\nINFO server\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      let info = String::from_utf8(response[1].clone()).unwrap();
+      assert!(info.starts_with('='));
+      assert!(info.contains("txt:# Server\r\nredis_version:7.4.0\r\n"));
+    }
+  }
+
+  mod test_value_types {
+    use super::*;
+    use std::collections::VecDeque;
+
+    fn data(value: Value) -> Data {
+      Data {
+        value,
+        expire_time: None,
+      }
+    }
+
+    #[test]
+    fn should_reject_string_commands_on_other_types() {
+      let db = mock_db();
+      let session = mock_session();
+      let list = VecDeque::from([Bytes::from("a")]);
+      db.lock().unwrap().set(b"list", &data(Value::List(list)));
+
+      let client_request =
+        "GET list\r\nSET list v GET\r\nSET list v NX\r\nSET list v\r\nGET list\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      let wrong_type =
+        b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n".to_vec();
+      let expected_response = vec![
+        wrong_type.clone(),
+        wrong_type,
+        b"$-1\r\n".to_vec(),
+        b"+OK\r\n".to_vec(),
+        b"$1\r\nv\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_report_type_of_each_value() {
+      let db = mock_db();
+      let session = mock_session();
+      {
+        let mut db = db.lock().unwrap();
+        db.set(b"list", &data(Value::List(VecDeque::new())));
+        db.set(b"hash", &data(Value::Hash(Hash::default())));
+        db.set(b"set", &data(Value::Set(SetValue::default())));
+        db.set(b"zset", &data(Value::ZSet(SortedSet::default())));
+      }
+
+      let client_request = "TYPE list\r\nTYPE hash\r\nTYPE set\r\nCOPY zset copy\r\nTYPE copy\r\n";
+      let response = generate_response(client_request.as_bytes(), &db, &session).unwrap();
+      let expected_response = vec![
+        b"+list\r\n".to_vec(),
+        b"+hash\r\n".to_vec(),
+        b"+set\r\n".to_vec(),
+        b":1\r\n".to_vec(),
+        b"+zset\r\n".to_vec(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+  }
+
+  mod test_list {
+    use super::*;
+
+    #[test]
+    fn should_push_and_pop_at_both_ends() {
+      let db = mock_db();
+
+      let response = run_str(
+        "RPUSH l b c\r\nLPUSH l a z\r\nLPUSHX missing a\r\nRPUSHX l d\r\nLPOP l\r\nRPOP l 2\r\nLPOP l 0\r\nLPOP missing 2\r\nLPOP missing\r\nLPOP l -1\r\nLPOP l 1 2\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response = vec![
+        ":2\r\n",
+        ":4\r\n",
+        ":0\r\n",
+        ":5\r\n",
+        "$1\r\nz\r\n",
+        "*2\r\n$1\r\nd\r\n$1\r\nc\r\n",
+        "*0\r\n",
+        "*-1\r\n",
+        "$-1\r\n",
+        "-ERR value is out of range, must be positive\r\n",
+        "-ERR wrong number of arguments for 'lpop' command\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_delete_list_when_last_element_is_removed() {
+      let db = mock_db();
+
+      let response = run_str(
+        "RPUSH l a b\r\nRPOP l 5\r\nEXISTS l\r\nLLEN l\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response = vec![":2\r\n", "*2\r\n$1\r\nb\r\n$1\r\na\r\n", ":0\r\n", ":0\r\n"];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_read_ranges_and_indexes_counting_from_either_end() {
+      let db = mock_db();
+
+      let response = run_str(
+        "RPUSH l a b c d\r\nLRANGE l 1 -2\r\nLRANGE l -100 100\r\nLRANGE l 3 1\r\nLINDEX l -1\r\nLINDEX l 4\r\nLSET l -4 A\r\nLSET l 4 x\r\nLSET missing 0 x\r\nLRANGE l 0 0\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response = vec![
+        ":4\r\n",
+        "*2\r\n$1\r\nb\r\n$1\r\nc\r\n",
+        "*4\r\n$1\r\na\r\n$1\r\nb\r\n$1\r\nc\r\n$1\r\nd\r\n",
+        "*0\r\n",
+        "$1\r\nd\r\n",
+        "$-1\r\n",
+        "+OK\r\n",
+        "-ERR index out of range\r\n",
+        "-ERR no such key\r\n",
+        "*1\r\n$1\r\nA\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_remove_insert_and_trim() {
+      let db = mock_db();
+
+      let response = run_str(
+        "RPUSH l x a x b x\r\nLREM l -2 x\r\nLRANGE l 0 -1\r\nLREM l 0 x\r\nLINSERT l BEFORE b x\r\nLINSERT l AFTER b y\r\nLINSERT l AFTER nope y\r\nLINSERT l MIDDLE b y\r\nLTRIM l 1 -2\r\nLRANGE l 0 -1\r\nLTRIM l 5 10\r\nEXISTS l\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response = vec![
+        ":5\r\n",
+        ":2\r\n",
+        "*3\r\n$1\r\nx\r\n$1\r\na\r\n$1\r\nb\r\n",
+        ":1\r\n",
+        ":3\r\n",
+        ":4\r\n",
+        ":-1\r\n",
+        "-ERR syntax error\r\n",
+        "+OK\r\n",
+        "*2\r\n$1\r\nx\r\n$1\r\nb\r\n",
+        "+OK\r\n",
+        ":0\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_move_elements_between_lists() {
+      let db = mock_db();
+
+      let response = run_str(
+        "RPUSH src a b c\r\nLMOVE src dst RIGHT LEFT\r\nRPOPLPUSH src src\r\nLRANGE src 0 -1\r\nSET str v\r\nLMOVE src str LEFT LEFT\r\nLMOVE missing dst LEFT LEFT\r\nLMOVE src dst UP LEFT\r\nLLEN src\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response = vec![
+        ":3\r\n",
+        "$1\r\nc\r\n",
+        "$1\r\nb\r\n",
+        "*2\r\n$1\r\nb\r\n$1\r\na\r\n",
+        "+OK\r\n",
+        "-WRONGTYPE Operation against a key holding the wrong kind of value\r\n",
+        "$-1\r\n",
+        "-ERR syntax error\r\n",
+        ":2\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_find_positions_of_matching_elements() {
+      let db = mock_db();
+
+      let response = run_str(
+        "RPUSH l a b c 1 2 3 c c\r\nLPOS l c\r\nLPOS l c RANK 2\r\nLPOS l c RANK -1\r\nLPOS l c COUNT 0\r\nLPOS l c COUNT 2 RANK -1\r\nLPOS l c MAXLEN 2\r\nLPOS l c RANK 0\r\nLPOS l c FOO 1\r\nLPOS missing c COUNT 1\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response = vec![
+        ":8\r\n",
+        ":2\r\n",
+        ":6\r\n",
+        ":7\r\n",
+        "*3\r\n:2\r\n:6\r\n:7\r\n",
+        "*2\r\n:7\r\n:6\r\n",
+        "$-1\r\n",
+        "-ERR RANK can't be zero: use 1 to start from the first match, 2 from the second ... or use negative to start from the end of the list\r\n",
+        "-ERR syntax error\r\n",
+        "*0\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+  }
+
+  mod test_blocking {
+    use super::*;
+
+    fn take_reply(session: &RefCell<Session>) -> Option<String> {
+      let mut blocked = session.borrow_mut().blocked.take().unwrap();
+      let reply = blocked.receiver.try_recv().ok()?;
+      Some(String::from_utf8(reply.encode(Protocol::Resp2)).unwrap())
+    }
+
+    fn pending_len(session: &RefCell<Session>) -> usize {
+      session.borrow().blocked.as_ref().unwrap().pending.len()
+    }
+
+    #[test]
+    fn should_serve_right_away_when_a_list_has_elements() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "RPUSH b x y\r\nBLPOP a b 0\r\nBRPOP a b 0.5\r\nLMPOP 2 a b LEFT COUNT 3\r\nLMPOP 1 a RIGHT\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        ":2\r\n",
+        "*2\r\n$1\r\nb\r\n$1\r\nx\r\n",
+        "*2\r\n$1\r\nb\r\n$1\r\ny\r\n",
+        "*-1\r\n",
+        "*-1\r\n",
+      ];
+      assert_eq!(response, expected_response);
+      assert!(session.borrow().blocked.is_none());
+    }
+
+    #[test]
+    fn should_wake_blocked_clients_in_fifo_order() {
+      let db = mock_db();
+      let (first, second, pusher) = (mock_session(), mock_session(), mock_session());
+
+      assert!(run_str("BLPOP l 0\r\nECHO after\r\n", &db, &first).is_empty());
+      assert!(run_str("BRPOP other l 1.5\r\n", &db, &second).is_empty());
+      assert_eq!(
+        pending_len(&first),
+        1,
+        "commands after a blocking one wait for its reply"
+      );
+
+      let response = run_str("RPUSH l a\r\nLLEN l\r\n", &db, &pusher);
+      assert_eq!(response, vec![":1\r\n", ":0\r\n"]);
+      assert_eq!(take_reply(&first).unwrap(), "*2\r\n$1\r\nl\r\n$1\r\na\r\n");
+
+      run_str("RPUSH l b c\r\n", &db, &pusher);
+      assert_eq!(take_reply(&second).unwrap(), "*2\r\n$1\r\nl\r\n$1\r\nc\r\n");
+      assert_eq!(
+        run_str("LRANGE l 0 -1\r\n", &db, &pusher),
+        vec!["*1\r\n$1\r\nb\r\n"]
+      );
+    }
+
+    #[test]
+    fn should_keep_waiting_while_a_key_holds_another_type() {
+      let db = mock_db();
+      let (list_waiter, zset_waiter, writer) = (mock_session(), mock_session(), mock_session());
+
+      assert!(run_str("BLPOP k 0\r\n", &db, &list_waiter).is_empty());
+      assert!(run_str("BZPOPMIN z 0\r\n", &db, &zset_waiter).is_empty());
+      run_str("SET k v\r\nSADD z a\r\n", &db, &writer);
+      assert!(list_waiter
+        .borrow_mut()
+        .blocked
+        .as_mut()
+        .unwrap()
+        .receiver
+        .try_recv()
+        .is_err());
+      assert!(zset_waiter
+        .borrow_mut()
+        .blocked
+        .as_mut()
+        .unwrap()
+        .receiver
+        .try_recv()
+        .is_err());
+
+      run_str("DEL k z\r\nRPUSH k a\r\nZADD z 1 m\r\n", &db, &writer);
+      assert_eq!(
+        take_reply(&list_waiter).unwrap(),
+        "*2\r\n$1\r\nk\r\n$1\r\na\r\n"
+      );
+      assert_eq!(
+        take_reply(&zset_waiter).unwrap(),
+        "*3\r\n$1\r\nz\r\n$1\r\nm\r\n$1\r\n1\r\n"
+      );
+    }
+
+    #[test]
+    fn should_not_serve_unblocked_clients() {
+      let db = mock_db();
+      let (waiter, pusher) = (mock_session(), mock_session());
+
+      run_str("BLPOP l 0.01\r\n", &db, &waiter);
+      let blocked = waiter.borrow_mut().blocked.take().unwrap();
+      assert_eq!(blocked.timeout_reply, Reply::NullArray);
+      db.lock().unwrap().unblock(blocked.id);
+
+      assert_eq!(
+        run_str("RPUSH l a\r\nLLEN l\r\n", &db, &pusher),
+        vec![":1\r\n", ":1\r\n"]
+      );
+    }
+
+    #[test]
+    fn should_wake_blmove_and_blmpop() {
+      let db = mock_db();
+      let (mover, popper, pusher) = (mock_session(), mock_session(), mock_session());
+
+      assert!(run_str("BLMOVE src dst RIGHT LEFT 0\r\n", &db, &mover).is_empty());
+      assert!(run_str("BLMPOP 0 2 x src LEFT COUNT 5\r\n", &db, &popper).is_empty());
+
+      run_str("RPUSH src a b\r\n", &db, &pusher);
+      assert_eq!(take_reply(&mover).unwrap(), "$1\r\nb\r\n");
+      assert_eq!(
+        take_reply(&popper).unwrap(),
+        "*2\r\n$3\r\nsrc\r\n*1\r\n$1\r\na\r\n"
+      );
+      assert_eq!(
+        run_str("LRANGE dst 0 -1\r\n", &db, &pusher),
+        vec!["*1\r\n$1\r\nb\r\n"]
+      );
+
+      assert_eq!(
+        run_str("BRPOPLPUSH dst src 0\r\n", &db, &mover),
+        vec!["$1\r\nb\r\n"]
+      );
+      assert_eq!(
+        run_str("LRANGE src 0 -1\r\n", &db, &pusher),
+        vec!["*1\r\n$1\r\nb\r\n"]
+      );
+    }
+
+    #[test]
+    fn should_serve_right_away_when_a_sorted_set_has_members() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "ZADD b 1 x 2 y 3 z\r\nBZPOPMIN a b 0\r\nBZPOPMAX a b 0.5\r\nZMPOP 2 a b MIN COUNT 5\r\nZMPOP 1 a MAX\r\nZMPOP 1 a SIDEWAYS\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        ":3\r\n",
+        "*3\r\n$1\r\nb\r\n$1\r\nx\r\n$1\r\n1\r\n",
+        "*3\r\n$1\r\nb\r\n$1\r\nz\r\n$1\r\n3\r\n",
+        "*2\r\n$1\r\nb\r\n*1\r\n*2\r\n$1\r\ny\r\n$1\r\n2\r\n",
+        "*-1\r\n",
+        "-ERR syntax error\r\n",
+      ];
+      assert_eq!(response, expected_response);
+      assert!(session.borrow().blocked.is_none());
+    }
+
+    #[test]
+    fn should_find_keys_of_zmpop_and_bzmpop() {
+      let response = run_str(
+        "COMMAND GETKEYS ZMPOP 2 a b MIN COUNT 5\r\nCOMMAND GETKEYS BZMPOP 0.5 1 a MAX\r\nCOMMAND GETKEYS BZMPOP 0 x a MAX\r\n",
+        &mock_db(),
+        &mock_session(),
+      );
+      let expected_response = vec![
+        "*2\r\n$1\r\na\r\n$1\r\nb\r\n",
+        "*1\r\n$1\r\na\r\n",
+        "-ERR The command has no key arguments\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_wake_sorted_set_poppers_in_fifo_order() {
+      let db = mock_db();
+      let (first, second, third, adder) = (
+        mock_session(),
+        mock_session(),
+        mock_session(),
+        mock_session(),
+      );
+
+      assert!(run_str("BZPOPMIN z 0\r\n", &db, &first).is_empty());
+      assert!(run_str("BZMPOP 0 2 other z MAX COUNT 2\r\n", &db, &second).is_empty());
+      assert!(run_str("BZPOPMAX z 0\r\n", &db, &third).is_empty());
+
+      run_str("ZADD z 1 a 2 b 3 c\r\n", &db, &adder);
+      assert_eq!(
+        take_reply(&first).unwrap(),
+        "*3\r\n$1\r\nz\r\n$1\r\na\r\n$1\r\n1\r\n"
+      );
+      assert_eq!(
+        take_reply(&second).unwrap(),
+        "*2\r\n$1\r\nz\r\n*2\r\n*2\r\n$1\r\nc\r\n$1\r\n3\r\n*2\r\n$1\r\nb\r\n$1\r\n2\r\n"
+      );
+      assert_eq!(pending_len(&third), 0, "the sorted set ran out");
+
+      run_str("ZINCRBY z 5 d\r\n", &db, &adder);
+      assert_eq!(
+        take_reply(&third).unwrap(),
+        "*3\r\n$1\r\nz\r\n$1\r\nd\r\n$1\r\n5\r\n"
+      );
+      assert_eq!(run_str("EXISTS z\r\n", &db, &adder), vec![":0\r\n"]);
+    }
+
+    #[test]
+    fn should_wake_stream_readers_waiting_for_their_entries() {
+      let db = mock_db();
+      let (later, newest, adder) = (mock_session(), mock_session(), mock_session());
+
+      assert!(run_str("XREAD BLOCK 0 STREAMS s 5\r\n", &db, &later).is_empty());
+      assert!(run_str("XREAD BLOCK 100 STREAMS other s 0 $\r\n", &db, &newest).is_empty());
+
+      run_str("XADD s 3 f v\r\n", &db, &adder);
+      assert_eq!(
+        take_reply(&newest).unwrap(),
+        "*1\r\n*2\r\n$1\r\ns\r\n*1\r\n*2\r\n$3\r\n3-0\r\n*2\r\n$1\r\nf\r\n$1\r\nv\r\n"
+      );
+      assert_eq!(pending_len(&later), 0, "3-0 is not after 5-0");
+
+      run_str("XADD s 6 f v\r\n", &db, &adder);
+      assert_eq!(
+        take_reply(&later).unwrap(),
+        "*1\r\n*2\r\n$1\r\ns\r\n*1\r\n*2\r\n$3\r\n6-0\r\n*2\r\n$1\r\nf\r\n$1\r\nv\r\n"
+      );
+      assert_eq!(
+        run_str(
+          "XREAD BLOCK -1 STREAMS s $\r\nXREAD BLOCK x STREAMS s $\r\n",
+          &db,
+          &adder
+        ),
+        vec![
+          "-ERR timeout is negative\r\n",
+          "-ERR timeout is not an integer or out of range\r\n"
+        ]
+      );
+    }
+
+    #[test]
+    fn should_wake_group_readers_waiting_for_new_entries() {
+      let clock = Arc::new(ManualClock::new(1_000_000));
+      let db = mock_db_with_clock(&clock);
+      let (alice, bob, adder) = (mock_session(), mock_session(), mock_session());
+
+      run_str(
+        "XGROUP CREATE s g $ MKSTREAM\r\nXGROUP CREATE s other $\r\n",
+        &db,
+        &adder,
+      );
+      assert!(run_str(
+        "XREADGROUP GROUP g alice BLOCK 0 STREAMS s >\r\n",
+        &db,
+        &alice
+      )
+      .is_empty());
+      assert!(run_str(
+        "XREADGROUP GROUP other bob BLOCK 0 STREAMS s >\r\n",
+        &db,
+        &bob
+      )
+      .is_empty());
+      assert_eq!(
+        run_str(
+          "XREADGROUP GROUP g carol BLOCK 0 STREAMS s 0\r\n",
+          &db,
+          &adder
+        ),
+        vec!["*1\r\n*2\r\n$1\r\ns\r\n*0\r\n"],
+        "reading the history never blocks"
+      );
+
+      run_str("XGROUP DESTROY s other\r\nXADD s 1 f v\r\n", &db, &adder);
+      assert_eq!(
+        take_reply(&alice).unwrap(),
+        "*1\r\n*2\r\n$1\r\ns\r\n*1\r\n*2\r\n$3\r\n1-0\r\n*2\r\n$1\r\nf\r\n$1\r\nv\r\n"
+      );
+      assert_eq!(
+        take_reply(&bob).unwrap(),
+        "-NOGROUP No such key 's' or consumer group 'other' in XREADGROUP with GROUP option\r\n"
+      );
+      assert_eq!(
+        run_str("XPENDING s g - + 10\r\n", &db, &adder),
+        vec!["*1\r\n*4\r\n$3\r\n1-0\r\n$5\r\nalice\r\n:0\r\n:1\r\n"]
+      );
+    }
+
+    #[test]
+    fn should_find_keys_of_lmpop_and_blmpop() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "COMMAND GETKEYS LMPOP 2 a b LEFT\r\nCOMMAND GETKEYS BLMPOP 0 1 a RIGHT COUNT 2\r\nCOMMAND GETKEYS LMPOP 3 a LEFT\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "*2\r\n$1\r\na\r\n$1\r\nb\r\n",
+        "*1\r\n$1\r\na\r\n",
+        "-ERR The command has no key arguments\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_reject_invalid_timeouts_and_arguments() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "BLPOP l -1\r\nBLPOP l abc\r\nBLMOVE a b UP LEFT 0\r\nLMPOP 0 l LEFT\r\nLMPOP 2 l LEFT\r\nLMPOP 1 l LEFT COUNT 0\r\nLMPOP 1 l LEFT FOO\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "-ERR timeout is negative\r\n",
+        "-ERR timeout is not a float or out of range\r\n",
+        "-ERR syntax error\r\n",
+        "-ERR numkeys should be greater than 0\r\n",
+        "-ERR syntax error\r\n",
+        "-ERR count should be greater than 0\r\n",
+        "-ERR syntax error\r\n",
+      ];
+      assert_eq!(response, expected_response);
+      assert!(session.borrow().blocked.is_none());
+    }
+
+    #[test]
+    fn should_reject_oversized_timeouts_without_poisoning_the_database() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "BLPOP l 1e19
+BLPOP l 1e300
+PING
+",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "-ERR timeout is out of range\r\n",
+        "-ERR timeout is out of range\r\n",
+        "+PONG\r\n",
+      ];
+      assert_eq!(response, expected_response);
+      assert!(session.borrow().blocked.is_none());
+      assert!(!db.is_poisoned());
+    }
+  }
+
+  mod test_hash {
+    use super::*;
+
+    #[test]
+    fn should_set_get_and_delete_fields() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "HSET h name alice age 30\r\nHSET h name bob\r\nHSETNX h name carol\r\nHSETNX h city seoul\r\nHGET h name\r\nHGET h missing\r\nHMGET h age missing\r\nHLEN h\r\nHEXISTS h age\r\nHSTRLEN h city\r\nHDEL h age city missing\r\nHDEL h name\r\nEXISTS h\r\nHSET h odd\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        ":2\r\n",
+        ":0\r\n",
+        ":0\r\n",
+        ":1\r\n",
+        "$3\r\nbob\r\n",
+        "$-1\r\n",
+        "*2\r\n$2\r\n30\r\n$-1\r\n",
+        ":3\r\n",
+        ":1\r\n",
+        ":5\r\n",
+        ":2\r\n",
+        ":1\r\n",
+        ":0\r\n",
+        "-ERR wrong number of arguments for 'hset' command\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_reply_hgetall_as_map_in_resp3() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "HSET h f v\r\nHGETALL h\r\nHKEYS h\r\nHVALS h\r\nHGETALL missing\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        ":1\r\n",
+        "*2\r\n$1\r\nf\r\n$1\r\nv\r\n",
+        "*1\r\n$1\r\nf\r\n",
+        "*1\r\n$1\r\nv\r\n",
+        "*0\r\n",
+      ];
+      assert_eq!(response, expected_response);
+
+      session.borrow_mut().protocol = Protocol::Resp3;
+      let response = run_str("HGETALL h\r\nHRANDFIELD h 1 WITHVALUES\r\n", &db, &session);
+      let expected_response = vec![
+        "%1\r\n$1\r\nf\r\n$1\r\nv\r\n",
+        "*1\r\n*2\r\n$1\r\nf\r\n$1\r\nv\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_increment_fields() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "HINCRBY h n 5\r\nHINCRBY h n -7\r\nHINCRBY h n x\r\nHSET h s abc\r\nHINCRBY h s 1\r\nHSET h big 9223372036854775807\r\nHINCRBY h big 1\r\nHINCRBYFLOAT h f 10.5\r\nHINCRBYFLOAT h f 0.25\r\nHINCRBYFLOAT h n 1.5\r\nHINCRBYFLOAT h s 1\r\nHINCRBYFLOAT h f nan\r\nHINCRBYFLOAT h f inf\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        ":5\r\n",
+        ":-2\r\n",
+        "-ERR value is not an integer or out of range\r\n",
+        ":1\r\n",
+        "-ERR hash value is not an integer\r\n",
+        ":1\r\n",
+        "-ERR increment or decrement would overflow\r\n",
+        "$4\r\n10.5\r\n",
+        "$5\r\n10.75\r\n",
+        "$4\r\n-0.5\r\n",
+        "-ERR hash value is not a float\r\n",
+        "-ERR value is not a valid float\r\n",
+        "-ERR increment would produce NaN or Infinity\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_pick_random_fields() {
+      let db = mock_db();
+      let session = mock_session();
+
+      run_str("HSET h a 1 b 2 c 3\r\n", &db, &session);
+      let response = run_str(
+        "HRANDFIELD h 5\r\nHRANDFIELD h -5\r\nHRANDFIELD h 2 WITHVALUES\r\nHRANDFIELD h 0\r\nHRANDFIELD missing\r\nHRANDFIELD missing 2\r\nHRANDFIELD h 1 FOO\r\n",
+        &db,
+        &session,
+      );
+      assert!(response[0].starts_with("*3\r\n"));
+      assert!(response[1].starts_with("*5\r\n"));
+      assert!(response[2].starts_with("*4\r\n"));
+      let expected_response = vec!["*0\r\n", "$-1\r\n", "*0\r\n", "-ERR syntax error\r\n"];
+      assert_eq!(response[3..], expected_response);
+
+      let field = run_str("HRANDFIELD h\r\n", &db, &session).remove(0);
+      assert!(["$1\r\na\r\n", "$1\r\nb\r\n", "$1\r\nc\r\n"].contains(&field.as_str()));
+    }
+
+    #[test]
+    fn should_reject_random_field_counts_out_of_range() {
+      let db = mock_db();
+      let session = mock_session();
+
+      run_str("HSET h a 1\r\n", &db, &session);
+      let response = run_str(
+        "HRANDFIELD h -9223372036854775808\r\nHRANDFIELD h -4611686018427387904 WITHVALUES\r\nHRANDFIELD h -2 WITHVALUES\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "-ERR value is out of range, value must between -9223372036854775807 and 9223372036854775807\r\n",
+        "-ERR value is out of range\r\n",
+        "*4\r\n$1\r\na\r\n$1\r\n1\r\n$1\r\na\r\n$1\r\n1\r\n",
+      ];
+      assert_eq!(response, expected_response);
+      assert!(!db.is_poisoned());
+    }
+
+    #[test]
+    fn should_set_and_read_field_expire_times() {
+      let clock = Arc::new(ManualClock::new(1_000_000));
+      let db = mock_db_with_clock(&clock);
+      let session = mock_session();
+
+      let response = run_str(
+        "HSET h a 1 b 2 c 3\r\nHEXPIRE h 100 FIELDS 2 a missing\r\nHPEXPIRE h 5000 NX FIELDS 2 a b\r\nHTTL h FIELDS 3 a b c\r\nHPTTL h FIELDS 1 b\r\nHEXPIRETIME h FIELDS 1 a\r\nHPEXPIRETIME h FIELDS 1 b\r\nHPERSIST h FIELDS 3 a c missing\r\nHEXPIREAT h 1 FIELDS 1 c\r\nHLEN h\r\nHTTL missing FIELDS 1 a\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        ":3\r\n",
+        "*2\r\n:1\r\n:-2\r\n",
+        "*2\r\n:0\r\n:1\r\n",
+        "*3\r\n:100\r\n:5\r\n:-1\r\n",
+        "*1\r\n:5000\r\n",
+        "*1\r\n:1100\r\n",
+        "*1\r\n:1005000\r\n",
+        "*3\r\n:1\r\n:-1\r\n:-2\r\n",
+        "*1\r\n:2\r\n",
+        ":2\r\n",
+        "*1\r\n:-2\r\n",
+      ];
+      assert_eq!(response, expected_response);
+
+      clock.advance(std::time::Duration::from_millis(1_500));
+      let response = run_str("HTTL h FIELDS 1 b\r\n", &db, &session);
+      assert_eq!(response, vec!["*1\r\n:4\r\n"]);
+    }
+
+    #[test]
+    fn should_delete_expired_fields_and_empty_hashes() {
+      let clock = Arc::new(ManualClock::new(1_000_000));
+      let db = mock_db_with_clock(&clock);
+      let session = mock_session();
+
+      let response = run_str(
+        "HSET h a 1 b 2 c 3 d 4\r\nHPEXPIRE h 100 FIELDS 3 a b c\r\nHSET h a 10\r\nHINCRBY h b 1\r\nHTTL h FIELDS 2 a b\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        ":4\r\n",
+        "*3\r\n:1\r\n:1\r\n:1\r\n",
+        ":0\r\n",
+        ":3\r\n",
+        "*2\r\n:-1\r\n:1\r\n",
+      ];
+      assert_eq!(
+        response, expected_response,
+        "HSET clears the expire time, HINCRBY keeps it"
+      );
+
+      clock.advance(std::time::Duration::from_millis(200));
+      let response = run_str(
+        "HGET h b\r\nHLEN h\r\nHPEXPIRE h 100 FIELDS 2 a d\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec!["$-1\r\n", ":2\r\n", "*2\r\n:1\r\n:1\r\n"];
+      assert_eq!(response, expected_response);
+
+      clock.advance(std::time::Duration::from_millis(200));
+      active_expire_cycle(&db);
+      assert!(
+        db.lock().unwrap().is_empty(),
+        "the active cycle deletes the emptied hash"
+      );
+      let info = run_str("INFO stats\r\n", &db, &session).remove(0);
+      assert!(info.contains("expired_keys:0\r\nexpired_subkeys:4\r\n"));
+    }
+
+    #[test]
+    fn should_reject_invalid_field_expire_arguments() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "HSET h a 1\r\nHEXPIRE h 10 FIELD 1 a\r\nHEXPIRE h 10 FIELDS 0 a\r\nHEXPIRE h 10 FIELDS 2 a\r\nHEXPIRE h -1 FIELDS 1 a\r\nHEXPIRE h 10 XY FIELDS 1 a\r\nHTTL h FIELDS x a\r\nHEXPIRE h 10 GT FIELDS 1 a\r\nHEXPIRE s 10 FIELDS 1 a\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        ":1\r\n",
+        "-ERR Mandatory argument FIELDS is missing or not at the right position\r\n",
+        "-ERR Parameter `numFields` should be greater than 0\r\n",
+        "-ERR The `numfields` parameter must match the number of arguments\r\n",
+        "-ERR invalid expire time, must be >= 0\r\n",
+        "-ERR Mandatory argument FIELDS is missing or not at the right position\r\n",
+        "-ERR Parameter `numFields` should be greater than 0\r\n",
+        "*1\r\n:0\r\n",
+        "*1\r\n:-2\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_reject_wrong_types() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "SET s v\r\nHSET s f v\r\nHGET s f\r\nHSET h f v\r\nGET h\r\n",
+        &db,
+        &session,
+      );
+      let wrong_type = "-WRONGTYPE Operation against a key holding the wrong kind of value\r\n";
+      let expected_response = vec!["+OK\r\n", wrong_type, wrong_type, ":1\r\n", wrong_type];
+      assert_eq!(response, expected_response);
+    }
+  }
+
+  mod test_set {
+    use super::*;
+
+    #[test]
+    fn should_add_check_and_remove_members() {
+      let db = mock_db();
+
+      let response = run_str(
+        "SADD s 3 1 2 1\r\nSMEMBERS s\r\nSCARD s\r\nSISMEMBER s 2\r\nSISMEMBER s 02\r\nSMISMEMBER s 1 9\r\nSREM s 1 9\r\nSREM s 2 3\r\nEXISTS s\r\nSMEMBERS missing\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response = vec![
+        ":3\r\n",
+        "*3\r\n$1\r\n1\r\n$1\r\n2\r\n$1\r\n3\r\n",
+        ":3\r\n",
+        ":1\r\n",
+        ":0\r\n",
+        "*2\r\n:1\r\n:0\r\n",
+        ":1\r\n",
+        ":2\r\n",
+        ":0\r\n",
+        "*0\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_keep_small_integer_sets_compact() {
+      let db = mock_db();
+
+      run_str("SADD ints 1 2 3\r\nSADD tags a 1\r\n", &db, &mock_session());
+      let mut db = db.lock().unwrap();
+      let ints = db.get_value::<SetValue>(b"ints").unwrap().unwrap();
+      assert_eq!(ints, &SetValue::IntSet(vec![1, 2, 3]));
+      let tags = db.get_value::<SetValue>(b"tags").unwrap().unwrap();
+      assert_eq!(tags.encoding(), "hashtable");
+    }
+
+    #[test]
+    fn should_reject_random_member_counts_out_of_range() {
+      let db = mock_db();
+      let session = mock_session();
+
+      run_str("SADD s a\r\n", &db, &session);
+      let response = run_str(
+        "SRANDMEMBER s -9223372036854775808\r\nSRANDMEMBER s -2\r\nSCARD s\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "-ERR value is out of range, value must between -9223372036854775807 and 9223372036854775807\r\n",
+        "*2\r\n$1\r\na\r\n$1\r\na\r\n",
+        ":1\r\n",
+      ];
+      assert_eq!(response, expected_response);
+      assert!(!db.is_poisoned());
+    }
+
+    #[test]
+    fn should_pop_and_pick_random_members() {
+      let db = mock_db();
+
+      run_str("SADD s 1 2 3 4\r\n", &db, &mock_session());
+      let response = run_str(
+        "SPOP s 3\r\nSCARD s\r\nSRANDMEMBER s 5\r\nSRANDMEMBER s -3\r\nSPOP s\r\nEXISTS s\r\nSPOP s\r\nSPOP s 2\r\nSRANDMEMBER s\r\nSRANDMEMBER s 2\r\nSPOP s -1\r\n",
+        &db,
+        &mock_session(),
+      );
+      assert!(response[0].starts_with("*3\r\n"));
+      assert!(response[2].starts_with("*1\r\n"));
+      assert!(response[3].starts_with("*3\r\n"));
+      let expected_response = vec![
+        ":0\r\n",
+        "$-1\r\n",
+        "*0\r\n",
+        "$-1\r\n",
+        "*0\r\n",
+        "-ERR value is out of range, must be positive\r\n",
+      ];
+      assert_eq!(response[5..], expected_response);
+      assert_eq!(response[1], ":1\r\n");
+      // the member left after popping three is the only one SRANDMEMBER could pick
+      assert_eq!(response[4], response[2]["*1\r\n".len()..]);
+    }
+
+    #[test]
+    fn should_move_members_between_sets() {
+      let db = mock_db();
+
+      let response = run_str(
+        "SADD a 1 2\r\nSMOVE a b 1\r\nSMOVE a b 9\r\nSMOVE a a 2\r\nSMOVE a b 2\r\nEXISTS a\r\nSMEMBERS b\r\nSET str x\r\nSMOVE b str 1\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response = vec![
+        ":2\r\n",
+        ":1\r\n",
+        ":0\r\n",
+        ":1\r\n",
+        ":1\r\n",
+        ":0\r\n",
+        "*2\r\n$1\r\n1\r\n$1\r\n2\r\n",
+        "+OK\r\n",
+        "-WRONGTYPE Operation against a key holding the wrong kind of value\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_combine_sets() {
+      let db = mock_db();
+
+      let response = run_str(
+        "SADD a 1 2 3 4\r\nSADD b 3 4 5\r\nSADD c 4 6\r\nSINTER a b c\r\nSINTER a missing\r\nSUNION a missing c\r\nSDIFF a b\r\nSDIFF missing a\r\nSINTERSTORE d a b\r\nSMEMBERS d\r\nSDIFFSTORE d a a\r\nEXISTS d\r\nSUNIONSTORE d b c\r\nSINTERCARD 2 a b\r\nSINTERCARD 2 a b LIMIT 1\r\nSINTERCARD 1 a LIMIT 0\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response = vec![
+        ":4\r\n",
+        ":3\r\n",
+        ":2\r\n",
+        "*1\r\n$1\r\n4\r\n",
+        "*0\r\n",
+        "*5\r\n$1\r\n1\r\n$1\r\n2\r\n$1\r\n3\r\n$1\r\n4\r\n$1\r\n6\r\n",
+        "*2\r\n$1\r\n1\r\n$1\r\n2\r\n",
+        "*0\r\n",
+        ":2\r\n",
+        "*2\r\n$1\r\n3\r\n$1\r\n4\r\n",
+        ":0\r\n",
+        ":0\r\n",
+        ":4\r\n",
+        ":2\r\n",
+        ":1\r\n",
+        ":4\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_find_keys_of_sintercard() {
+      let response = run_str(
+        "COMMAND GETKEYS SINTERCARD 2 a b LIMIT 1\r\nCOMMAND GETKEYS SINTERCARD 3 a b\r\n",
+        &mock_db(),
+        &mock_session(),
+      );
+      let expected_response = vec![
+        "*2\r\n$1\r\na\r\n$1\r\nb\r\n",
+        "-ERR The command has no key arguments\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_reject_invalid_sintercard_arguments() {
+      let db = mock_db();
+
+      let response = run_str(
+        "SINTERCARD 0 a\r\nSINTERCARD 3 a b\r\nSINTERCARD 1 a LIMIT -1\r\nSINTERCARD 1 a FOO 1\r\nSET str x\r\nSINTER str\r\n",
+        &db,
+        &mock_session(),
+      );
+      let expected_response = vec![
+        "-ERR numkeys should be greater than 0\r\n",
+        "-ERR Number of keys can't be greater than number of args\r\n",
+        "-ERR LIMIT can't be negative\r\n",
+        "-ERR syntax error\r\n",
+        "+OK\r\n",
+        "-WRONGTYPE Operation against a key holding the wrong kind of value\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+  }
+
+  mod test_sorted_set {
+    use super::*;
+
+    #[test]
+    fn should_add_members_with_options() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "ZADD z 1 a 2 b\r\nZADD z NX 5 a 3 c\r\nZADD z XX CH 5 a 9 d\r\nZADD z GT CH 4 a 6 b\r\nZADD z LT 1 e\r\nZADD z INCR 2.5 a\r\nZADD z NX INCR 1 a\r\nZADD z inf a\r\nZADD z INCR -inf a\r\nZADD missing XX 1 a\r\nEXISTS missing\r\nZRANGE z 0 -1 WITHSCORES\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        ":2\r\n",
+        ":1\r\n",
+        ":1\r\n",
+        ":1\r\n",
+        ":1\r\n",
+        "$3\r\n7.5\r\n",
+        "$-1\r\n",
+        ":0\r\n",
+        "-ERR resulting score is not a number (NaN)\r\n",
+        ":0\r\n",
+        ":0\r\n",
+        "*8\r\n$1\r\ne\r\n$1\r\n1\r\n$1\r\nc\r\n$1\r\n3\r\n$1\r\nb\r\n$1\r\n6\r\n$1\r\na\r\n$3\r\ninf\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_reject_invalid_zadd_options() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "ZADD z XX NX 1 a\r\nZADD z GT LT 1 a\r\nZADD z NX GT 1 a\r\nZADD z INCR 1 a 2 b\r\nZADD z 1 a 2\r\nZADD z x a\r\nEXISTS z\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "-ERR XX and NX options at the same time are not compatible\r\n",
+        "-ERR GT, LT, and/or NX options at the same time are not compatible\r\n",
+        "-ERR GT, LT, and/or NX options at the same time are not compatible\r\n",
+        "-ERR INCR option supports a single increment-element pair\r\n",
+        "-ERR syntax error\r\n",
+        "-ERR value is not a valid float\r\n",
+        ":0\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_select_ranges_by_index_score_and_lex() {
+      let db = mock_db();
+      let session = mock_session();
+
+      run_str(
+        "ZADD z 1 a 2 b 3 c 4 d 5 e\r\nZADD l 0 a 0 b 0 c 0 d\r\n",
+        &db,
+        &session,
+      );
+      let response = run_str(
+        "ZRANGE z 1 -2\r\nZRANGE z 0 1 REV\r\nZREVRANGE z 0 0 WITHSCORES\r\nZRANGE z (1 3 BYSCORE\r\nZRANGE z 4 (2 BYSCORE REV LIMIT 1 5\r\nZRANGEBYSCORE z -inf +inf LIMIT 3 -1\r\nZREVRANGEBYSCORE z +inf 4\r\nZCOUNT z (1 3\r\nZRANGE l [b + BYLEX\r\nZREVRANGEBYLEX l (d -\r\nZRANGE z 5 1\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "*3\r\n$1\r\nb\r\n$1\r\nc\r\n$1\r\nd\r\n",
+        "*2\r\n$1\r\ne\r\n$1\r\nd\r\n",
+        "*2\r\n$1\r\ne\r\n$1\r\n5\r\n",
+        "*2\r\n$1\r\nb\r\n$1\r\nc\r\n",
+        "*1\r\n$1\r\nc\r\n",
+        "*2\r\n$1\r\nd\r\n$1\r\ne\r\n",
+        "*2\r\n$1\r\ne\r\n$1\r\nd\r\n",
+        ":2\r\n",
+        "*3\r\n$1\r\nb\r\n$1\r\nc\r\n$1\r\nd\r\n",
+        "*3\r\n$1\r\nc\r\n$1\r\nb\r\n$1\r\na\r\n",
+        "*0\r\n",
+      ];
+      assert_eq!(response, expected_response);
+
+      let response = run_str(
+        "ZRANGE z 0 -1 LIMIT 0 1\r\nZRANGE z x 1 BYSCORE\r\nZRANGEBYLEX l b c\r\nZRANGE l - + BYLEX WITHSCORES\r\nZRANGE z 0 1 FOO\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "-ERR syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX\r\n",
+        "-ERR min or max is not a float\r\n",
+        "-ERR min or max not valid string range item\r\n",
+        "-ERR syntax error, WITHSCORES not supported in combination with BYLEX\r\n",
+        "-ERR syntax error\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_store_ranges() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "ZADD z 1 a 2 b 3 c 4 d\r\nZRANGESTORE dst z 2 4 BYSCORE\r\nZRANGE dst 0 -1\r\nZRANGESTORE dst z 0 0 REV\r\nZRANGE dst 0 -1 WITHSCORES\r\nZRANGESTORE dst z 10 20 BYSCORE\r\nEXISTS dst\r\nZRANGESTORE dst z 0 1 WITHSCORES\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        ":4\r\n",
+        ":3\r\n",
+        "*3\r\n$1\r\nb\r\n$1\r\nc\r\n$1\r\nd\r\n",
+        ":1\r\n",
+        "*2\r\n$1\r\nd\r\n$1\r\n4\r\n",
+        ":0\r\n",
+        ":0\r\n",
+        "-ERR syntax error\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_rank_score_and_remove_members() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "ZADD z 1 a 2 b 3 c\r\nZRANK z b\r\nZREVRANK z b WITHSCORE\r\nZRANK z x\r\nZRANK z x WITHSCORE\r\nZSCORE z c\r\nZSCORE z x\r\nZINCRBY z 1.5 a\r\nZINCRBY z 1 new\r\nZCARD z\r\nZREM z new x\r\nZPOPMIN z\r\nZPOPMAX z 5\r\nEXISTS z\r\nZPOPMIN z\r\nZPOPMIN z -1\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        ":3\r\n",
+        ":1\r\n",
+        "*2\r\n:1\r\n$1\r\n2\r\n",
+        "$-1\r\n",
+        "*-1\r\n",
+        "$1\r\n3\r\n",
+        "$-1\r\n",
+        "$3\r\n2.5\r\n",
+        "$1\r\n1\r\n",
+        ":4\r\n",
+        ":1\r\n",
+        "*2\r\n$1\r\nb\r\n$1\r\n2\r\n",
+        "*4\r\n$1\r\nc\r\n$1\r\n3\r\n$1\r\na\r\n$3\r\n2.5\r\n",
+        ":0\r\n",
+        "*0\r\n",
+        "-ERR value is out of range, must be positive\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_reply_with_score_pairs_in_resp3() {
+      let db = mock_db();
+      let session = mock_session();
+      session.borrow_mut().protocol = Protocol::Resp3;
+
+      let response = run_str(
+        "ZADD z 1 a 2 b 3 c\r\nZRANGE z 0 0 WITHSCORES\r\nZPOPMIN z\r\nZPOPMAX z 1\r\nZSCORE z c\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        ":3\r\n",
+        "*1\r\n*2\r\n$1\r\na\r\n,1\r\n",
+        "*2\r\n$1\r\na\r\n,1\r\n",
+        "*1\r\n*2\r\n$1\r\nc\r\n,3\r\n",
+        "_\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_find_keys_of_zunionstore_and_zinterstore() {
+      let response = run_str(
+        "COMMAND GETKEYS ZUNIONSTORE u 2 a b WEIGHTS 1 2\r\nCOMMAND GETKEYS ZINTERSTORE i 1 a AGGREGATE MAX\r\nCOMMAND GETKEYS ZINTERSTORE i 2 a\r\n",
+        &mock_db(),
+        &mock_session(),
+      );
+      let expected_response = vec![
+        "*3\r\n$1\r\nu\r\n$1\r\na\r\n$1\r\nb\r\n",
+        "*2\r\n$1\r\ni\r\n$1\r\na\r\n",
+        "-ERR The command has no key arguments\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_combine_sorted_sets() {
+      let db = mock_db();
+      let session = mock_session();
+
+      run_str(
+        "ZADD a 1 x 2 y 3 z\r\nZADD b 10 y 20 z 30 w\r\nSADD s z w\r\n",
+        &db,
+        &session,
+      );
+      let response = run_str(
+        "ZUNIONSTORE u 2 a b\r\nZRANGE u 0 -1 WITHSCORES\r\nZINTERSTORE i 3 a b s WEIGHTS 2 1 100 AGGREGATE MAX\r\nZRANGE i 0 -1 WITHSCORES\r\nZINTERSTORE i 2 a b AGGREGATE MIN\r\nZRANGE i 0 -1 WITHSCORES\r\nZINTERSTORE i 2 a missing\r\nEXISTS i\r\nZADD n inf x\r\nZUNIONSTORE u 1 n WEIGHTS 0\r\nZSCORE u x\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        ":4\r\n",
+        "*8\r\n$1\r\nx\r\n$1\r\n1\r\n$1\r\ny\r\n$2\r\n12\r\n$1\r\nz\r\n$2\r\n23\r\n$1\r\nw\r\n$2\r\n30\r\n",
+        ":1\r\n",
+        "*2\r\n$1\r\nz\r\n$3\r\n100\r\n",
+        ":2\r\n",
+        "*4\r\n$1\r\ny\r\n$1\r\n2\r\n$1\r\nz\r\n$1\r\n3\r\n",
+        ":0\r\n",
+        ":0\r\n",
+        ":1\r\n",
+        ":1\r\n",
+        "$1\r\n0\r\n",
+      ];
+      assert_eq!(response, expected_response);
+
+      let response = run_str(
+        "ZUNIONSTORE u 0 a\r\nZINTERSTORE u 2 a\r\nZUNIONSTORE u 1 a WEIGHTS x\r\nZUNIONSTORE u 1 a AGGREGATE avg\r\nSET str x\r\nZUNIONSTORE u 1 str\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "-ERR at least 1 input key is needed for 'zunionstore' command\r\n",
+        "-ERR syntax error\r\n",
+        "-ERR weight value is not a float\r\n",
+        "-ERR syntax error\r\n",
+        "+OK\r\n",
+        "-WRONGTYPE Operation against a key holding the wrong kind of value\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+  }
+
+  mod test_stream {
+    use super::*;
+
+    #[test]
+    fn should_add_and_range_entries() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "XADD s 1-1 a 1\r\nXADD s 1-* b 2\r\nXADD s 2 c 3 d 4\r\nXLEN s\r\nXRANGE s - +\r\nXRANGE s (1-1 2 COUNT 1\r\nXREVRANGE s + 1-2\r\nXRANGE s 1 1\r\nXRANGE s 2 1\r\nXADD missing NOMKSTREAM * a 1\r\nEXISTS missing\r\nXRANGE missing - +\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "$3\r\n1-1\r\n".to_owned(),
+        "$3\r\n1-2\r\n".to_owned(),
+        "$3\r\n2-0\r\n".to_owned(),
+        ":3\r\n".to_owned(),
+        format!("*3\r\n*2\r\n$3\r\n1-1\r\n*2\r\n$1\r\na\r\n$1\r\n1\r\n*2\r\n$3\r\n1-2\r\n*2\r\n$1\r\nb\r\n$1\r\n2\r\n*2\r\n$3\r\n2-0\r\n*4\r\n$1\r\nc\r\n$1\r\n3\r\n$1\r\nd\r\n$1\r\n4\r\n"),
+        format!("*1\r\n*2\r\n$3\r\n1-2\r\n*2\r\n$1\r\nb\r\n$1\r\n2\r\n"),
+        format!("*2\r\n*2\r\n$3\r\n2-0\r\n*4\r\n$1\r\nc\r\n$1\r\n3\r\n$1\r\nd\r\n$1\r\n4\r\n*2\r\n$3\r\n1-2\r\n*2\r\n$1\r\nb\r\n$1\r\n2\r\n"),
+        format!("*2\r\n*2\r\n$3\r\n1-1\r\n*2\r\n$1\r\na\r\n$1\r\n1\r\n*2\r\n$3\r\n1-2\r\n*2\r\n$1\r\nb\r\n$1\r\n2\r\n"),
+        "*0\r\n".to_owned(),
+        "$-1\r\n".to_owned(),
+        ":0\r\n".to_owned(),
+        "*0\r\n".to_owned(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_enforce_increasing_ids() {
+      let clock = Arc::new(ManualClock::new(1_000));
+      let db = mock_db_with_clock(&clock);
+      let session = mock_session();
+
+      let response = run_str(
+        "XADD s * f v\r\nXADD s * f v\r\nXADD s 1000-1 f v\r\nXADD s 999-* f v\r\nXADD s 0-0 f v\r\nXADD s 1-x f v\r\nXADD s * f v g\r\nSET str x\r\nXADD str * f v\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "$6\r\n1000-0\r\n",
+        "$6\r\n1000-1\r\n",
+        "-ERR The ID specified in XADD is equal or smaller than the target stream top item\r\n",
+        "-ERR The ID specified in XADD is equal or smaller than the target stream top item\r\n",
+        "-ERR The ID specified in XADD must be greater than 0-0\r\n",
+        "-ERR Invalid stream ID specified as stream command argument\r\n",
+        "-ERR wrong number of arguments for 'xadd' command\r\n",
+        "+OK\r\n",
+        "-WRONGTYPE Operation against a key holding the wrong kind of value\r\n",
+      ];
+      assert_eq!(response, expected_response);
+
+      clock.advance(std::time::Duration::from_millis(5));
+      assert_eq!(
+        run_str("XADD s * f v\r\n", &db, &session),
+        vec!["$6\r\n1005-0\r\n"]
+      );
+    }
+
+    #[test]
+    fn should_trim_and_delete_entries() {
+      let db = mock_db();
+      let session = mock_session();
+
+      run_str(
+        "XADD s 1 f v\r\nXADD s 2 f v\r\nXADD s 3 f v\r\nXADD s 4 f v\r\nXADD s 5 f v\r\n",
+        &db,
+        &session,
+      );
+      let response = run_str(
+        "XTRIM s MAXLEN 3\r\nXTRIM s MAXLEN ~ 1\r\nXTRIM s MINID 4\r\nXDEL s 4-0 9-0\r\nXDEL s 5\r\nXLEN s\r\nEXISTS s\r\nXADD s 5-0 f v\r\nXADD s MAXLEN = 1 6 f v\r\nXADD s MINID ~ 7 LIMIT 10 7 f v\r\nXADD s MAXLEN 1 8 f v\r\nXLEN s\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        ":2\r\n",
+        ":0\r\n",
+        ":1\r\n",
+        ":1\r\n",
+        ":1\r\n",
+        ":0\r\n",
+        ":1\r\n",
+        "-ERR The ID specified in XADD is equal or smaller than the target stream top item\r\n",
+        "$3\r\n6-0\r\n",
+        "$3\r\n7-0\r\n",
+        "$3\r\n8-0\r\n",
+        ":1\r\n",
+      ];
+      assert_eq!(response, expected_response);
+
+      let response = run_str(
+        "XTRIM s MAXLEN -1\r\nXTRIM s MAXLEN 1 LIMIT 5\r\nXTRIM s MAXLEN ~ 1 LIMIT -1\r\nXTRIM s FOO 1\r\nXTRIM s MINID x\r\nXTRIM s MAXLEN 1 extra\r\nXRANGE s (+ +\r\nXADD s LIMIT 10 * f v\r\nXADD s MAXLEN = 1 LIMIT 10 * f v\r\nXADD s MINID 1 LIMIT 10 * f v\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "-ERR The MAXLEN argument must be >= 0.\r\n",
+        "-ERR syntax error, LIMIT cannot be used without the special ~ option\r\n",
+        "-ERR The LIMIT argument must be >= 0.\r\n",
+        "-ERR syntax error\r\n",
+        "-ERR Invalid stream ID specified as stream command argument\r\n",
+        "-ERR syntax error\r\n",
+        "-ERR Invalid stream ID specified as stream command argument\r\n",
+        "-ERR syntax error, LIMIT cannot be used without specifying a trimming strategy\r\n",
+        "-ERR syntax error, LIMIT cannot be used without the special ~ option\r\n",
+        "-ERR syntax error, LIMIT cannot be used without the special ~ option\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_read_from_several_streams() {
+      let db = mock_db();
+      let session = mock_session();
+
+      run_str(
+        "XADD a 1 f v\r\nXADD b 2 f v\r\nXADD b 3 f v\r\n",
+        &db,
+        &session,
+      );
+      let response = run_str(
+        "XREAD COUNT 1 STREAMS a b 0 0\r\nXREAD STREAMS a b 1 $\r\nXREAD STREAMS b +\r\nXREAD COUNT 1 STREAMS a\r\nXREAD STREAMS a b 0\r\nXREAD COUNT 1 a 0\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "*2\r\n*2\r\n$1\r\na\r\n*1\r\n*2\r\n$3\r\n1-0\r\n*2\r\n$1\r\nf\r\n$1\r\nv\r\n*2\r\n$1\r\nb\r\n*1\r\n*2\r\n$3\r\n2-0\r\n*2\r\n$1\r\nf\r\n$1\r\nv\r\n",
+        "*-1\r\n",
+        "*1\r\n*2\r\n$1\r\nb\r\n*1\r\n*2\r\n$3\r\n3-0\r\n*2\r\n$1\r\nf\r\n$1\r\nv\r\n",
+        "-ERR Unbalanced 'xread' list of streams: for each stream key an ID or '$' must be specified.\r\n",
+        "-ERR Unbalanced 'xread' list of streams: for each stream key an ID or '$' must be specified.\r\n",
+        "-ERR syntax error\r\n",
+      ];
+      assert_eq!(response, expected_response);
+
+      session.borrow_mut().protocol = Protocol::Resp3;
+      let response = run_str("XREAD STREAMS a 0\r\n", &db, &session);
+      let expected_response =
+        vec!["%1\r\n$1\r\na\r\n*1\r\n*2\r\n$3\r\n1-0\r\n*2\r\n$1\r\nf\r\n$1\r\nv\r\n"];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_find_keys_of_xread() {
+      let response = run_str(
+        "COMMAND GETKEYS XREAD COUNT 1 BLOCK 0 STREAMS a b 0 $\r\nCOMMAND GETKEYS XREAD STREAMS streams 0\r\nCOMMAND GETKEYS XREAD STREAMS a b 0\r\nCOMMAND GETKEYS XREAD COUNT 1 a 0\r\n",
+        &mock_db(),
+        &mock_session(),
+      );
+      let expected_response = vec![
+        "*2\r\n$1\r\na\r\n$1\r\nb\r\n",
+        "*1\r\n$7\r\nstreams\r\n",
+        "-ERR The command has no key arguments\r\n",
+        "-ERR The command has no key arguments\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_find_keys_of_xreadgroup() {
+      let response = run_str(
+        "COMMAND GETKEYS XREADGROUP GROUP streams alice NOACK COUNT 1 STREAMS a b > 0\r\nCOMMAND GETKEYS XREADGROUP GROUP g alice STREAMS a b >\r\n",
+        &mock_db(),
+        &mock_session(),
+      );
+      let expected_response = vec![
+        "*2\r\n$1\r\na\r\n$1\r\nb\r\n",
+        "-ERR The command has no key arguments\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_deliver_entries_to_consumer_groups() {
+      let db = mock_db();
+      let session = mock_session();
+
+      let response = run_str(
+        "XADD s 1 a 1\r\nXADD s 2 b 2\r\nXGROUP CREATE missing g $\r\nXGROUP CREATE s g 0\r\nXGROUP CREATE s g $\r\nXREADGROUP GROUP g alice COUNT 1 STREAMS s >\r\nXREADGROUP GROUP g bob STREAMS s >\r\nXREADGROUP GROUP g bob STREAMS s >\r\nXREADGROUP GROUP g alice STREAMS s 0\r\nXACK s g 1 1 3\r\nXREADGROUP GROUP g alice STREAMS s 0\r\nXDEL s 2\r\nXREADGROUP GROUP g bob STREAMS s 0\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "$3\r\n1-0\r\n",
+        "$3\r\n2-0\r\n",
+        "-ERR The XGROUP subcommand requires the key to exist. Note that for CREATE you may want to use the MKSTREAM option to create an empty stream automatically.\r\n",
+        "+OK\r\n",
+        "-BUSYGROUP Consumer Group name already exists\r\n",
+        "*1\r\n*2\r\n$1\r\ns\r\n*1\r\n*2\r\n$3\r\n1-0\r\n*2\r\n$1\r\na\r\n$1\r\n1\r\n",
+        "*1\r\n*2\r\n$1\r\ns\r\n*1\r\n*2\r\n$3\r\n2-0\r\n*2\r\n$1\r\nb\r\n$1\r\n2\r\n",
+        "*-1\r\n",
+        "*1\r\n*2\r\n$1\r\ns\r\n*1\r\n*2\r\n$3\r\n1-0\r\n*2\r\n$1\r\na\r\n$1\r\n1\r\n",
+        ":1\r\n",
+        "*1\r\n*2\r\n$1\r\ns\r\n*0\r\n",
+        ":1\r\n",
+        "*1\r\n*2\r\n$1\r\ns\r\n*1\r\n*2\r\n$3\r\n2-0\r\n*-1\r\n",
+      ];
+      assert_eq!(response, expected_response);
+
+      let response = run_str(
+        "XREADGROUP GROUP nope alice STREAMS s >\r\nXREADGROUP GROUP g alice STREAMS s $\r\nXREADGROUP GROUP g alice COUNT 1 STREAMS s\r\nXGROUP CREATE new g $ MKSTREAM\r\nXLEN new\r\nXGROUP DELCONSUMER s g bob\r\nXGROUP DESTROY s g\r\nXGROUP DESTROY s g\r\nXGROUP SETID s g 0\r\nXGROUP CREATE s g 0 ENTRIESREAD -2\r\nXGROUP FOO s\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "-NOGROUP No such key 's' or consumer group 'nope' in XREADGROUP with GROUP option\r\n",
+        "-ERR The $ ID is meaningless in the context of XREADGROUP: you want to read the history of this consumer by specifying a proper ID, or use the > ID to get new messages. The $ ID would just return an empty result set.\r\n",
+        "-ERR Unbalanced 'xreadgroup' list of streams: for each stream key an ID or '>' must be specified.\r\n",
+        "+OK\r\n",
+        ":0\r\n",
+        ":1\r\n",
+        ":1\r\n",
+        ":0\r\n",
+        "-NOGROUP No such consumer group 'g' for key name 's'\r\n",
+        "-ERR value for ENTRIESREAD must be positive or -1\r\n",
+        "-ERR unknown subcommand or wrong number of arguments for 'FOO'. Try XGROUP HELP.\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_report_and_claim_pending_entries() {
+      let clock = Arc::new(ManualClock::new(1_000));
+      let db = mock_db_with_clock(&clock);
+      let session = mock_session();
+
+      run_str(
+        "XADD s 1 a 1\r\nXADD s 2 b 2\r\nXADD s 3 c 3\r\nXGROUP CREATE s g 0\r\nXREADGROUP GROUP g alice STREAMS s >\r\n",
+        &db,
+        &session,
+      );
+      clock.advance(std::time::Duration::from_millis(500));
+      let response = run_str(
+        "XPENDING s g\r\nXPENDING s g IDLE 400 - + 2 alice\r\nXPENDING s g IDLE 600 - + 2\r\nXCLAIM s g bob 400 1 JUSTID\r\nXCLAIM s g bob 600 3\r\nXDEL s 2\r\nXCLAIM s g bob 0 2 3\r\nXPENDING s g - + 10\r\nXPENDING s g - + 10 alice\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "*4\r\n:3\r\n$3\r\n1-0\r\n$3\r\n3-0\r\n*1\r\n*2\r\n$5\r\nalice\r\n$1\r\n3\r\n",
+        "*2\r\n*4\r\n$3\r\n1-0\r\n$5\r\nalice\r\n:500\r\n:1\r\n*4\r\n$3\r\n2-0\r\n$5\r\nalice\r\n:500\r\n:1\r\n",
+        "*0\r\n",
+        "*1\r\n$3\r\n1-0\r\n",
+        "*0\r\n",
+        ":1\r\n",
+        "*1\r\n*2\r\n$3\r\n3-0\r\n*2\r\n$1\r\nc\r\n$1\r\n3\r\n",
+        "*2\r\n*4\r\n$3\r\n1-0\r\n$3\r\nbob\r\n:0\r\n:1\r\n*4\r\n$3\r\n3-0\r\n$3\r\nbob\r\n:0\r\n:2\r\n",
+        "*0\r\n",
+      ];
+      assert_eq!(response, expected_response);
+
+      clock.advance(std::time::Duration::from_millis(1_000));
+      let response = run_str(
+        "XAUTOCLAIM s g carol 500 0 COUNT 1\r\nXAUTOCLAIM s g carol 500 (1 JUSTID\r\nXPENDING s g\r\nXAUTOCLAIM s g carol 500 0 COUNT 0\r\nXCLAIM s g carol 0 9 FORCE\r\nXCLAIM s g carol x 1\r\nXCLAIM s g carol 0 1 FOO\r\nXPENDING missing g\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "*3\r\n$3\r\n3-0\r\n*1\r\n*2\r\n$3\r\n1-0\r\n*2\r\n$1\r\na\r\n$1\r\n1\r\n*0\r\n",
+        "*3\r\n$3\r\n0-0\r\n*1\r\n$3\r\n3-0\r\n*0\r\n",
+        "*4\r\n:2\r\n$3\r\n1-0\r\n$3\r\n3-0\r\n*1\r\n*2\r\n$5\r\ncarol\r\n$1\r\n2\r\n",
+        "-ERR COUNT must be > 0\r\n",
+        "*0\r\n",
+        "-ERR Invalid min-idle-time argument for XCLAIM\r\n",
+        "-ERR Unrecognized XCLAIM option 'FOO'\r\n",
+        "-NOGROUP No such key 'missing' or consumer group 'g'\r\n",
+      ];
+      assert_eq!(response, expected_response);
+    }
+
+    #[test]
+    fn should_clamp_claimed_delivery_times_out_of_range() {
+      let clock = Arc::new(ManualClock::new(1_000));
+      let db = mock_db_with_clock(&clock);
+      let session = mock_session();
+
+      run_str(
+        "XADD s 1 a 1\r\nXADD s 2 b 2\r\nXGROUP CREATE s g 0\r\nXREADGROUP GROUP g alice STREAMS s >\r\n",
+        &db,
+        &session,
+      );
+      clock.advance(std::time::Duration::from_millis(500));
+      let response = run_str(
+        "XCLAIM s g bob 0 1 IDLE -9223372036854775808 JUSTID\r\nXCLAIM s g bob 0 2 TIME 9223372036854775807 JUSTID\r\nXCLAIM s g bob 0 1 IDLE 9223372036854775807 JUSTID\r\nXPENDING s g - + 10\r\n",
+        &db,
+        &session,
+      );
+      let expected_response = vec![
+        "*1\r\n$3\r\n1-0\r\n",
+        "*1\r\n$3\r\n2-0\r\n",
+        "*1\r\n$3\r\n1-0\r\n",
+        "*2\r\n*4\r\n$3\r\n1-0\r\n$3\r\nbob\r\n:0\r\n:1\r\n*4\r\n$3\r\n2-0\r\n$3\r\nbob\r\n:0\r\n:1\r\n",
+      ];
+      assert_eq!(response, expected_response);
+      assert!(!db.is_poisoned());
+    }
+
+    #[test]
+    fn should_describe_streams_groups_and_consumers() {
+      let clock = Arc::new(ManualClock::new(1_000));
+      let db = mock_db_with_clock(&clock);
+      let session = mock_session();
+
+      run_str(
+        "XADD s 1 a 1\r\nXADD s 2 b 2\r\nXDEL s 1\r\nXGROUP CREATE s g 0\r\nXGROUP CREATE s g2 $ ENTRIESREAD 2\r\n",
+        &db,
+        &session,
+      );
+      assert_eq!(
+        run_str(
+          "XGROUP CREATECONSUMER s g alice\r\nXGROUP CREATECONSUMER s g alice\r\n",
+          &db,
+          &session
+        ),
+        vec![":1\r\n", ":0\r\n"]
+      );
+      clock.advance(std::time::Duration::from_millis(100));
+      run_str("XREADGROUP GROUP g bob STREAMS s >\r\n", &db, &session);
+      clock.advance(std::time::Duration::from_millis(50));
+
+      let response = run_str(
+        "XINFO STREAM s\r\nXINFO GROUPS s\r\nXINFO CONSUMERS s g\r\nXINFO CONSUMERS s nope\r\nXINFO STREAM missing\r\n",
+        &db,
+        &session,
+      );
+      let entry = "*2\r\n$3\r\n2-0\r\n*2\r\n$1\r\nb\r\n$1\r\n2\r\n";
+      let expected_response = vec![
+        format!("*16\r\n$6\r\nlength\r\n:1\r\n$17\r\nlast-generated-id\r\n$3\r\n2-0\r\n$20\r\nmax-deleted-entry-id\r\n$3\r\n1-0\r\n$13\r\nentries-added\r\n:2\r\n$23\r\nrecorded-first-entry-id\r\n$3\r\n2-0\r\n$6\r\ngroups\r\n:2\r\n$11\r\nfirst-entry\r\n{entry}$10\r\nlast-entry\r\n{entry}"),
+        "*2\r\n*12\r\n$4\r\nname\r\n$1\r\ng\r\n$9\r\nconsumers\r\n:2\r\n$7\r\npending\r\n:1\r\n$17\r\nlast-delivered-id\r\n$3\r\n2-0\r\n$12\r\nentries-read\r\n:2\r\n$3\r\nlag\r\n:0\r\n*12\r\n$4\r\nname\r\n$2\r\ng2\r\n$9\r\nconsumers\r\n:0\r\n$7\r\npending\r\n:0\r\n$17\r\nlast-delivered-id\r\n$3\r\n2-0\r\n$12\r\nentries-read\r\n:2\r\n$3\r\nlag\r\n:0\r\n".to_owned(),
+        "*2\r\n*8\r\n$4\r\nname\r\n$5\r\nalice\r\n$7\r\npending\r\n:0\r\n$4\r\nidle\r\n:150\r\n$8\r\ninactive\r\n:-1\r\n*8\r\n$4\r\nname\r\n$3\r\nbob\r\n$7\r\npending\r\n:1\r\n$4\r\nidle\r\n:50\r\n$8\r\ninactive\r\n:50\r\n".to_owned(),
+        "-NOGROUP No such consumer group 'nope' for key name 's'\r\n".to_owned(),
+        "-ERR no such key\r\n".to_owned(),
+      ];
+      assert_eq!(response, expected_response);
+
+      run_str("XGROUP SETID s g 0\r\n", &db, &session);
+      assert_eq!(
+        run_str("XINFO GROUPS s\r\n", &db, &session)[0],
+        "*2\r\n*12\r\n$4\r\nname\r\n$1\r\ng\r\n$9\r\nconsumers\r\n:2\r\n$7\r\npending\r\n:1\r\n$17\r\nlast-delivered-id\r\n$3\r\n0-0\r\n$12\r\nentries-read\r\n$-1\r\n$3\r\nlag\r\n:1\r\n*12\r\n$4\r\nname\r\n$2\r\ng2\r\n$9\r\nconsumers\r\n:0\r\n$7\r\npending\r\n:0\r\n$17\r\nlast-delivered-id\r\n$3\r\n2-0\r\n$12\r\nentries-read\r\n:2\r\n$3\r\nlag\r\n:0\r\n",
+        "an estimate, as the deleted entry may have come before 0-0 or not"
+      );
+    }
+
+    #[test]
+    fn should_describe_streams_in_full() {
+      let clock = Arc::new(ManualClock::new(1_000));
+      let db = mock_db_with_clock(&clock);
+      let session = mock_session();
+
+      run_str(
+        "XADD s 1 a 1\r\nXADD s 2 b 2\r\nXADD s 3 c 3\r\nXGROUP CREATE s g 0\r\nXREADGROUP GROUP g bob COUNT 2 STREAMS s >\r\n",
+        &db,
+        &session,
+      );
+      clock.advance(std::time::Duration::from_millis(100));
+
+      let response = run_str(
+        "XINFO STREAM s FULL COUNT 1\r\nXINFO STREAM s FULL\r\nXINFO STREAM s FULL COUNT 0\r\nXINFO STREAM s FULL COUNT\r\nXINFO STREAM s FOO\r\nXINFO STREAM s FULL COUNT x\r\n",
+        &db,
+        &session,
+      );
+      let entry = |id: u8, field: char| {
+        format!("*2\r\n$3\r\n{id}-0\r\n*2\r\n$1\r\n{field}\r\n$1\r\n{id}\r\n")
+      };
+      let full = |entries: &[String], pending: &[u8]| {
+        let group_pending: String = pending
+          .iter()
+          .map(|id| format!("*4\r\n$3\r\n{id}-0\r\n$3\r\nbob\r\n:1000\r\n:1\r\n"))
+          .collect();
+        let consumer_pending: String = pending
+          .iter()
+          .map(|id| format!("*3\r\n$3\r\n{id}-0\r\n:1000\r\n:1\r\n"))
+          .collect();
+        format!(
+          "*14\r\n$6\r\nlength\r\n:3\r\n$17\r\nlast-generated-id\r\n$3\r\n3-0\r\n$20\r\nmax-deleted-entry-id\r\n$3\r\n0-0\r\n$13\r\nentries-added\r\n:3\r\n$23\r\nrecorded-first-entry-id\r\n$3\r\n1-0\r\n$7\r\nentries\r\n*{}\r\n{}$6\r\ngroups\r\n*1\r\n*14\r\n$4\r\nname\r\n$1\r\ng\r\n$17\r\nlast-delivered-id\r\n$3\r\n2-0\r\n$12\r\nentries-read\r\n:2\r\n$3\r\nlag\r\n:1\r\n$9\r\npel-count\r\n:2\r\n$7\r\npending\r\n*{}\r\n{group_pending}$9\r\nconsumers\r\n*1\r\n*10\r\n$4\r\nname\r\n$3\r\nbob\r\n$9\r\nseen-time\r\n:1000\r\n$11\r\nactive-time\r\n:1000\r\n$9\r\npel-count\r\n:2\r\n$7\r\npending\r\n*{}\r\n{consumer_pending}",
+          entries.len(),
+          entries.concat(),
+          pending.len(),
+          pending.len(),
+        )
+      };
+      let all_entries = [entry(1, 'a'), entry(2, 'b'), entry(3, 'c')];
+      let expected_response = vec![
+        full(&all_entries[..1], &[1]),
+        full(&all_entries, &[1, 2]),
+        full(&all_entries, &[1, 2]),
+        "-ERR syntax error\r\n".to_owned(),
+        "-ERR syntax error\r\n".to_owned(),
+        "-ERR value is not an integer or out of range\r\n".to_owned(),
+      ];
+      assert_eq!(response, expected_response);
+    }
+  }
 }
@@ -0,0 +1,64 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use bytes::Bytes;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+use super::{Reply, RespValue};
+
+static NEXT_CLIENT_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum Protocol {
+  #[default]
+  Resp2,
+  Resp3,
+}
+
+impl Protocol {
+  pub fn version(&self) -> i64 {
+    match self {
+      Protocol::Resp2 => 2,
+      Protocol::Resp3 => 3,
+    }
+  }
+}
+
+/// Per-connection state that outlives a single command.
+#[derive(Debug)]
+pub struct Session {
+  pub id: u64,
+  pub protocol: Protocol,
+  pub name: Option<Bytes>,
+  /// set by a blocking command that has to wait for keys
+  pub blocked: Option<Blocked>,
+}
+
+/// A blocking command waiting for its keys, see `Database::block`.
+#[derive(Debug)]
+pub struct Blocked {
+  pub id: u64,
+  pub receiver: oneshot::Receiver<Reply>,
+  /// `None` means waiting forever
+  pub deadline: Option<Instant>,
+  pub timeout_reply: Reply,
+  /// commands pipelined after the blocking one, executed once it got its reply
+  pub pending: Vec<RespValue>,
+}
+
+impl Session {
+  pub fn new() -> Self {
+    Session {
+      id: NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed),
+      protocol: Protocol::default(),
+      name: None,
+      blocked: None,
+    }
+  }
+}
+
+impl Default for Session {
+  fn default() -> Self {
+    Session::new()
+  }
+}
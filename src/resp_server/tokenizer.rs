@@ -1,30 +1,120 @@
-use super::Token;
+use bytes::Bytes;
+
 use super::{bail, Context, Result};
+use super::{RedisError, Token};
+
+/// Every byte that starts a RESP value. Anything else at the start of a request is an inline command.
+const TYPE_BYTES: &[u8] = b"*~>%|$=+-:_,#(";
+
+/// How deeply aggregate types may nest in a frame, so an untrusted client can't exhaust the stack.
+const MAX_NESTING_DEPTH: usize = 128;
 
-pub fn tokenize(input: &str) -> Result<Vec<Token>> {
+pub fn tokenize(input: &[u8]) -> Result<Vec<Token>> {
   let mut tokens = Vec::new();
-  let mut chars = input.chars();
-
-  while let Some(c) = chars.next() {
-    match c {
-      '*' => {
-        let length = read_until_crlf(&mut chars)
-          .parse()
-          .context("unexpected token: usize value expected while lexing array")?;
-        tokens.push(Token::Array(length));
-      }
-      '$' => {
-        let length: isize = read_until_crlf(&mut chars)
-          .parse()
+  let mut pos = 0;
+  // number of values still needed to complete the current top-level value
+  let mut pending_elements = 0;
+
+  while let Some(&type_byte) = input.get(pos) {
+    if pending_elements == 0 && !TYPE_BYTES.contains(&type_byte) {
+      let (args, next) = read_inline_command(input, pos)?;
+      pos = next;
+      if !args.is_empty() {
+        tokens.push(Token::Array(args.len()));
+        for arg in args {
+          tokens.push(Token::BulkString(arg.len()));
+          tokens.push(Token::StringValue(arg));
+        }
+      }
+      continue;
+    }
+    pending_elements = usize::saturating_sub(pending_elements, 1);
+
+    let (line, next) =
+      read_line(input, pos + 1).context("unexpected end of input: CRLF expected")?;
+    pos = next;
+
+    match type_byte {
+      b'*' | b'~' | b'>' | b'%' | b'|' => {
+        let length: isize = parse_length(line)
+          .context("unexpected token: usize value expected while lexing aggregate type")?;
+        let token = match (type_byte, usize::try_from(length)) {
+          (b'*', Err(_)) => Token::NullArray,
+          (_, Err(_)) => bail!("unexpected token: negative length of aggregate type"),
+          (b'*', Ok(length)) => Token::Array(length),
+          (b'~', Ok(length)) => Token::Set(length),
+          (b'>', Ok(length)) => Token::Push(length),
+          (b'%', Ok(length)) => Token::Map(length),
+          (_, Ok(length)) => Token::Attribute(length),
+        };
+        let elements = match token {
+          Token::Map(length) => length.checked_mul(2),
+          Token::Attribute(length) => length.checked_mul(2).and_then(|n| n.checked_add(1)),
+          Token::Array(length) | Token::Set(length) | Token::Push(length) => Some(length),
+          _ => Some(0),
+        };
+        pending_elements = elements
+          .and_then(|elements| pending_elements.checked_add(elements))
+          .context(RedisError::Protocol("invalid multibulk length".to_owned()))?;
+        tokens.push(token);
+      }
+      b'$' | b'=' => {
+        let length: isize = parse_length(line)
           .context("unexpected token: usize value expected while lexing bulk string")?;
         if length < 0 {
+          if type_byte == b'=' {
+            bail!("unexpected token: negative length of verbatim string");
+          }
           tokens.push(Token::NullBulkString);
         } else {
-          tokens.push(Token::BulkString(length as usize));
+          let length = length as usize;
+          tokens.push(match type_byte {
+            b'$' => Token::BulkString(length),
+            _ => Token::VerbatimString(length),
+          });
 
-          let string = read_until_crlf(&mut chars);
-          tokens.push(Token::StringValue(string));
+          // bulk strings are binary-safe, so they are read by their declared length
+          let end = pos
+            .checked_add(length)
+            .context(RedisError::Protocol("invalid bulk length".to_owned()))?;
+          let Some(string) = input.get(pos..end) else {
+            bail!("unexpected end of input: bulk string shorter than its declared length");
+          };
+          if input.get(end..end.saturating_add(2)) != Some(b"\r\n") {
+            bail!("unexpected token: CRLF expected after bulk string");
+          }
+          tokens.push(Token::StringValue(Bytes::copy_from_slice(string)));
+          pos += length + 2;
+        }
+      }
+      b'+' => tokens.push(Token::SimpleString(Bytes::copy_from_slice(line))),
+      b'-' => tokens.push(Token::Error(Bytes::copy_from_slice(line))),
+      b':' => {
+        let integer = parse_length(line).context("unexpected token: invalid integer")?;
+        tokens.push(Token::Integer(integer));
+      }
+      b'_' => {
+        if !line.is_empty() {
+          bail!("unexpected token: null should not carry a value");
         }
+        tokens.push(Token::Null);
+      }
+      b',' => {
+        let double = parse_length(line).context("unexpected token: invalid double")?;
+        tokens.push(Token::Double(double));
+      }
+      b'#' => match line {
+        b"t" => tokens.push(Token::Boolean(true)),
+        b"f" => tokens.push(Token::Boolean(false)),
+        _ => bail!("unexpected token: boolean should be either 't' or 'f'"),
+      },
+      b'(' => {
+        let big_number = std::str::from_utf8(line)?;
+        let digits = big_number.strip_prefix('-').unwrap_or(big_number);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+          bail!("unexpected token: invalid big number");
+        }
+        tokens.push(Token::BigNumber(big_number.to_owned()));
       }
       _ => {
         bail!("unexpected token: the given token not exists in grammar (or not yet implemented to be parsed)")
@@ -35,16 +125,285 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>> {
   Ok(tokens)
 }
 
-fn read_until_crlf(chars: &mut std::str::Chars) -> String {
-  let mut result = "".to_owned();
-  while let Some(c) = chars.next() {
-    if c == '\r' {
-      if chars.next() == Some('\n') {
-        break;
+/// Returns the number of bytes taken by the first complete RESP frame in `input`,
+/// or `None` if more bytes have to be read before the frame is complete.
+pub fn frame_len(input: &[u8]) -> Result<Option<usize>> {
+  match input.first() {
+    Some(type_byte) if !TYPE_BYTES.contains(type_byte) => {
+      return Ok(input.iter().position(|&b| b == b'\n').map(|pos| pos + 1));
+    }
+    _ => {}
+  }
+
+  fn _frame_len(input: &[u8], start: usize, depth: usize) -> Result<Option<usize>> {
+    let Some(&type_byte) = input.get(start) else {
+      return Ok(None);
+    };
+    let Some((line, next)) = read_line(input, start + 1) else {
+      return Ok(None);
+    };
+
+    match type_byte {
+      b'*' | b'~' | b'>' | b'%' | b'|' => {
+        let invalid_length = || RedisError::Protocol("invalid multibulk length".to_owned());
+        let length: isize = parse_length(line).context(invalid_length())?;
+        let length = length.max(0);
+        let elements = match type_byte {
+          // maps and attributes hold key-value pairs, and attributes precede the value they describe
+          b'%' => length.checked_mul(2),
+          b'|' => length.checked_mul(2).and_then(|n| n.checked_add(1)),
+          _ => Some(length),
+        };
+        let elements = elements.context(invalid_length())?;
+        if elements > 0 && depth >= MAX_NESTING_DEPTH {
+          bail!(RedisError::Protocol(
+            "multibulk nesting too deep".to_owned()
+          ));
+        }
+        let mut end = next;
+        for _ in 0..elements {
+          match _frame_len(input, end, depth + 1)? {
+            Some(element_end) => end = element_end,
+            None => return Ok(None),
+          }
+        }
+        Ok(Some(end))
+      }
+      b'$' | b'=' => {
+        let length: isize =
+          parse_length(line).context(RedisError::Protocol("invalid bulk length".to_owned()))?;
+        if length < 0 {
+          return Ok(Some(next));
+        }
+        let end = next
+          .checked_add(length as usize)
+          .context(RedisError::Protocol("invalid bulk length".to_owned()))?;
+        match input.get(end..end.saturating_add(2)) {
+          None => Ok(None),
+          Some(b"\r\n") => Ok(Some(end + 2)),
+          Some(_) => bail!(RedisError::Protocol(
+            "expected CRLF after bulk string".to_owned()
+          )),
+        }
+      }
+      b'+' | b'-' | b':' | b'_' | b',' | b'#' | b'(' => Ok(Some(next)),
+      _ => bail!(RedisError::Protocol(format!(
+        "expected '$', got '{}'",
+        type_byte.escape_ascii()
+      ))),
+    }
+  }
+
+  _frame_len(input, 0, 0)
+}
+
+/// Reads a newline-terminated inline command starting at `start` and splits it into arguments,
+/// returning them with the position right after the newline.
+fn read_inline_command(input: &[u8], start: usize) -> Result<(Vec<Bytes>, usize)> {
+  let rest = &input[start..];
+  let Some(newline) = rest.iter().position(|&b| b == b'\n') else {
+    bail!("unexpected end of input: newline expected after inline command");
+  };
+  let line = rest[..newline]
+    .strip_suffix(b"\r")
+    .unwrap_or(&rest[..newline]);
+  let args = split_inline_args(line).context(RedisError::Protocol(
+    "unbalanced quotes in request".to_owned(),
+  ))?;
+  Ok((args, start + newline + 1))
+}
+
+/// Splits an inline command line the way `redis-cli` does: arguments are separated by whitespace
+/// and may be wrapped in double quotes (with escape sequences) or single quotes.
+fn split_inline_args(line: &[u8]) -> Result<Vec<Bytes>> {
+  let mut args = vec![];
+  let mut iter = line.iter().copied().peekable();
+
+  loop {
+    while iter.next_if(|b| b.is_ascii_whitespace()).is_some() {}
+    let Some(&first) = iter.peek() else {
+      return Ok(args);
+    };
+
+    let mut arg = vec![];
+    match first {
+      b'"' => {
+        iter.next();
+        loop {
+          match iter.next() {
+            Some(b'"') => break,
+            Some(b'\\') => match iter.next() {
+              Some(b'x') => {
+                let hex = [iter.next(), iter.next()];
+                let [Some(high), Some(low)] = hex else {
+                  bail!("incomplete hex escape");
+                };
+                let hex = std::str::from_utf8(&[high, low])?.to_owned();
+                arg.push(u8::from_str_radix(&hex, 16).context("invalid hex escape")?);
+              }
+              Some(b'n') => arg.push(b'\n'),
+              Some(b'r') => arg.push(b'\r'),
+              Some(b't') => arg.push(b'\t'),
+              Some(b'b') => arg.push(0x08),
+              Some(b'a') => arg.push(0x07),
+              Some(escaped) => arg.push(escaped),
+              None => bail!("unterminated double quote"),
+            },
+            Some(b) => arg.push(b),
+            None => bail!("unterminated double quote"),
+          }
+        }
+      }
+      b'\'' => {
+        iter.next();
+        loop {
+          match iter.next() {
+            Some(b'\'') => break,
+            Some(b'\\') if iter.peek() == Some(&b'\'') => arg.push(iter.next().unwrap()),
+            Some(b) => arg.push(b),
+            None => bail!("unterminated single quote"),
+          }
+        }
+      }
+      _ => {
+        while let Some(b) = iter.next_if(|b| !b.is_ascii_whitespace()) {
+          arg.push(b);
+        }
       }
-    } else {
-      result.push(c);
     }
+
+    // a closing quote must be followed by a separator
+    if matches!(first, b'"' | b'\'') && iter.peek().is_some_and(|b| !b.is_ascii_whitespace()) {
+      bail!("closing quote must be followed by a space");
+    }
+    args.push(Bytes::from(arg));
+  }
+}
+
+fn read_line(input: &[u8], start: usize) -> Option<(&[u8], usize)> {
+  let rest = input.get(start..)?;
+  let pos = rest.windows(2).position(|window| window == b"\r\n")?;
+  Some((&rest[..pos], start + pos + 2))
+}
+
+fn parse_length<T>(line: &[u8]) -> Result<T>
+where
+  T: std::str::FromStr,
+  T::Err: std::error::Error + Send + Sync + 'static,
+{
+  Ok(std::str::from_utf8(line)?.parse()?)
+}
+
+#[cfg(test)]
+mod tests_tokenization {
+  use super::*;
+
+  #[test]
+  fn should_report_incomplete_frame() {
+    assert_eq!(frame_len(b"").unwrap(), None);
+    assert_eq!(frame_len(b"*2\r\n$4\r\nECHO\r\n").unwrap(), None);
+    assert_eq!(frame_len(b"*2\r\n$4\r\nECHO\r\n$3\r\nhe").unwrap(), None);
+    assert_eq!(frame_len(b"*2\r\n$4\r\nECHO\r\n$3\r\nhey\r").unwrap(), None);
+  }
+
+  #[test]
+  fn should_measure_first_complete_frame_only() {
+    let frame = b"*2\r\n$4\r\nECHO\r\n$3\r\nhey\r\n";
+    assert_eq!(frame_len(frame).unwrap(), Some(frame.len()));
+
+    let mut buffer = frame.to_vec();
+    buffer.extend_from_slice(b"*1\r\n$4\r\nPI");
+    assert_eq!(frame_len(&buffer).unwrap(), Some(frame.len()));
+  }
+
+  #[test]
+  fn should_reject_malformed_frame() {
+    assert!(frame_len(b"*x\r\n").is_err());
+    assert!(frame_len(b"$3\r\nheyy\r\n").is_err());
+    assert!(frame_len(b"*1\r\n?\r\n").is_err());
+  }
+
+  #[test]
+  fn should_reject_untrusted_lengths_and_nesting() {
+    let nested = b"*1\r\n".repeat(100_000);
+    assert!(frame_len(&nested).is_err());
+    assert_eq!(
+      frame_len(&b"*1\r\n".repeat(MAX_NESTING_DEPTH)).unwrap(),
+      None
+    );
+
+    let huge = format!("%{}\r\n", isize::MAX);
+    assert!(frame_len(huge.as_bytes()).is_err());
+    assert!(tokenize(format!("*2\r\n{huge}{huge}").as_bytes()).is_err());
+    assert!(tokenize(format!("${}\r\nx\r\n", isize::MAX).as_bytes()).is_err());
+  }
+
+  #[test]
+  fn should_read_bulk_string_by_declared_length() {
+    let tokens = tokenize(b"*1\r\n$6\r\na\r\n\xff\r\n\r\n").unwrap();
+    assert_eq!(
+      tokens,
+      vec![
+        Token::Array(1),
+        Token::BulkString(6),
+        Token::StringValue(Bytes::from_static(b"a\r\n\xff\r\n")),
+      ]
+    );
+
+    assert!(tokenize(b"$6\r\nshort\r\n").is_err());
+  }
+
+  #[test]
+  fn should_tokenize_resp3_types() {
+    let input = b"%2\r\n+first\r\n:1\r\n$6\r\nsecond\r\n,1.5\r\n~2\r\n#t\r\n_\r\n|1\r\n+ttl\r\n(123\r\n=7\r\ntxt:abc\r\n>1\r\n-ERR x\r\n";
+    assert_eq!(frame_len(input).unwrap(), Some(34));
+    assert_eq!(
+      tokenize(input).unwrap(),
+      vec![
+        Token::Map(2),
+        Token::SimpleString(Bytes::from("first")),
+        Token::Integer(1),
+        Token::BulkString(6),
+        Token::StringValue(Bytes::from("second")),
+        Token::Double(1.5),
+        Token::Set(2),
+        Token::Boolean(true),
+        Token::Null,
+        Token::Attribute(1),
+        Token::SimpleString(Bytes::from("ttl")),
+        Token::BigNumber("123".to_owned()),
+        Token::VerbatimString(7),
+        Token::StringValue(Bytes::from("txt:abc")),
+        Token::Push(1),
+        Token::Error(Bytes::from("ERR x")),
+      ]
+    );
+  }
+
+  #[test]
+  fn should_tokenize_inline_commands() {
+    let tokens = tokenize(b"set  key \"a b\\x41\\n\" 'it\\'s'\r\nPING\n\r\n").unwrap();
+    assert_eq!(
+      tokens,
+      vec![
+        Token::Array(4),
+        Token::BulkString(3),
+        Token::StringValue(Bytes::from("set")),
+        Token::BulkString(3),
+        Token::StringValue(Bytes::from("key")),
+        Token::BulkString(5),
+        Token::StringValue(Bytes::from("a bA\n")),
+        Token::BulkString(4),
+        Token::StringValue(Bytes::from("it's")),
+        Token::Array(1),
+        Token::BulkString(4),
+        Token::StringValue(Bytes::from("PING")),
+      ]
+    );
+
+    assert_eq!(frame_len(b"PING\r\n").unwrap(), Some(6));
+    assert_eq!(frame_len(b"PI").unwrap(), None);
+    assert!(tokenize(b"SET k \"unterminated\n").is_err());
+    assert!(tokenize(b"SET k \"a\"b\n").is_err());
   }
-  result
 }